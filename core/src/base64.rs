@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Base64 string encoding and decoding.
+
+use {
+    crate::bytes::ByteBuffer,
+    base64::{
+        Engine,
+        engine::{GeneralPurpose, general_purpose},
+    },
+};
+
+pub use base64::DecodeError;
+
+fn engine(url_safe: bool) -> GeneralPurpose {
+    if url_safe {
+        general_purpose::URL_SAFE
+    } else {
+        general_purpose::STANDARD
+    }
+}
+
+/// Encodes the trimmed input as padded Base64.
+pub fn encode(input: &str, url_safe: bool) -> String {
+    encode_bytes(input.trim().as_bytes(), url_safe)
+}
+
+/// Encodes bytes as padded Base64.
+pub fn encode_bytes(input: &[u8], url_safe: bool) -> String {
+    engine(url_safe).encode(input)
+}
+
+/// Decodes trimmed, padded Base64 input into the original bytes.
+pub fn decode(input: &str, url_safe: bool) -> Result<ByteBuffer, DecodeError> {
+    decode_bytes(input.as_bytes(), url_safe)
+}
+
+/// Decodes trimmed, padded Base64 held in a byte buffer.
+pub fn decode_bytes(input: &[u8], url_safe: bool) -> Result<ByteBuffer, DecodeError> {
+    engine(url_safe)
+        .decode(input.trim_ascii())
+        .map(ByteBuffer::from)
+}
+
+/// Whether `encoded` decodes to `plain` and `plain` encodes to `encoded`, so that converting
+/// either way and back gives the same text.
+///
+/// Fails for Base64 that decodes but is not written the way this encoder writes it, such as
+/// input without padding.
+pub fn is_round_trip(encoded: &str, plain: &[u8], url_safe: bool) -> bool {
+    encode_bytes(plain, url_safe) == encoded.trim()
+        && decode(encoded, url_safe).is_ok_and(|decoded| decoded.as_bytes() == plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_standard_alphabet() {
+        let encoded = encode("subjects?_d>", false);
+        assert_eq!(encoded, "c3ViamVjdHM/X2Q+");
+        assert_eq!(
+            decode(&encoded, false).unwrap().as_text(),
+            Ok("subjects?_d>")
+        );
+    }
+
+    #[test]
+    fn round_trips_url_safe_alphabet() {
+        let encoded = encode("subjects?_d>", true);
+        assert_eq!(encoded, "c3ViamVjdHM_X2Q-");
+        assert_eq!(
+            decode(&encoded, true).unwrap().as_text(),
+            Ok("subjects?_d>")
+        );
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        assert_eq!(encode("  hello\n", false), "aGVsbG8=");
+        assert_eq!(
+            decode("\taGVsbG8=\n", false).unwrap().as_text(),
+            Ok("hello")
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_alphabet() {
+        assert!(decode("c3ViamVjdHM_X2Q-", false).is_err());
+        assert!(decode("c3ViamVjdHM/X2Q+", true).is_err());
+    }
+
+    #[test]
+    fn keeps_binary_data() {
+        let bytes = [0x1f, 0x8b, 0x00, 0xff, 0x0a];
+        assert_eq!(
+            decode(&encode_bytes(&bytes, false), false)
+                .unwrap()
+                .as_bytes(),
+            bytes
+        );
+        assert!(decode("/w==", false).unwrap().as_text().is_err());
+    }
+
+    #[test]
+    fn checks_round_trips() {
+        assert!(is_round_trip("aGVsbG8=\n", b"hello", false));
+        assert!(!is_round_trip("aGVsbG8=", b"hello\n", false));
+        assert!(!is_round_trip("c3ViamVjdHM/X2Q+", b"subjects?_d>", true));
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Byte buffers produced by decoders, with text renderings for binary data.
+
+use std::fmt::Write;
+
+/// Decoded or decompressed data, which is not necessarily valid UTF-8.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ByteBuffer(Vec<u8>);
+
+impl ByteBuffer {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The buffer as a string, when it holds valid UTF-8.
+    pub fn as_text(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.0)
+    }
+
+    /// The rendering that shows the buffer best, text unless it holds binary data.
+    pub fn preferred_rendering(&self) -> Rendering {
+        match self.as_text() {
+            Ok(text) if !text.contains(is_binary_control) => Rendering::Text,
+            _ => Rendering::HexDump,
+        }
+    }
+
+    pub fn render(&self, rendering: Rendering) -> String {
+        match rendering {
+            Rendering::Text => String::from_utf8_lossy(&self.0).into_owned(),
+            Rendering::HexDump => hex_dump(&self.0),
+            Rendering::Escaped => escape(&self.0),
+        }
+    }
+}
+
+impl From<Vec<u8>> for ByteBuffer {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<String> for ByteBuffer {
+    fn from(text: String) -> Self {
+        Self(text.into_bytes())
+    }
+}
+
+impl From<&str> for ByteBuffer {
+    fn from(text: &str) -> Self {
+        Self(text.as_bytes().to_vec())
+    }
+}
+
+/// Control characters other than whitespace, which only appear in binary data.
+fn is_binary_control(c: char) -> bool {
+    c.is_control() && !c.is_whitespace()
+}
+
+/// Ways of showing a buffer in a text editor.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rendering {
+    /// UTF-8 text, replacing invalid sequences.
+    #[default]
+    Text,
+    /// Offsets, hexadecimal bytes and printable ASCII, as `hexdump -C` prints them.
+    HexDump,
+    /// Text with control characters and invalid bytes written as escape sequences.
+    Escaped,
+}
+
+impl Rendering {
+    pub const ALL: [Rendering; 3] = [Rendering::Text, Rendering::HexDump, Rendering::Escaped];
+}
+
+/// Formats the bytes in rows of sixteen, prefixed by their offset.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(dump, "{:08x} ", row * 16);
+        for column in 0..16 {
+            if column == 8 {
+                dump.push(' ');
+            }
+            match chunk.get(column) {
+                Some(byte) => {
+                    let _ = write!(dump, " {byte:02x}");
+                }
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str("  |");
+        dump.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        dump.push_str("|\n");
+    }
+    dump
+}
+
+/// Escapes the bytes like a double-quoted string literal, with `\xNN` for bytes that are not UTF-8.
+pub fn escape(bytes: &[u8]) -> String {
+    let mut escaped = String::new();
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\'' => escaped.push(c),
+                _ => escaped.extend(c.escape_debug()),
+            }
+        }
+        for byte in chunk.invalid() {
+            let _ = write!(escaped, "\\x{byte:02x}");
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_rows_of_sixteen_bytes() {
+        assert_eq!(
+            hex_dump(b"hello, binary world\x00\xff"),
+            "00000000  68 65 6c 6c 6f 2c 20 62  69 6e 61 72 79 20 77 6f  |hello, binary wo|\n\
+             00000010  72 6c 64 00 ff                                    |rld..|\n"
+        );
+        assert_eq!(hex_dump(b""), "");
+    }
+
+    #[test]
+    fn escapes_invalid_bytes() {
+        assert_eq!(
+            escape(b"a\tb\n\"'\xff\xfe\xc3\xbc"),
+            "a\\tb\\n\\\"'\\xff\\xfeü"
+        );
+    }
+
+    #[test]
+    fn prefers_hex_dump_for_binary_data() {
+        assert_eq!(
+            ByteBuffer::from("line\nline\n").preferred_rendering(),
+            Rendering::Text
+        );
+        assert_eq!(
+            ByteBuffer::from(vec![0x08, 0x96, 0x01]).preferred_rendering(),
+            Rendering::HexDump
+        );
+        assert_eq!(
+            ByteBuffer::from(vec![0xff]).preferred_rendering(),
+            Rendering::HexDump
+        );
+    }
+}
@@ -0,0 +1,287 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Checks of the registered claims of a JSON Web Token against the values expected of it.
+//!
+//! Unlike [`crate::jwt::verify_signature`], every claim is reported on its own, so that an
+//! expired token still shows whether its audience and issuer are the expected ones.
+
+use {
+    serde::{Deserialize, Serialize},
+    serde_json::Value,
+};
+
+/// A registered claim checked by [`validate`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Claim {
+    Exp,
+    Nbf,
+    Iat,
+    Aud,
+    Iss,
+    Sub,
+}
+
+impl Claim {
+    pub const ALL: [Claim; 6] = [
+        Claim::Exp,
+        Claim::Nbf,
+        Claim::Iat,
+        Claim::Aud,
+        Claim::Iss,
+        Claim::Sub,
+    ];
+
+    /// The name of the claim in a token.
+    pub fn name(self) -> &'static str {
+        match self {
+            Claim::Exp => "exp",
+            Claim::Nbf => "nbf",
+            Claim::Iat => "iat",
+            Claim::Aud => "aud",
+            Claim::Iss => "iss",
+            Claim::Sub => "sub",
+        }
+    }
+}
+
+/// What the claims of a token are checked against.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ClaimRules {
+    /// Seconds by which the clocks of the issuer and of this check may differ.
+    pub leeway: u64,
+    /// The audience the token must be meant for, any audience is accepted when empty.
+    pub audience: String,
+    /// The issuer the token must come from, any issuer is accepted when empty.
+    pub issuer: String,
+    /// The subject the token must be about, any subject is accepted when empty.
+    pub subject: String,
+    /// Claims the token fails without.
+    pub required: Vec<Claim>,
+}
+
+impl ClaimRules {
+    /// The value expected of `aud`, `iss` or `sub`, empty when any value is accepted.
+    pub fn expected(&self, claim: Claim) -> &str {
+        match claim {
+            Claim::Aud => &self.audience,
+            Claim::Iss => &self.issuer,
+            Claim::Sub => &self.subject,
+            Claim::Exp | Claim::Nbf | Claim::Iat => "",
+        }
+    }
+}
+
+impl Default for ClaimRules {
+    /// Requires an expiry and allows a minute of clock skew, like most verifiers do.
+    fn default() -> Self {
+        Self {
+            leeway: 60,
+            audience: String::new(),
+            issuer: String::new(),
+            subject: String::new(),
+            required: vec![Claim::Exp],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// The claim is absent but not required.
+    Skipped,
+}
+
+/// What was found in the token for a claim.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Finding {
+    Missing,
+    /// A time claim that is not a number, or a string claim that is not a string.
+    Malformed,
+    /// A time claim, with the seconds from the time of the check to it, negative when past.
+    Time {
+        timestamp: i64,
+        from_now: i64,
+    },
+    /// The values of `aud`, `iss` or `sub`, `aud` being the only claim with several.
+    Values(Vec<String>),
+}
+
+/// The outcome of checking a single claim.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimCheck {
+    pub claim: Claim,
+    pub verdict: Verdict,
+    pub finding: Finding,
+    pub required: bool,
+}
+
+/// Checks every registered claim of `claims` at the Unix time `now`, in the order of
+/// [`Claim::ALL`].
+pub fn validate(claims: &Value, rules: &ClaimRules, now: i64) -> Vec<ClaimCheck> {
+    Claim::ALL
+        .into_iter()
+        .map(|claim| {
+            let required = rules.required.contains(&claim);
+            let finding = match claims.get(claim.name()) {
+                None => Finding::Missing,
+                Some(value) => find(claim, value, now),
+            };
+            let verdict = match &finding {
+                Finding::Missing if required => Verdict::Fail,
+                Finding::Missing => Verdict::Skipped,
+                Finding::Malformed => Verdict::Fail,
+                Finding::Time { timestamp, .. } => {
+                    let leeway = i64::try_from(rules.leeway).unwrap_or(i64::MAX);
+                    let passes = match claim {
+                        Claim::Exp => timestamp.saturating_add(leeway) > now,
+                        // Neither usable nor issued in the future.
+                        _ => *timestamp <= now.saturating_add(leeway),
+                    };
+                    if passes { Verdict::Pass } else { Verdict::Fail }
+                }
+                Finding::Values(values) => {
+                    let expected = rules.expected(claim);
+                    if expected.is_empty() || values.iter().any(|value| value == expected) {
+                        Verdict::Pass
+                    } else {
+                        Verdict::Fail
+                    }
+                }
+            };
+            ClaimCheck {
+                claim,
+                verdict,
+                finding,
+                required,
+            }
+        })
+        .collect()
+}
+
+/// Whether no claim failed its check.
+pub fn is_valid(checks: &[ClaimCheck]) -> bool {
+    checks.iter().all(|check| check.verdict != Verdict::Fail)
+}
+
+fn find(claim: Claim, value: &Value, now: i64) -> Finding {
+    match claim {
+        // NumericDate allows fractions of a second, which are dropped.
+        Claim::Exp | Claim::Nbf | Claim::Iat => value
+            .as_i64()
+            .or_else(|| value.as_f64().map(|timestamp| timestamp as i64))
+            .map_or(Finding::Malformed, |timestamp| Finding::Time {
+                timestamp,
+                from_now: timestamp.saturating_sub(now),
+            }),
+        Claim::Aud => match value {
+            Value::String(audience) => Finding::Values(vec![audience.clone()]),
+            Value::Array(audiences) => audiences
+                .iter()
+                .map(|audience| audience.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .map_or(Finding::Malformed, Finding::Values),
+            _ => Finding::Malformed,
+        },
+        Claim::Iss | Claim::Sub => value.as_str().map_or(Finding::Malformed, |text| {
+            Finding::Values(vec![text.to_string()])
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn verdicts(claims: &Value, rules: &ClaimRules) -> Vec<Verdict> {
+        validate(claims, rules, NOW)
+            .into_iter()
+            .map(|check| check.verdict)
+            .collect()
+    }
+
+    #[test]
+    fn checks_times_with_leeway() {
+        let rules = ClaimRules::default();
+        let claims = json!({"exp": NOW - 30, "nbf": NOW + 30, "iat": NOW - 3600});
+        let checks = validate(&claims, &rules, NOW);
+        assert_eq!(checks[0].verdict, Verdict::Pass);
+        assert_eq!(
+            checks[0].finding,
+            Finding::Time {
+                timestamp: NOW - 30,
+                from_now: -30
+            }
+        );
+        assert_eq!(checks[1].verdict, Verdict::Pass);
+        assert_eq!(checks[2].verdict, Verdict::Pass);
+
+        let strict = ClaimRules {
+            leeway: 0,
+            ..ClaimRules::default()
+        };
+        assert_eq!(
+            verdicts(&claims, &strict)[..3],
+            [Verdict::Fail, Verdict::Fail, Verdict::Pass]
+        );
+        assert_eq!(
+            verdicts(
+                &json!({"exp": NOW, "iat": NOW + 120}),
+                &ClaimRules::default()
+            )[..3],
+            [Verdict::Pass, Verdict::Skipped, Verdict::Fail]
+        );
+    }
+
+    #[test]
+    fn compares_expected_values() {
+        let rules = ClaimRules {
+            audience: "api".to_string(),
+            issuer: "https://issuer.example".to_string(),
+            ..ClaimRules::default()
+        };
+        let claims = json!({
+            "exp": NOW + 3600,
+            "aud": ["web", "api"],
+            "iss": "https://other.example",
+            "sub": "user",
+        });
+        assert_eq!(
+            verdicts(&claims, &rules),
+            [
+                Verdict::Pass,
+                Verdict::Skipped,
+                Verdict::Skipped,
+                Verdict::Pass,
+                Verdict::Fail,
+                Verdict::Pass
+            ]
+        );
+        assert!(!is_valid(&validate(&claims, &rules, NOW)));
+    }
+
+    #[test]
+    fn fails_missing_required_and_malformed_claims() {
+        let rules = ClaimRules {
+            required: vec![Claim::Exp, Claim::Sub],
+            ..ClaimRules::default()
+        };
+        let checks = validate(&json!({"aud": 5, "iat": "yesterday"}), &rules, NOW);
+        assert_eq!(checks[0].finding, Finding::Missing);
+        assert_eq!(checks[0].verdict, Verdict::Fail);
+        assert_eq!(checks[2].finding, Finding::Malformed);
+        assert_eq!(checks[3].finding, Finding::Malformed);
+        assert_eq!(checks[5].verdict, Verdict::Fail);
+        assert!(checks[5].required);
+
+        let none_required = ClaimRules {
+            required: Vec::new(),
+            ..ClaimRules::default()
+        };
+        assert!(is_valid(&validate(&json!({}), &none_required, NOW)));
+    }
+}
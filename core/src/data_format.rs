@@ -0,0 +1,464 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Conversion and formatting between JSON, YAML, XML and TOML documents.
+
+use {
+    serde::{Deserialize, Serialize},
+    std::{fmt, path::Path},
+};
+
+/// The indent widths offered for formatted output.
+pub const INDENTS: [usize; 3] = [2, 4, 8];
+
+/// The document formats that can be converted between.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    #[default]
+    Json,
+    Yaml,
+    Xml,
+    Toml,
+}
+
+impl DataFormat {
+    pub const ALL: [DataFormat; 4] = [
+        DataFormat::Json,
+        DataFormat::Yaml,
+        DataFormat::Xml,
+        DataFormat::Toml,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DataFormat::Json => "JSON",
+            DataFormat::Yaml => "YAML",
+            DataFormat::Xml => "XML",
+            DataFormat::Toml => "TOML",
+        }
+    }
+
+    /// TOML output always uses the pretty printer's fixed layout.
+    pub fn supports_indent(self) -> bool {
+        self != DataFormat::Toml
+    }
+
+    /// The usual file extension of documents in this format, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            DataFormat::Json => "json",
+            DataFormat::Yaml => "yaml",
+            DataFormat::Xml => "xml",
+            DataFormat::Toml => "toml",
+        }
+    }
+
+    /// The format of a file, guessed from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(DataFormat::Json),
+            "yaml" | "yml" => Some(DataFormat::Yaml),
+            "xml" => Some(DataFormat::Xml),
+            "toml" => Some(DataFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A 1-based line and column within a document, with the column counted in characters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TextPosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TextPosition {
+    /// Locates a byte offset, clamping offsets past the end of the input to its last character.
+    pub fn from_offset(input: &str, offset: usize) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for TextPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConversionError {
+    #[error("Invalid JSON: {source}")]
+    Json {
+        source: serde_json::Error,
+        position: Option<TextPosition>,
+    },
+    #[error("Invalid YAML: {source}")]
+    Yaml {
+        source: serde_saphyr::Error,
+        position: Option<TextPosition>,
+    },
+    #[error("Invalid XML{}: {source}", at(position))]
+    Xml {
+        source: quick_xml::DeError,
+        position: Option<TextPosition>,
+    },
+    #[error("Invalid TOML{}: {}", at(position), source.message())]
+    Toml {
+        source: toml::de::Error,
+        position: Option<TextPosition>,
+    },
+    #[error("Cannot convert to {format}: {message}")]
+    Serialize { format: DataFormat, message: String },
+}
+
+fn at(position: &Option<TextPosition>) -> String {
+    position
+        .map(|position| format!(" at {}", position))
+        .unwrap_or_default()
+}
+
+impl ConversionError {
+    /// The format that failed to parse or serialize.
+    pub fn format(&self) -> DataFormat {
+        match self {
+            ConversionError::Json { .. } => DataFormat::Json,
+            ConversionError::Yaml { .. } => DataFormat::Yaml,
+            ConversionError::Xml { .. } => DataFormat::Xml,
+            ConversionError::Toml { .. } => DataFormat::Toml,
+            ConversionError::Serialize { format, .. } => *format,
+        }
+    }
+
+    /// Where in the input a parse error was found, when the parser reports it.
+    pub fn position(&self) -> Option<TextPosition> {
+        match self {
+            ConversionError::Json { position, .. }
+            | ConversionError::Yaml { position, .. }
+            | ConversionError::Xml { position, .. }
+            | ConversionError::Toml { position, .. } => *position,
+            ConversionError::Serialize { .. } => None,
+        }
+    }
+}
+
+/// Parses a document in the given format into a format-independent value.
+pub fn parse(input: &str, format: DataFormat) -> Result<serde_json::Value, ConversionError> {
+    match format {
+        DataFormat::Json => serde_json::from_str(input).map_err(|source| {
+            // serde_json reports line 0 for errors that are not tied to the input text.
+            let position = (source.line() > 0).then(|| TextPosition {
+                line: source.line(),
+                column: source.column().max(1),
+            });
+            ConversionError::Json { source, position }
+        }),
+        DataFormat::Yaml => serde_saphyr::from_str(input).map_err(|source| {
+            let position = source.location().map(|location| TextPosition {
+                line: location.line() as usize,
+                column: location.column() as usize,
+            });
+            ConversionError::Yaml { source, position }
+        }),
+        DataFormat::Xml => quick_xml::de::from_str(input).map_err(|source| {
+            let position = xml_error_position(input, &source);
+            ConversionError::Xml { source, position }
+        }),
+        DataFormat::Toml => toml::from_str(input).map_err(|source| {
+            let position = source
+                .span()
+                .map(|span| TextPosition::from_offset(input, span.start));
+            ConversionError::Toml { source, position }
+        }),
+    }
+}
+
+/// The deserializer does not expose its reader, so malformed markup is located by reading the
+/// input again, and documents that end too early are reported at their end.
+fn xml_error_position(input: &str, error: &quick_xml::DeError) -> Option<TextPosition> {
+    let mut reader = quick_xml::Reader::from_str(input);
+    loop {
+        match reader.read_event() {
+            Ok(quick_xml::events::Event::Eof) => break,
+            Ok(_) => {}
+            Err(_) => {
+                return Some(TextPosition::from_offset(
+                    input,
+                    reader.error_position() as usize,
+                ));
+            }
+        }
+    }
+    matches!(
+        error,
+        quick_xml::DeError::UnexpectedEof
+            | quick_xml::DeError::InvalidXml(quick_xml::Error::IllFormed(
+                quick_xml::errors::IllFormedError::MissingEndTag(_)
+            ))
+    )
+    .then(|| TextPosition::from_offset(input, input.len()))
+}
+
+/// Serializes a value in the given format, indenting nested levels by `indent` spaces.
+pub fn serialize(
+    value: &serde_json::Value,
+    format: DataFormat,
+    indent: usize,
+) -> Result<String, ConversionError> {
+    let serialize_error = |message: String| ConversionError::Serialize { format, message };
+    let mut output = String::new();
+    match format {
+        DataFormat::Json => {
+            let mut buf = vec![];
+            let indent = " ".repeat(indent);
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            let mut json_serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+            value
+                .serialize(&mut json_serializer)
+                .map_err(|err| serialize_error(err.to_string()))?;
+            output = String::from_utf8(buf).expect("serde_json only writes UTF-8");
+        }
+        DataFormat::Yaml => {
+            let mut yaml_serializer =
+                serde_saphyr::ser::YamlSerializer::with_indent(&mut output, indent);
+            value
+                .serialize(&mut yaml_serializer)
+                .map_err(|err| serialize_error(err.to_string()))?;
+        }
+        DataFormat::Xml => {
+            let mut xml_serializer =
+                quick_xml::se::Serializer::with_root(&mut output, Some("root"))
+                    .expect("`root` is a valid XML name");
+            xml_serializer.indent(' ', indent);
+            xml_serializer
+                .empty_element_handling(quick_xml::se::EmptyElementHandling::SelfClosedWithSpace);
+            value
+                .serialize(xml_serializer)
+                .map_err(|err| serialize_error(err.to_string()))?;
+        }
+        DataFormat::Toml => {
+            output =
+                toml::to_string_pretty(value).map_err(|err| serialize_error(err.to_string()))?
+        }
+    }
+    Ok(output)
+}
+
+/// Whether a document and its conversion hold the same data, so that converting the output back
+/// loses nothing.
+///
+/// Fails when the output format cannot represent every value, e.g. XML turns numbers into text.
+pub fn is_round_trip(
+    input: &str,
+    input_format: DataFormat,
+    output: &str,
+    output_format: DataFormat,
+) -> bool {
+    match (parse(input, input_format), parse(output, output_format)) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => false,
+    }
+}
+
+/// Converts a document from one format to another, also used to reformat within one format.
+pub fn convert(
+    input: &str,
+    input_format: DataFormat,
+    output_format: DataFormat,
+    indent: usize,
+) -> Result<String, ConversionError> {
+    serialize(&parse(input, input_format)?, output_format, indent)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn document() -> serde_json::Value {
+        json!({
+            "name": "dev-toolbox",
+            "version": "2.0.0",
+            "package": {
+                "license": "GPL-3.0",
+                "edition": "2024"
+            }
+        })
+    }
+
+    /// quick-xml reads the text of an element as `{"$text": ...}`, so XML does not read back to
+    /// the shape it was written from.
+    fn reads_back(format: DataFormat) -> bool {
+        format != DataFormat::Xml
+    }
+
+    #[test]
+    fn round_trips_through_every_format() {
+        for format in DataFormat::ALL.into_iter().filter(|format| reads_back(*format)) {
+            for indent in INDENTS {
+                let serialized = serialize(&document(), format, indent).unwrap();
+                assert_eq!(
+                    parse(&serialized, format).unwrap(),
+                    document(),
+                    "{format} with indent {indent}:\n{serialized}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn converts_between_every_pair_of_formats() {
+        for input_format in DataFormat::ALL {
+            let input = serialize(&document(), input_format, 2).unwrap();
+            let value = parse(&input, input_format).unwrap();
+            for output_format in DataFormat::ALL {
+                let output = convert(&input, input_format, output_format, 4).unwrap();
+                if reads_back(output_format) {
+                    assert_eq!(
+                        parse(&output, output_format).unwrap(),
+                        value,
+                        "{input_format} to {output_format}:\n{output}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reads_the_text_of_xml_elements_as_text_fields() {
+        let xml = "<root><name>dev-toolbox</name><version>2.0.0</version></root>";
+        assert_eq!(
+            parse(xml, DataFormat::Xml).unwrap(),
+            json!({"name": {"$text": "dev-toolbox"}, "version": {"$text": "2.0.0"}})
+        );
+    }
+
+    #[test]
+    fn checks_round_trips() {
+        let input = r#"{"count": 3, "name": "a"}"#;
+        let yaml = convert(input, DataFormat::Json, DataFormat::Yaml, 2).unwrap();
+        assert!(is_round_trip(input, DataFormat::Json, &yaml, DataFormat::Yaml));
+        let xml = convert(input, DataFormat::Json, DataFormat::Xml, 2).unwrap();
+        assert!(!is_round_trip(input, DataFormat::Json, &xml, DataFormat::Xml));
+        assert!(!is_round_trip(input, DataFormat::Json, "{", DataFormat::Json));
+    }
+
+    #[test]
+    fn preserves_typed_values_outside_xml() {
+        let value = json!({ "count": 3, "ratio": 0.5, "enabled": true, "tags": ["a", "b"] });
+        for format in [DataFormat::Json, DataFormat::Yaml, DataFormat::Toml] {
+            let serialized = serialize(&value, format, 2).unwrap();
+            assert_eq!(parse(&serialized, format).unwrap(), value, "{format}");
+        }
+    }
+
+    #[test]
+    fn formats_json_with_requested_indent() {
+        assert_eq!(
+            convert(r#"{"a":[1]}"#, DataFormat::Json, DataFormat::Json, 4).unwrap(),
+            "{\n    \"a\": [\n        1\n    ]\n}"
+        );
+    }
+
+    #[test]
+    fn wraps_xml_in_root_element() {
+        let output = convert(r#"{"a":"b"}"#, DataFormat::Json, DataFormat::Xml, 2).unwrap();
+        assert_eq!(output, "<root>\n  <a>b</a>\n</root>");
+    }
+
+    #[test]
+    fn guesses_format_from_extension() {
+        for format in DataFormat::ALL {
+            let file_name = format!("document.{}", format.extension());
+            assert_eq!(DataFormat::from_path(Path::new(&file_name)), Some(format));
+        }
+        assert_eq!(
+            DataFormat::from_path(Path::new("/etc/app/config.YML")),
+            Some(DataFormat::Yaml)
+        );
+        assert_eq!(DataFormat::from_path(Path::new("notes.txt")), None);
+        assert_eq!(DataFormat::from_path(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn reports_parse_errors_by_format() {
+        assert!(matches!(
+            parse("{", DataFormat::Json),
+            Err(ConversionError::Json { .. })
+        ));
+        assert!(matches!(
+            parse("a: [", DataFormat::Yaml),
+            Err(ConversionError::Yaml { .. })
+        ));
+        assert!(matches!(
+            parse("<a>", DataFormat::Xml),
+            Err(ConversionError::Xml { .. })
+        ));
+        assert!(matches!(
+            parse("a = ", DataFormat::Toml),
+            Err(ConversionError::Toml { .. })
+        ));
+    }
+
+    #[test]
+    fn locates_parse_errors() {
+        let cases = [
+            (DataFormat::Json, "{\n  \"a\": 1,\n  \"b\" 2\n}", 3, 7),
+            (DataFormat::Yaml, "a: 1\nb: [1, 2\n", 2, 4),
+            (DataFormat::Xml, "<a>\n  <b></c>\n</a>", 2, 6),
+            (DataFormat::Toml, "a = 1\nb = = 2\n", 2, 5),
+        ];
+        for (format, input, line, column) in cases {
+            let err = parse(input, format).unwrap_err();
+            assert_eq!(err.format(), format);
+            assert_eq!(
+                err.position(),
+                Some(TextPosition { line, column }),
+                "{format}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn locates_unterminated_xml_at_the_end() {
+        let err = parse("<a>\n<b>x</b>", DataFormat::Xml).unwrap_err();
+        assert_eq!(err.position(), Some(TextPosition { line: 2, column: 9 }));
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(
+            TextPosition::from_offset("é\nàb", 5),
+            TextPosition { line: 2, column: 2 }
+        );
+        assert_eq!(
+            TextPosition::from_offset("ab", 10),
+            TextPosition { line: 1, column: 3 }
+        );
+    }
+
+    #[test]
+    fn reports_values_toml_cannot_represent() {
+        assert!(matches!(
+            convert("[1, 2]", DataFormat::Json, DataFormat::Toml, 2),
+            Err(ConversionError::Serialize {
+                format: DataFormat::Toml,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,620 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Line diffs highlighting the changes within lines, and structural diffs of parsed documents
+//! that ignore the order of keys.
+
+use {
+    serde_json::Value,
+    std::{
+        collections::BTreeSet,
+        iter,
+        ops::{Index, IndexMut},
+    },
+};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiffOptions {
+    /// Compares lines with all of their whitespace removed.
+    pub ignore_whitespace: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineKind {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+impl LineKind {
+    /// The prefix of the line in a unified diff.
+    pub fn marker(self) -> char {
+        match self {
+            LineKind::Unchanged => ' ',
+            LineKind::Removed => '-',
+            LineKind::Added => '+',
+        }
+    }
+}
+
+/// A run of a line, changed when it is missing from the line it was paired with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    pub text: String,
+    pub changed: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    /// The 1-based number of the line in the original text, `None` for added lines.
+    pub old_number: Option<usize>,
+    /// The 1-based number of the line in the changed text, `None` for removed lines.
+    pub new_number: Option<usize>,
+    pub segments: Vec<Segment>,
+}
+
+impl DiffLine {
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+}
+
+/// Compares two texts line by line.
+///
+/// Removed lines are followed by the added lines that replace them, and each removed line is
+/// paired with an added one to highlight the words that changed between them. Unchanged lines
+/// hold the text of the changed side, which differs in whitespace when it is ignored.
+pub fn diff_lines(old: &str, new: &str, options: DiffOptions) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let old_keys: Vec<String> = old_lines.iter().map(|line| options.key(line)).collect();
+    let new_keys: Vec<String> = new_lines.iter().map(|line| options.key(line)).collect();
+    let edits = shortest_edit(&old_keys, &new_keys);
+
+    let mut lines = Vec::with_capacity(edits.len());
+    let (mut old_index, mut new_index) = (0, 0);
+    let mut edit_index = 0;
+    while edit_index < edits.len() {
+        if edits[edit_index] == Edit::Equal {
+            lines.push(DiffLine {
+                kind: LineKind::Unchanged,
+                old_number: Some(old_index + 1),
+                new_number: Some(new_index + 1),
+                segments: vec![Segment::new(new_lines[new_index], false)],
+            });
+            old_index += 1;
+            new_index += 1;
+            edit_index += 1;
+            continue;
+        }
+
+        // A run of removed and added lines between two unchanged ones.
+        let end = edits[edit_index..]
+            .iter()
+            .position(|edit| *edit == Edit::Equal)
+            .map_or(edits.len(), |position| edit_index + position);
+        let removed = edits[edit_index..end]
+            .iter()
+            .filter(|edit| **edit == Edit::Delete)
+            .count();
+        let added = end - edit_index - removed;
+
+        let mut added_lines = Vec::with_capacity(added);
+        for offset in 0..removed.max(added) {
+            let old_line = (offset < removed).then(|| old_lines[old_index + offset]);
+            let new_line = (offset < added).then(|| new_lines[new_index + offset]);
+            let (old_segments, new_segments) = match (old_line, new_line) {
+                (Some(old_line), Some(new_line)) => diff_words(old_line, new_line, options),
+                (old_line, new_line) => (
+                    old_line
+                        .map(|line| vec![Segment::new(line, false)])
+                        .unwrap_or_default(),
+                    new_line
+                        .map(|line| vec![Segment::new(line, false)])
+                        .unwrap_or_default(),
+                ),
+            };
+            if old_line.is_some() {
+                lines.push(DiffLine {
+                    kind: LineKind::Removed,
+                    old_number: Some(old_index + offset + 1),
+                    new_number: None,
+                    segments: old_segments,
+                });
+            }
+            if new_line.is_some() {
+                added_lines.push(DiffLine {
+                    kind: LineKind::Added,
+                    old_number: None,
+                    new_number: Some(new_index + offset + 1),
+                    segments: new_segments,
+                });
+            }
+        }
+        lines.append(&mut added_lines);
+        old_index += removed;
+        new_index += added;
+        edit_index = end;
+    }
+    lines
+}
+
+/// Whether no line was removed or added.
+pub fn is_unchanged(lines: &[DiffLine]) -> bool {
+    lines.iter().all(|line| line.kind == LineKind::Unchanged)
+}
+
+/// The lines prefixed with `-`, `+` or a space, as in a unified diff without hunk headers.
+pub fn unified(lines: &[DiffLine]) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{} {}\n", line.kind.marker(), line.text()))
+        .collect()
+}
+
+impl DiffOptions {
+    /// What a line or word is compared by.
+    fn key(&self, text: &str) -> String {
+        if self.ignore_whitespace {
+            text.split_whitespace().collect()
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+impl Segment {
+    fn new(text: &str, changed: bool) -> Self {
+        Self {
+            text: text.to_string(),
+            changed,
+        }
+    }
+}
+
+/// Highlights the words of two paired lines that the other line lacks.
+fn diff_words(old: &str, new: &str, options: DiffOptions) -> (Vec<Segment>, Vec<Segment>) {
+    let old_words = words(old);
+    let new_words = words(new);
+    let key = |word: &&str| {
+        if options.ignore_whitespace && word.trim().is_empty() {
+            String::new()
+        } else {
+            word.to_string()
+        }
+    };
+    let old_keys: Vec<String> = old_words.iter().map(key).collect();
+    let new_keys: Vec<String> = new_words.iter().map(key).collect();
+
+    let (mut old_segments, mut new_segments) = (Vec::new(), Vec::new());
+    let (mut old_words, mut new_words) = (old_words.into_iter(), new_words.into_iter());
+    for edit in shortest_edit(&old_keys, &new_keys) {
+        match edit {
+            Edit::Equal => {
+                push_word(&mut old_segments, old_words.next().unwrap(), false);
+                push_word(&mut new_segments, new_words.next().unwrap(), false);
+            }
+            Edit::Delete => push_word(&mut old_segments, old_words.next().unwrap(), true),
+            Edit::Insert => push_word(&mut new_segments, new_words.next().unwrap(), true),
+        }
+    }
+    (old_segments, new_segments)
+}
+
+/// Splits a line into runs of word characters, runs of whitespace and single other characters.
+fn words(line: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            1
+        } else if c.is_whitespace() {
+            2
+        } else {
+            3
+        }
+    };
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        let end = chars.peek().map_or(line.len(), |(index, _)| *index);
+        let ends_word = match chars.peek() {
+            Some((_, next)) => class(c) == 3 || class(*next) != class(c),
+            None => true,
+        };
+        if ends_word {
+            words.push(&line[start..end]);
+            start = end;
+        }
+    }
+    words
+}
+
+/// Appends a word to the last segment when it is changed alike.
+fn push_word(segments: &mut Vec<Segment>, word: &str, changed: bool) {
+    match segments.last_mut() {
+        Some(segment) if segment.changed == changed => segment.text.push_str(word),
+        _ => segments.push(Segment::new(word, changed)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Edit {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// The edits turning `old` into `new` with the fewest deletions and insertions.
+///
+/// Uses the linear space variant of Myers' algorithm, deletions coming before insertions in each
+/// run of changes.
+fn shortest_edit<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    let mut forward = Furthest::new(old.len(), new.len());
+    let mut backward = Furthest::new(old.len(), new.len());
+    divide(old, new, &mut forward, &mut backward, &mut edits);
+    for run in edits.split_mut(|edit| *edit == Edit::Equal) {
+        run.sort_by_key(|edit| *edit == Edit::Insert);
+    }
+    edits
+}
+
+/// The furthest `x` reached on each diagonal `k = x - y` of the edit graph, or -1 on the
+/// diagonals that were not reached yet.
+struct Furthest {
+    offset: isize,
+    x: Vec<isize>,
+}
+
+impl Furthest {
+    fn new(old_len: usize, new_len: usize) -> Self {
+        // The diagonals of the graph and one more on each side.
+        Self {
+            offset: new_len as isize + 1,
+            x: vec![-1; old_len + new_len + 3],
+        }
+    }
+
+    fn reset(&mut self, n: isize, m: isize) {
+        let range = (self.offset - m - 1) as usize..=(self.offset + n + 1) as usize;
+        self.x[range].fill(-1);
+    }
+}
+
+impl Index<isize> for Furthest {
+    type Output = isize;
+
+    fn index(&self, k: isize) -> &isize {
+        &self.x[(self.offset + k) as usize]
+    }
+}
+
+impl IndexMut<isize> for Furthest {
+    fn index_mut(&mut self, k: isize) -> &mut isize {
+        &mut self.x[(self.offset + k) as usize]
+    }
+}
+
+/// Appends the edits between `old` and `new`, splitting them where a shortest edit path crosses
+/// its middle so that only the furthest reached points of two searches are kept.
+fn divide<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    forward: &mut Furthest,
+    backward: &mut Furthest,
+    edits: &mut Vec<Edit>,
+) {
+    let prefix = iter::zip(old, new).take_while(|(a, b)| a == b).count();
+    let suffix = iter::zip(old[prefix..].iter().rev(), new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    edits.extend(iter::repeat_n(Edit::Equal, prefix));
+    if old_middle.is_empty() || new_middle.is_empty() {
+        edits.extend(iter::repeat_n(Edit::Delete, old_middle.len()));
+        edits.extend(iter::repeat_n(Edit::Insert, new_middle.len()));
+    } else {
+        let (x, y) = middle_snake(old_middle, new_middle, forward, backward);
+        divide(&old_middle[..x], &new_middle[..y], forward, backward, edits);
+        divide(&old_middle[x..], &new_middle[y..], forward, backward, edits);
+    }
+    edits.extend(iter::repeat_n(Edit::Equal, suffix));
+}
+
+/// A point of a shortest edit path between two non-empty sequences that differ in their first
+/// and last items, found by searching from both corners of the edit graph until they overlap.
+///
+/// The backward search walks the graph of the reversed sequences, in which the diagonal `k` of
+/// the forward search is `n - m - k`.
+fn middle_snake<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    forward: &mut Furthest,
+    backward: &mut Furthest,
+) -> (usize, usize) {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    forward.reset(n, m);
+    backward.reset(n, m);
+
+    for d in 0..=(n + m + 1) / 2 {
+        // The diagonals reached with `d` edits that stay within the graph.
+        let low = if d > m { -m + (d - m) % 2 } else { -d };
+        let high = if d > n { n - (d - n) % 2 } else { d };
+
+        for k in (low..=high).step_by(2) {
+            let start_x = next_x(forward, d, k);
+            let (mut x, mut y) = (start_x, start_x - k);
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[k] = x;
+            if odd && (delta - k).abs() < d && x + backward[delta - k] >= n {
+                return (start_x as usize, (start_x - k) as usize);
+            }
+        }
+
+        for k in (low..=high).step_by(2) {
+            let start_x = next_x(backward, d, k);
+            let (mut x, mut y) = (start_x, start_x - k);
+            while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[k] = x;
+            if !odd && (delta - k).abs() <= d && x + forward[delta - k] >= n {
+                return ((n - x) as usize, (m - y) as usize);
+            }
+        }
+    }
+    unreachable!("the searches overlap once they have made half of the edits")
+}
+
+/// Where an edit path on diagonal `k` starts with `d` edits: after a deletion from diagonal
+/// `k - 1` or an insertion from diagonal `k + 1`, whichever reached further.
+fn next_x(furthest: &Furthest, d: isize, k: isize) -> isize {
+    if d == 0 {
+        0
+    } else if furthest[k - 1] < furthest[k + 1] {
+        furthest[k + 1]
+    } else {
+        furthest[k - 1] + 1
+    }
+}
+
+/// A difference between two documents at the path of a value, such as `$.items[0].name`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValueChange {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl ValueChange {
+    pub fn path(&self) -> &str {
+        match self {
+            ValueChange::Added { path, .. }
+            | ValueChange::Removed { path, .. }
+            | ValueChange::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// Compares two parsed documents by structure.
+///
+/// Objects are compared key by key whatever their order, arrays item by item, and numbers by
+/// value, so that `1` and `1.0` are equal.
+pub fn diff_values(old: &Value, new: &Value) -> Vec<ValueChange> {
+    let mut changes = Vec::new();
+    collect_changes("$".to_string(), old, new, &mut changes);
+    changes
+}
+
+/// The changes one per line, removed values prefixed with `-`, added ones with `+` and changed
+/// ones with `~`.
+pub fn changes_text(changes: &[ValueChange]) -> String {
+    changes
+        .iter()
+        .map(|change| match change {
+            ValueChange::Added { path, value } => format!("+ {path}: {value}\n"),
+            ValueChange::Removed { path, value } => format!("- {path}: {value}\n"),
+            ValueChange::Changed { path, old, new } => format!("~ {path}: {old} → {new}\n"),
+        })
+        .collect()
+}
+
+fn collect_changes(path: String, old: &Value, new: &Value, changes: &mut Vec<ValueChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let path = key_path(&path, key);
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => collect_changes(path, old, new, changes),
+                    (Some(old), None) => changes.push(ValueChange::Removed {
+                        path,
+                        value: old.clone(),
+                    }),
+                    (None, Some(new)) => changes.push(ValueChange::Added {
+                        path,
+                        value: new.clone(),
+                    }),
+                    (None, None) => unreachable!("keys come from either object"),
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let path = format!("{path}[{index}]");
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => collect_changes(path, old, new, changes),
+                    (Some(old), None) => changes.push(ValueChange::Removed {
+                        path,
+                        value: old.clone(),
+                    }),
+                    (None, Some(new)) => changes.push(ValueChange::Added {
+                        path,
+                        value: new.clone(),
+                    }),
+                    (None, None) => unreachable!("indices are below the longer length"),
+                }
+            }
+        }
+        (Value::Number(a), Value::Number(b)) if a == b || a.as_f64() == b.as_f64() => {}
+        (old, new) if old == new => {}
+        (old, new) => changes.push(ValueChange::Changed {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+/// Appends a key to a path, quoting it unless it is an identifier.
+fn key_path(path: &str, key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}[{}]", Value::from(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn kinds(lines: &[DiffLine]) -> String {
+        lines.iter().map(|line| line.kind.marker()).collect()
+    }
+
+    #[test]
+    fn finds_removed_and_added_lines() {
+        let lines = diff_lines("a\nb\nc\nd\n", "a\nc\nd\ne\n", DiffOptions::default());
+        assert_eq!(kinds(&lines), " -  +");
+        assert_eq!(lines[1].text(), "b");
+        assert_eq!((lines[1].old_number, lines[1].new_number), (Some(2), None));
+        assert_eq!((lines[4].old_number, lines[4].new_number), (None, Some(4)));
+        assert_eq!(unified(&lines), "  a\n- b\n  c\n  d\n+ e\n");
+        assert!(is_unchanged(&diff_lines(
+            "x\ny",
+            "x\ny\n",
+            DiffOptions::default()
+        )));
+    }
+
+    #[test]
+    fn highlights_changed_words() {
+        let lines = diff_lines(
+            r#"  "name": "old","#,
+            r#"  "name": "new","#,
+            DiffOptions::default(),
+        );
+        assert_eq!(kinds(&lines), "-+");
+        assert_eq!(
+            lines[0].segments,
+            [
+                Segment::new(r#"  "name": ""#, false),
+                Segment::new("old", true),
+                Segment::new(r#"","#, false),
+            ]
+        );
+        assert_eq!(lines[1].segments[1], Segment::new("new", true));
+    }
+
+    #[test]
+    fn ignores_whitespace_when_asked() {
+        let old = "fn main() {\n    run();\n}";
+        let new = "fn main()  {\n\trun();\n}";
+        assert!(!is_unchanged(&diff_lines(old, new, DiffOptions::default())));
+        let lines = diff_lines(
+            old,
+            new,
+            DiffOptions {
+                ignore_whitespace: true,
+            },
+        );
+        assert!(is_unchanged(&lines));
+        assert_eq!(lines[1].text(), "\trun();");
+    }
+
+    #[test]
+    fn diffs_a_minified_document_against_its_formatted_lines() {
+        let minified = format!("[{}]", vec!["1"; 5_000].join(","));
+        let formatted = iter::once("[")
+            .chain(iter::repeat_n("  1,", 9_998))
+            .chain(["]"])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let lines = diff_lines(&minified, &formatted, DiffOptions::default());
+        assert_eq!(lines.len(), 10_001);
+        assert_eq!(lines[0].kind, LineKind::Removed);
+        assert!(lines[1..].iter().all(|line| line.kind == LineKind::Added));
+
+        // Every other line kept, so that the edits are spread over the whole text.
+        let old: Vec<String> = (0..10_000).map(|line| line.to_string()).collect();
+        let new: Vec<String> = (0..10_000)
+            .step_by(2)
+            .map(|line| line.to_string())
+            .collect();
+        let lines = diff_lines(&old.join("\n"), &new.join("\n"), DiffOptions::default());
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.kind == LineKind::Removed)
+                .count(),
+            5_000
+        );
+        assert!(lines.iter().all(|line| line.kind != LineKind::Added));
+    }
+
+    #[test]
+    fn compares_documents_regardless_of_key_order() {
+        let old = json!({"id": 1, "tags": ["a", "b"], "owner": {"name": "x"}, "my key": true});
+        let new = json!({"owner": {"name": "y"}, "tags": ["a"], "id": 1.0, "extra": null});
+        assert_eq!(
+            diff_values(&old, &new),
+            [
+                ValueChange::Added {
+                    path: "$.extra".to_string(),
+                    value: Value::Null,
+                },
+                ValueChange::Removed {
+                    path: r#"$["my key"]"#.to_string(),
+                    value: json!(true),
+                },
+                ValueChange::Changed {
+                    path: "$.owner.name".to_string(),
+                    old: json!("x"),
+                    new: json!("y"),
+                },
+                ValueChange::Removed {
+                    path: "$.tags[1]".to_string(),
+                    value: json!("b"),
+                },
+            ]
+        );
+        assert!(diff_values(&json!({"a": 1, "b": 2}), &json!({"b": 2, "a": 1})).is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Fuzzy matching of search queries against command titles.
+//!
+//! Every word of a query has to appear in the title as a case-insensitive subsequence, in any
+//! order, so that "b64 dec" finds "Base64: Decode". Matches at the start of words and runs of
+//! consecutive characters score higher.
+
+/// Points for every matched character.
+const MATCH_SCORE: u32 = 1;
+/// Extra points for a character following the previous match.
+const CONSECUTIVE_BONUS: u32 = 4;
+/// Extra points for a character starting a word of the title.
+const WORD_START_BONUS: u32 = 6;
+
+/// Scores how well `query` matches `title`, `None` when a word of the query is missing.
+///
+/// An empty query matches every title with a score of zero.
+pub fn score(query: &str, title: &str) -> Option<u32> {
+    let title: Vec<char> = title.to_lowercase().chars().collect();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .map(|word| word_score(&word.chars().collect::<Vec<_>>(), &title))
+        .sum()
+}
+
+/// Ranks titles by their score, dropping the ones that do not match.
+///
+/// Returns the indices of the matching titles, best first. Of titles with the same score the
+/// shorter one comes first, as fewer of its words were left unmatched.
+pub fn rank<'a>(query: &str, titles: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+    let mut scores: Vec<(usize, u32, usize)> = titles
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, title)| Some((index, score(query, title)?, title.len())))
+        .collect();
+    scores.sort_by_key(|&(_, score, length)| (std::cmp::Reverse(score), length));
+    scores.into_iter().map(|(index, ..)| index).collect()
+}
+
+/// The best score of a query word among the subsequences of the title starting at its first
+/// character.
+fn word_score(word: &[char], title: &[char]) -> Option<u32> {
+    let (&first, rest) = word.split_first()?;
+    title
+        .iter()
+        .enumerate()
+        .filter(|&(_, &character)| character == first)
+        .filter_map(|(start, _)| {
+            let mut score = MATCH_SCORE + character_bonus(title, start, None);
+            let mut previous = start;
+            for &character in rest {
+                let index = previous
+                    + 1
+                    + title[previous + 1..]
+                        .iter()
+                        .position(|&other| other == character)?;
+                score += MATCH_SCORE + character_bonus(title, index, Some(previous));
+                previous = index;
+            }
+            Some(score)
+        })
+        .max()
+}
+
+fn character_bonus(title: &[char], index: usize, previous: Option<usize>) -> u32 {
+    let mut bonus = 0;
+    if previous.is_some_and(|previous| previous + 1 == index) {
+        bonus += CONSECUTIVE_BONUS;
+    }
+    if index == 0 || !title[index - 1].is_alphanumeric() {
+        bonus += WORD_START_BONUS;
+    }
+    bonus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_words_in_any_order() {
+        assert!(score("decode base64", "Base64: Decode").is_some());
+        assert!(score("b64 dec", "Base64: Decode").is_some());
+        assert!(score("CONVERT yaml json", "Data Converter: YAML to JSON").is_some());
+        assert_eq!(score("", "Base64: Decode"), Some(0));
+    }
+
+    #[test]
+    fn rejects_missing_words() {
+        assert_eq!(score("base64 gzip", "Base64: Decode"), None);
+        assert_eq!(score("edoc", "Base64: Decode"), None);
+    }
+
+    #[test]
+    fn ranks_word_starts_and_runs_first() {
+        let titles = [
+            "URL Encoder/Decoder: Decode",
+            "Base64: Encode",
+            "Base64: Decode URL-Safe",
+            "Base64: Decode",
+        ];
+        assert_eq!(rank("base64 decode", titles), [3, 2]);
+        assert_eq!(rank("decode", titles), [3, 2, 0]);
+        assert_eq!(rank("b64 url", titles), [2]);
+        assert_eq!(rank("", titles), [1, 3, 2, 0]);
+    }
+}
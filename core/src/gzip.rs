@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! GZip compression to and decompression from Base64 text.
+
+use {
+    crate::bytes::ByteBuffer,
+    base64::{Engine, engine::general_purpose::STANDARD},
+    flate2::{Compression, read::GzDecoder, write::GzEncoder},
+    std::io::{self, Read, Write},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecompressError {
+    #[error("Base64 decoding error: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("GZip decompression error: {0}")]
+    Gzip(#[from] io::Error),
+}
+
+/// The first bytes of every GZip member.
+pub const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Compresses the input with GZip and returns the result encoded as standard Base64.
+pub fn compress(input: &str) -> String {
+    compress_bytes(input.as_bytes())
+}
+
+/// Compresses bytes with GZip and returns the result encoded as standard Base64.
+pub fn compress_bytes(input: &[u8]) -> String {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(input)
+        .expect("writing to a Vec cannot fail");
+    let compressed_data = encoder.finish().expect("writing to a Vec cannot fail");
+    STANDARD.encode(compressed_data)
+}
+
+/// Decodes standard Base64 input and decompresses the resulting GZip data.
+pub fn decompress(input: &str) -> Result<ByteBuffer, DecompressError> {
+    decompress_bytes(input.as_bytes())
+}
+
+/// Decompresses GZip data, decoding it from standard Base64 first unless it is raw GZip.
+pub fn decompress_bytes(input: &[u8]) -> Result<ByteBuffer, DecompressError> {
+    let decoded;
+    let compressed_data = if input.starts_with(&MAGIC) {
+        input
+    } else {
+        decoded = STANDARD.decode(input.trim_ascii())?;
+        &decoded
+    };
+    let mut decoder = GzDecoder::new(compressed_data);
+    let mut decompressed_data = Vec::new();
+    decoder.read_to_end(&mut decompressed_data)?;
+    Ok(decompressed_data.into())
+}
+
+/// Whether `compressed` decompresses to `plain`.
+///
+/// Compressors differ in the bytes they write for the same data, so unlike the encoders only
+/// decompressing is compared.
+pub fn is_round_trip(compressed: &str, plain: &[u8]) -> bool {
+    decompress(compressed).is_ok_and(|decompressed| decompressed.as_bytes() == plain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let input = "{\"hello\": \"world\"}\n".repeat(32);
+        let compressed = compress(&input);
+        assert!(compressed.starts_with("H4sI"));
+        assert_eq!(
+            decompress(&compressed).unwrap().as_text(),
+            Ok(input.as_str())
+        );
+    }
+
+    #[test]
+    fn decompresses_known_payload() {
+        // `printf hello | gzip -n | base64`
+        assert_eq!(
+            decompress("H4sIAAAAAAAAA8tIzcnJBwCGphA2BQAAAA==\n")
+                .unwrap()
+                .as_text(),
+            Ok("hello")
+        );
+    }
+
+    #[test]
+    fn keeps_binary_data() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(
+            decompress(&compress_bytes(&bytes)).unwrap().as_bytes(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn decompresses_raw_gzip() {
+        let compressed = STANDARD
+            .decode("H4sIAAAAAAAAA8tIzcnJBwCGphA2BQAAAA==")
+            .unwrap();
+        assert_eq!(
+            decompress_bytes(&compressed).unwrap().as_text(),
+            Ok("hello")
+        );
+    }
+
+    #[test]
+    fn reports_invalid_base64() {
+        assert!(matches!(
+            decompress("not base64!"),
+            Err(DecompressError::Base64(_))
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_gzip() {
+        assert!(matches!(
+            decompress("aGVsbG8="),
+            Err(DecompressError::Gzip(_))
+        ));
+    }
+
+    #[test]
+    fn checks_round_trips() {
+        assert!(is_round_trip(&compress("hello"), b"hello"));
+        assert!(!is_round_trip(&compress("hello"), b"hello\n"));
+        assert!(!is_round_trip("aGVsbG8=", b"hello"));
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Recent inputs and outputs of the tools, bounded in size so that they can be kept on disk.
+//!
+//! Pinned entries are never evicted, the oldest unpinned ones make room for new entries.
+
+use {
+    serde::{Deserialize, Serialize},
+    std::collections::HashMap,
+};
+
+/// Entries kept per tool, not counting pinned ones.
+pub const MAX_ENTRIES_PER_TOOL: usize = 50;
+/// Entries whose input and output are larger than this are not recorded.
+pub const MAX_ENTRY_SIZE: usize = 64 * 1024;
+/// The total size of the inputs and outputs of all entries.
+pub const MAX_SIZE: usize = 1024 * 1024;
+/// Conversions of a tool this many seconds apart replace each other, so that typing an input
+/// records it once.
+pub const COALESCE_SECONDS: i64 = 10;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HistoryEntry {
+    /// Identifies the tool whose input this is.
+    pub tool: String,
+    pub input: String,
+    pub output: String,
+    /// When the conversion ran, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl HistoryEntry {
+    pub fn new(tool: &str, input: String, output: String, timestamp: i64) -> Self {
+        Self {
+            tool: tool.to_string(),
+            input,
+            output,
+            timestamp,
+            pinned: false,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.input.len() + self.output.len()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct History {
+    /// Ordered from the newest to the oldest entry.
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Adds an entry at the top, returning whether the history changed.
+    ///
+    /// An entry with the same input moves to the top instead of being repeated, and an unpinned
+    /// entry of the same tool recorded moments ago is replaced. Recording the newest entry again
+    /// changes nothing.
+    pub fn record(&mut self, entry: HistoryEntry) -> bool {
+        if entry.input.trim().is_empty() || entry.size() > MAX_ENTRY_SIZE {
+            return false;
+        }
+        let mut entry = entry;
+        if let Some(index) = self
+            .entries
+            .iter()
+            .position(|other| other.tool == entry.tool && other.input == entry.input)
+        {
+            if index == 0 && self.entries[0].output == entry.output {
+                return false;
+            }
+            let previous = self.entries.remove(index);
+            entry.pinned = previous.pinned;
+        } else if let Some(index) = self
+            .entries
+            .iter()
+            .position(|other| other.tool == entry.tool)
+            && !self.entries[index].pinned
+            && entry.timestamp - self.entries[index].timestamp < COALESCE_SECONDS
+        {
+            self.entries.remove(index);
+        }
+        self.entries.insert(0, entry);
+        self.evict();
+        true
+    }
+
+    pub fn set_pinned(&mut self, index: usize, pinned: bool) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.pinned = pinned;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.entries.remove(index);
+        }
+    }
+
+    /// Removes the unpinned entries of a tool.
+    pub fn clear(&mut self, tool: &str) {
+        self.entries
+            .retain(|entry| entry.pinned || entry.tool != tool);
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(input)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("history is always serializable")
+    }
+
+    /// Drops the oldest unpinned entries until every bound holds.
+    fn evict(&mut self) {
+        let mut counts = HashMap::<&str, usize>::new();
+        let keep: Vec<bool> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                if entry.pinned {
+                    return true;
+                }
+                let count = counts.entry(&entry.tool).or_default();
+                *count += 1;
+                *count <= MAX_ENTRIES_PER_TOOL
+            })
+            .collect();
+        let mut keep = keep.into_iter();
+        self.entries.retain(|_| keep.next().unwrap_or(true));
+
+        let mut size: usize = self.entries.iter().map(HistoryEntry::size).sum();
+        while size > MAX_SIZE {
+            let Some(index) = self.entries.iter().rposition(|entry| !entry.pinned) else {
+                break;
+            };
+            size -= self.entries.remove(index).size();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tool: &str, input: &str, timestamp: i64) -> HistoryEntry {
+        HistoryEntry::new(tool, input.to_string(), format!("{input}!"), timestamp)
+    }
+
+    fn inputs(history: &History) -> Vec<&str> {
+        history
+            .entries()
+            .iter()
+            .map(|entry| entry.input.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_newest_entries_first() {
+        let mut history = History::default();
+        history.record(entry("base64", "a", 0));
+        history.record(entry("url", "b", 100));
+        history.record(entry("base64", "c", 200));
+        assert_eq!(inputs(&history), ["c", "b", "a"]);
+
+        history.record(entry("base64", "a", 300));
+        assert_eq!(inputs(&history), ["a", "c", "b"]);
+        assert_eq!(history.entries()[0].timestamp, 300);
+        assert!(!history.record(entry("base64", "a", 400)));
+    }
+
+    #[test]
+    fn coalesces_typing_into_one_entry() {
+        let mut history = History::default();
+        history.record(entry("base64", "a", 0));
+        history.record(entry("url", "x", 1));
+        history.record(entry("base64", "ab", 2));
+        history.record(entry("base64", "abc", 3));
+        assert_eq!(inputs(&history), ["abc", "x"]);
+
+        history.set_pinned(0, true);
+        history.record(entry("base64", "abcd", 4));
+        assert_eq!(inputs(&history), ["abcd", "abc", "x"]);
+    }
+
+    #[test]
+    fn evicts_oldest_unpinned_entries() {
+        let mut history = History::default();
+        for index in 0..=MAX_ENTRIES_PER_TOOL as i64 {
+            history.record(entry(
+                "base64",
+                &index.to_string(),
+                index * COALESCE_SECONDS,
+            ));
+            if index == 0 {
+                history.set_pinned(0, true);
+            }
+        }
+        assert_eq!(history.entries().len(), MAX_ENTRIES_PER_TOOL + 1);
+        assert!(history.entries().last().unwrap().pinned);
+
+        history.record(entry("base64", "new", i64::MAX));
+        assert_eq!(history.entries().len(), MAX_ENTRIES_PER_TOOL + 1);
+        assert_eq!(history.entries()[MAX_ENTRIES_PER_TOOL - 1].input, "2");
+
+        let large = "x".repeat(MAX_ENTRY_SIZE / 2 - 8);
+        for index in 0..MAX_SIZE / MAX_ENTRY_SIZE * 2 {
+            history.record(entry(
+                &index.to_string(),
+                &format!("{index}{large}"),
+                i64::MAX,
+            ));
+        }
+        let size: usize = history.entries().iter().map(HistoryEntry::size).sum();
+        assert!(size <= MAX_SIZE);
+        assert_eq!(history.entries().last().unwrap().input, "0");
+    }
+
+    #[test]
+    fn skips_empty_and_oversized_entries() {
+        let mut history = History::default();
+        assert!(!history.record(entry("base64", " \n", 0)));
+        assert!(!history.record(entry("base64", &"x".repeat(MAX_ENTRY_SIZE), 0)));
+        assert!(history.entries().is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut history = History::default();
+        history.record(entry("jwt-debugger", "token", 0));
+        history.set_pinned(0, true);
+        assert_eq!(History::from_json(&history.to_json()).unwrap(), history);
+    }
+}
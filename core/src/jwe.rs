@@ -0,0 +1,679 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Decryption and encryption of compact JSON Web Encryption tokens.
+//!
+//! The payload of a nested token is itself a signed token, which [`crate::jwt`] decodes.
+
+use {
+    crate::jwt::{self, JwtError, JwtKeys},
+    aes_gcm::{
+        Aes128Gcm, Aes256Gcm, AesGcm, KeyInit,
+        aead::{Aead, Payload, consts::U12},
+        aes::{Aes128, Aes192, Aes256},
+    },
+    aes_kw::KekAes256,
+    base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD},
+    cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit, block_padding::Pkcs7},
+    hmac::{Hmac, Mac},
+    p256::elliptic_curve::sec1::ToEncodedPoint,
+    rand::{RngCore, rngs::OsRng},
+    rsa::{
+        Oaep, RsaPrivateKey, RsaPublicKey,
+        pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
+        pkcs8::{DecodePrivateKey, DecodePublicKey},
+    },
+    serde_json::{Map, Value, json},
+    sha1::Sha1,
+    sha2::{Digest, Sha256, Sha384, Sha512},
+    subtle::ConstantTimeEq,
+};
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+/// The `alg` of a token: how the content encryption key is agreed upon or carried.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyManagement {
+    /// The symmetric key is the content encryption key.
+    Dir,
+    /// The content encryption key is wrapped with the symmetric key.
+    A256Kw,
+    RsaOaep,
+    RsaOaep256,
+    /// The content encryption key is derived from an ephemeral key and the key pair.
+    EcdhEs,
+}
+
+impl KeyManagement {
+    pub const ALL: [KeyManagement; 5] = [
+        KeyManagement::Dir,
+        KeyManagement::A256Kw,
+        KeyManagement::RsaOaep,
+        KeyManagement::RsaOaep256,
+        KeyManagement::EcdhEs,
+    ];
+
+    /// The name of the algorithm in a header.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyManagement::Dir => "dir",
+            KeyManagement::A256Kw => "A256KW",
+            KeyManagement::RsaOaep => "RSA-OAEP",
+            KeyManagement::RsaOaep256 => "RSA-OAEP-256",
+            KeyManagement::EcdhEs => "ECDH-ES",
+        }
+    }
+
+    /// Whether the algorithm uses the symmetric key rather than the PEM keys.
+    pub fn is_symmetric(self) -> bool {
+        matches!(self, KeyManagement::Dir | KeyManagement::A256Kw)
+    }
+}
+
+/// The `enc` of a token: how the payload is encrypted with the content encryption key.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContentEncryption {
+    A128Gcm,
+    A192Gcm,
+    A256Gcm,
+    A128CbcHs256,
+    A192CbcHs384,
+    A256CbcHs512,
+}
+
+impl ContentEncryption {
+    pub const ALL: [ContentEncryption; 6] = [
+        ContentEncryption::A128Gcm,
+        ContentEncryption::A192Gcm,
+        ContentEncryption::A256Gcm,
+        ContentEncryption::A128CbcHs256,
+        ContentEncryption::A192CbcHs384,
+        ContentEncryption::A256CbcHs512,
+    ];
+
+    /// The name of the algorithm in a header.
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentEncryption::A128Gcm => "A128GCM",
+            ContentEncryption::A192Gcm => "A192GCM",
+            ContentEncryption::A256Gcm => "A256GCM",
+            ContentEncryption::A128CbcHs256 => "A128CBC-HS256",
+            ContentEncryption::A192CbcHs384 => "A192CBC-HS384",
+            ContentEncryption::A256CbcHs512 => "A256CBC-HS512",
+        }
+    }
+
+    /// The length in bytes of the content encryption key, which for CBC is the MAC key followed
+    /// by the AES key.
+    pub fn key_len(self) -> usize {
+        match self {
+            ContentEncryption::A128Gcm => 16,
+            ContentEncryption::A192Gcm => 24,
+            ContentEncryption::A256Gcm | ContentEncryption::A128CbcHs256 => 32,
+            ContentEncryption::A192CbcHs384 => 48,
+            ContentEncryption::A256CbcHs512 => 64,
+        }
+    }
+
+    fn iv_len(self) -> usize {
+        match self {
+            ContentEncryption::A128Gcm
+            | ContentEncryption::A192Gcm
+            | ContentEncryption::A256Gcm => 12,
+            _ => 16,
+        }
+    }
+}
+
+/// Whether `token` has the five parts of a compact encrypted token rather than the three of a
+/// signed one.
+pub fn is_jwe(token: &str) -> bool {
+    token.trim().split('.').count() == 5
+}
+
+/// Whether the payload of a token with this header is a signed token.
+pub fn is_nested(header: &Map<String, Value>) -> bool {
+    header
+        .get("cty")
+        .and_then(Value::as_str)
+        .is_some_and(|content_type| content_type.eq_ignore_ascii_case("JWT"))
+}
+
+/// Decodes the protected header of `token` without decrypting it.
+pub fn decode_header(token: &str) -> Result<Map<String, Value>, JwtError> {
+    let protected = token.trim().split('.').next().unwrap_or_default();
+    let header = URL_SAFE_NO_PAD
+        .decode(protected)
+        .map_err(|_| JwtError::InvalidToken)?;
+    serde_json::from_slice(&header).map_err(|_| JwtError::InvalidToken)
+}
+
+/// Parses a header written as JSON, requiring a supported `alg` and `enc`.
+pub fn parse_header(
+    header_input: &str,
+) -> Result<(Map<String, Value>, KeyManagement, ContentEncryption), JwtError> {
+    let header = serde_json::from_str::<Map<String, Value>>(header_input)
+        .map_err(|_| JwtError::InvalidHeader)?;
+    let (alg, enc) = algorithms(&header)?;
+    Ok((header, alg, enc))
+}
+
+/// Decrypts `token`, returning its payload.
+///
+/// `dir` and `A256KW` use the symmetric key, RSA and ECDH the PEM private key.
+pub fn decrypt(token: &str, keys: &JwtKeys) -> Result<Vec<u8>, JwtError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    let [protected, encrypted_key, iv, ciphertext, tag] = parts[..] else {
+        return Err(JwtError::InvalidToken);
+    };
+    let header = decode_header(token)?;
+    let (alg, enc) = algorithms(&header)?;
+    let [encrypted_key, iv, ciphertext, tag] =
+        [encrypted_key, iv, ciphertext, tag].map(|part| URL_SAFE_NO_PAD.decode(part));
+    let (encrypted_key, iv, ciphertext, tag) = (
+        encrypted_key.map_err(|_| JwtError::InvalidToken)?,
+        iv.map_err(|_| JwtError::InvalidToken)?,
+        ciphertext.map_err(|_| JwtError::InvalidToken)?,
+        tag.map_err(|_| JwtError::InvalidToken)?,
+    );
+    if iv.len() != enc.iv_len() {
+        return Err(JwtError::InvalidToken);
+    }
+
+    let cek = match alg {
+        KeyManagement::Dir => jwt::symmetric_key_bytes(keys)?,
+        KeyManagement::A256Kw => kek(keys)?
+            .unwrap_vec(&encrypted_key)
+            .map_err(|_| JwtError::DecryptionFailed)?,
+        KeyManagement::RsaOaep => rsa_private_key(&keys.private_pem())?
+            .decrypt(Oaep::new::<Sha1>(), &encrypted_key)
+            .map_err(|_| JwtError::DecryptionFailed)?,
+        KeyManagement::RsaOaep256 => rsa_private_key(&keys.private_pem())?
+            .decrypt(Oaep::new::<Sha256>(), &encrypted_key)
+            .map_err(|_| JwtError::DecryptionFailed)?,
+        KeyManagement::EcdhEs => {
+            let epk = header.get("epk").ok_or(JwtError::InvalidHeader)?;
+            let shared_secret = static_agreement(&keys.private_pem(), epk)?;
+            derive_key(&shared_secret, enc, &header)?
+        }
+    };
+    if cek.len() != enc.key_len() {
+        return Err(JwtError::InvalidKey);
+    }
+    decrypt_content(enc, &cek, &iv, &ciphertext, &tag, protected.as_bytes())
+}
+
+/// Encrypts `payload` into a compact token with the header written as JSON.
+///
+/// `dir` and `A256KW` use the symmetric key, RSA and ECDH the PEM public key. ECDH adds the
+/// ephemeral public key to the header as `epk`.
+pub fn encrypt(header_input: &str, payload: &[u8], keys: &JwtKeys) -> Result<String, JwtError> {
+    let (mut header, alg, enc) = parse_header(header_input)?;
+
+    let (cek, encrypted_key) = match alg {
+        KeyManagement::Dir => (jwt::symmetric_key_bytes(keys)?, Vec::new()),
+        KeyManagement::A256Kw => {
+            let cek = random_bytes(enc.key_len());
+            let encrypted_key = kek(keys)?
+                .wrap_vec(&cek)
+                .map_err(|_| JwtError::EncodingFailed)?;
+            (cek, encrypted_key)
+        }
+        KeyManagement::RsaOaep | KeyManagement::RsaOaep256 => {
+            let public_key = rsa_public_key(&keys.public_pem())?;
+            let cek = random_bytes(enc.key_len());
+            let encrypted_key = if alg == KeyManagement::RsaOaep {
+                public_key.encrypt(&mut OsRng, Oaep::new::<Sha1>(), &cek)
+            } else {
+                public_key.encrypt(&mut OsRng, Oaep::new::<Sha256>(), &cek)
+            }
+            .map_err(|_| JwtError::EncodingFailed)?;
+            (cek, encrypted_key)
+        }
+        KeyManagement::EcdhEs => {
+            let (epk, shared_secret) = ephemeral_agreement(&keys.public_pem())?;
+            header.insert("epk".to_string(), epk);
+            (derive_key(&shared_secret, enc, &header)?, Vec::new())
+        }
+    };
+    if cek.len() != enc.key_len() {
+        return Err(JwtError::InvalidKey);
+    }
+
+    let protected = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).unwrap());
+    let iv = random_bytes(enc.iv_len());
+    let (ciphertext, tag) = encrypt_content(enc, &cek, &iv, payload, protected.as_bytes())?;
+    Ok([
+        protected,
+        URL_SAFE_NO_PAD.encode(encrypted_key),
+        URL_SAFE_NO_PAD.encode(iv),
+        URL_SAFE_NO_PAD.encode(ciphertext),
+        URL_SAFE_NO_PAD.encode(tag),
+    ]
+    .join("."))
+}
+
+/// The `alg` and `enc` of a header, which must both be supported.
+pub fn algorithms(
+    header: &Map<String, Value>,
+) -> Result<(KeyManagement, ContentEncryption), JwtError> {
+    let (Some(alg), Some(enc)) = (
+        header.get("alg").and_then(Value::as_str),
+        header.get("enc").and_then(Value::as_str),
+    ) else {
+        return Err(JwtError::InvalidHeader);
+    };
+    // Compressed payloads are not supported.
+    if header.contains_key("zip") {
+        return Err(JwtError::UnsupportedAlgorithm);
+    }
+    let alg = KeyManagement::ALL
+        .into_iter()
+        .find(|key_management| key_management.name() == alg)
+        .ok_or(JwtError::UnsupportedAlgorithm)?;
+    let enc = ContentEncryption::ALL
+        .into_iter()
+        .find(|content_encryption| content_encryption.name() == enc)
+        .ok_or(JwtError::UnsupportedAlgorithm)?;
+    Ok((alg, enc))
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn kek(keys: &JwtKeys) -> Result<KekAes256, JwtError> {
+    let key = jwt::symmetric_key_bytes(keys)?;
+    <[u8; 32]>::try_from(key.as_slice())
+        .map(KekAes256::from)
+        .map_err(|_| JwtError::InvalidKey)
+}
+
+fn rsa_private_key(pem: &str) -> Result<RsaPrivateKey, JwtError> {
+    let pem = pem.trim();
+    RsaPrivateKey::from_pkcs8_pem(pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+        .map_err(|_| JwtError::InvalidKey)
+}
+
+fn rsa_public_key(pem: &str) -> Result<RsaPublicKey, JwtError> {
+    let pem = pem.trim();
+    RsaPublicKey::from_public_key_pem(pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        .map_err(|_| JwtError::InvalidKey)
+}
+
+/// Agrees on a secret with the PEM public key of the recipient, returning it with the
+/// ephemeral public key as a JWK.
+fn ephemeral_agreement(pem: &str) -> Result<(Value, Vec<u8>), JwtError> {
+    let pem = pem.trim();
+    if let Ok(public_key) = p256::PublicKey::from_public_key_pem(pem) {
+        let secret = p256::ecdh::EphemeralSecret::random(&mut OsRng);
+        let shared_secret = secret.diffie_hellman(&public_key);
+        let point = secret.public_key().to_encoded_point(false);
+        Ok((
+            epk("P-256", point.as_bytes()),
+            shared_secret.raw_secret_bytes().to_vec(),
+        ))
+    } else if let Ok(public_key) = p384::PublicKey::from_public_key_pem(pem) {
+        let secret = p384::ecdh::EphemeralSecret::random(&mut OsRng);
+        let shared_secret = secret.diffie_hellman(&public_key);
+        let point = secret.public_key().to_encoded_point(false);
+        Ok((
+            epk("P-384", point.as_bytes()),
+            shared_secret.raw_secret_bytes().to_vec(),
+        ))
+    } else {
+        Err(JwtError::InvalidKey)
+    }
+}
+
+/// The JWK of an uncompressed point, a 0x04 tag followed by both coordinates.
+fn epk(curve: &str, point: &[u8]) -> Value {
+    let (x, y) = point[1..].split_at((point.len() - 1) / 2);
+    json!({
+        "kty": "EC",
+        "crv": curve,
+        "x": URL_SAFE_NO_PAD.encode(x),
+        "y": URL_SAFE_NO_PAD.encode(y),
+    })
+}
+
+/// Agrees on a secret with the ephemeral public key of the sender, using the PEM private key.
+fn static_agreement(pem: &str, epk: &Value) -> Result<Vec<u8>, JwtError> {
+    let pem = pem.trim();
+    let coordinate = |name: &str| {
+        epk.get(name)
+            .and_then(Value::as_str)
+            .and_then(|coordinate| URL_SAFE_NO_PAD.decode(coordinate).ok())
+            .ok_or(JwtError::InvalidHeader)
+    };
+    let point = [vec![0x04], coordinate("x")?, coordinate("y")?].concat();
+    match epk.get("crv").and_then(Value::as_str) {
+        Some("P-256") => {
+            let secret_key = p256::SecretKey::from_pkcs8_pem(pem)
+                .or_else(|_| p256::SecretKey::from_sec1_pem(pem))
+                .map_err(|_| JwtError::InvalidKey)?;
+            let public_key =
+                p256::PublicKey::from_sec1_bytes(&point).map_err(|_| JwtError::InvalidHeader)?;
+            let shared_secret =
+                p256::ecdh::diffie_hellman(secret_key.to_nonzero_scalar(), public_key.as_affine());
+            Ok(shared_secret.raw_secret_bytes().to_vec())
+        }
+        Some("P-384") => {
+            let secret_key = p384::SecretKey::from_pkcs8_pem(pem)
+                .or_else(|_| p384::SecretKey::from_sec1_pem(pem))
+                .map_err(|_| JwtError::InvalidKey)?;
+            let public_key =
+                p384::PublicKey::from_sec1_bytes(&point).map_err(|_| JwtError::InvalidHeader)?;
+            let shared_secret =
+                p384::ecdh::diffie_hellman(secret_key.to_nonzero_scalar(), public_key.as_affine());
+            Ok(shared_secret.raw_secret_bytes().to_vec())
+        }
+        _ => Err(JwtError::UnsupportedAlgorithm),
+    }
+}
+
+/// Derives the content encryption key of `ECDH-ES` with the Concat KDF of RFC 7518 section
+/// 4.6.2, using the `apu` and `apv` of the header.
+fn derive_key(
+    shared_secret: &[u8],
+    enc: ContentEncryption,
+    header: &Map<String, Value>,
+) -> Result<Vec<u8>, JwtError> {
+    let party_info = |name: &str| match header.get(name).and_then(Value::as_str) {
+        Some(info) => URL_SAFE_NO_PAD
+            .decode(info)
+            .map_err(|_| JwtError::InvalidHeader),
+        None => Ok(Vec::new()),
+    };
+    let mut other_info = Vec::new();
+    for field in [
+        enc.name().as_bytes().to_vec(),
+        party_info("apu")?,
+        party_info("apv")?,
+    ] {
+        other_info.extend((field.len() as u32).to_be_bytes());
+        other_info.extend(field);
+    }
+    other_info.extend((enc.key_len() as u32 * 8).to_be_bytes());
+
+    let mut key = Vec::with_capacity(enc.key_len());
+    for counter in 1..=enc.key_len().div_ceil(32) as u32 {
+        key.extend(
+            Sha256::new()
+                .chain_update(counter.to_be_bytes())
+                .chain_update(shared_secret)
+                .chain_update(&other_info)
+                .finalize(),
+        );
+    }
+    key.truncate(enc.key_len());
+    Ok(key)
+}
+
+fn encrypt_content(
+    enc: ContentEncryption,
+    cek: &[u8],
+    iv: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), JwtError> {
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+    let ciphertext = match enc {
+        ContentEncryption::A128Gcm => gcm_encrypt::<Aes128Gcm>(cek, iv, payload),
+        ContentEncryption::A192Gcm => gcm_encrypt::<Aes192Gcm>(cek, iv, payload),
+        ContentEncryption::A256Gcm => gcm_encrypt::<Aes256Gcm>(cek, iv, payload),
+        ContentEncryption::A128CbcHs256
+        | ContentEncryption::A192CbcHs384
+        | ContentEncryption::A256CbcHs512 => {
+            let (mac_key, enc_key) = cek.split_at(cek.len() / 2);
+            let ciphertext = match enc_key.len() {
+                16 => cbc::Encryptor::<Aes128>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.encrypt_padded_vec_mut::<Pkcs7>(plaintext)),
+                24 => cbc::Encryptor::<Aes192>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.encrypt_padded_vec_mut::<Pkcs7>(plaintext)),
+                _ => cbc::Encryptor::<Aes256>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.encrypt_padded_vec_mut::<Pkcs7>(plaintext)),
+            }
+            .map_err(|_| JwtError::InvalidKey)?;
+            let tag = cbc_tag(enc, mac_key, aad, iv, &ciphertext)?;
+            return Ok((ciphertext, tag));
+        }
+    }?;
+    Ok(ciphertext)
+}
+
+fn decrypt_content(
+    enc: ContentEncryption,
+    cek: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, JwtError> {
+    match enc {
+        ContentEncryption::A128Gcm | ContentEncryption::A192Gcm | ContentEncryption::A256Gcm => {
+            // The AEAD ciphers expect the tag after the ciphertext.
+            let ciphertext = [ciphertext, tag].concat();
+            let payload = Payload {
+                msg: &ciphertext,
+                aad,
+            };
+            match enc {
+                ContentEncryption::A128Gcm => gcm_decrypt::<Aes128Gcm>(cek, iv, payload),
+                ContentEncryption::A192Gcm => gcm_decrypt::<Aes192Gcm>(cek, iv, payload),
+                _ => gcm_decrypt::<Aes256Gcm>(cek, iv, payload),
+            }
+        }
+        ContentEncryption::A128CbcHs256
+        | ContentEncryption::A192CbcHs384
+        | ContentEncryption::A256CbcHs512 => {
+            let (mac_key, enc_key) = cek.split_at(cek.len() / 2);
+            let expected_tag = cbc_tag(enc, mac_key, aad, iv, ciphertext)?;
+            if !bool::from(expected_tag.ct_eq(tag)) {
+                return Err(JwtError::DecryptionFailed);
+            }
+            match enc_key.len() {
+                16 => cbc::Decryptor::<Aes128>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.decrypt_padded_vec_mut::<Pkcs7>(ciphertext)),
+                24 => cbc::Decryptor::<Aes192>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.decrypt_padded_vec_mut::<Pkcs7>(ciphertext)),
+                _ => cbc::Decryptor::<Aes256>::new_from_slices(enc_key, iv)
+                    .map(|cipher| cipher.decrypt_padded_vec_mut::<Pkcs7>(ciphertext)),
+            }
+            .map_err(|_| JwtError::InvalidKey)?
+            .map_err(|_| JwtError::DecryptionFailed)
+        }
+    }
+}
+
+/// Returns the ciphertext followed by the tag.
+fn gcm_encrypt<C: Aead + KeyInit>(
+    cek: &[u8],
+    iv: &[u8],
+    payload: Payload<'_, '_>,
+) -> Result<(Vec<u8>, Vec<u8>), JwtError> {
+    let cipher = C::new_from_slice(cek).map_err(|_| JwtError::InvalidKey)?;
+    let mut ciphertext = cipher
+        .encrypt(iv.into(), payload)
+        .map_err(|_| JwtError::EncodingFailed)?;
+    let tag = ciphertext.split_off(ciphertext.len() - 16);
+    Ok((ciphertext, tag))
+}
+
+fn gcm_decrypt<C: Aead + KeyInit>(
+    cek: &[u8],
+    iv: &[u8],
+    payload: Payload<'_, '_>,
+) -> Result<Vec<u8>, JwtError> {
+    let cipher = C::new_from_slice(cek).map_err(|_| JwtError::InvalidKey)?;
+    cipher
+        .decrypt(iv.into(), payload)
+        .map_err(|_| JwtError::DecryptionFailed)
+}
+
+/// The tag of RFC 7518 section 5.2.2.1: the first half of the HMAC of the additional
+/// authenticated data, the IV, the ciphertext and the length in bits of the data.
+fn cbc_tag(
+    enc: ContentEncryption,
+    mac_key: &[u8],
+    aad: &[u8],
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, JwtError> {
+    let input = [aad, iv, ciphertext, &(aad.len() as u64 * 8).to_be_bytes()].concat();
+    let mac = match enc {
+        ContentEncryption::A192CbcHs384 => <Hmac<Sha384> as Mac>::new_from_slice(mac_key)
+            .map(|mac| mac.chain_update(&input).finalize().into_bytes().to_vec()),
+        ContentEncryption::A256CbcHs512 => <Hmac<Sha512> as Mac>::new_from_slice(mac_key)
+            .map(|mac| mac.chain_update(&input).finalize().into_bytes().to_vec()),
+        _ => <Hmac<Sha256> as Mac>::new_from_slice(mac_key)
+            .map(|mac| mac.chain_update(&input).finalize().into_bytes().to_vec()),
+    }
+    .map_err(|_| JwtError::InvalidKey)?;
+    Ok(mac[..mac_key.len()].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, crate::jwt::KeyEncoding, base64::engine::general_purpose::STANDARD,
+        p256::elliptic_curve::sec1::FromEncodedPoint,
+    };
+
+    const CLAIMS: &[u8] = br#"{"sub":"1234567890"}"#;
+
+    fn secret(len: usize) -> JwtKeys {
+        JwtKeys {
+            symmetric_key: STANDARD.encode(vec![7; len]),
+            key_encoding: KeyEncoding::Base64,
+            ..JwtKeys::default()
+        }
+    }
+
+    fn pem_keys(name: &str) -> JwtKeys {
+        let read = |kind: &str| {
+            std::fs::read_to_string(format!(
+                "{}/tests/keys/{name}_{kind}.pem",
+                env!("CARGO_MANIFEST_DIR")
+            ))
+            .unwrap()
+        };
+        JwtKeys {
+            public_key: read("public"),
+            private_key: read("private"),
+            ..JwtKeys::default()
+        }
+    }
+
+    fn header(alg: KeyManagement, enc: ContentEncryption) -> String {
+        format!(r#"{{"alg":"{}","enc":"{}"}}"#, alg.name(), enc.name())
+    }
+
+    #[test]
+    fn round_trips_every_algorithm() {
+        for enc in ContentEncryption::ALL {
+            for (alg, keys) in [
+                (KeyManagement::Dir, secret(enc.key_len())),
+                (KeyManagement::A256Kw, secret(32)),
+                (KeyManagement::RsaOaep, pem_keys("rsa")),
+                (KeyManagement::RsaOaep256, pem_keys("rsa")),
+                (KeyManagement::EcdhEs, pem_keys("ec_p256")),
+                (KeyManagement::EcdhEs, pem_keys("ec_p384")),
+            ] {
+                let token = encrypt(&header(alg, enc), CLAIMS, &keys).unwrap();
+                assert!(is_jwe(&token));
+                assert_eq!(decrypt(&token, &keys).unwrap(), CLAIMS, "{alg:?} {enc:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_tokens_and_wrong_keys() {
+        for enc in [ContentEncryption::A256Gcm, ContentEncryption::A256CbcHs512] {
+            let keys = secret(enc.key_len());
+            let token = encrypt(&header(KeyManagement::Dir, enc), CLAIMS, &keys).unwrap();
+            let mut parts: Vec<&str> = token.split('.').collect();
+            parts[3] = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+            assert_eq!(
+                decrypt(&parts.join("."), &keys),
+                Err(JwtError::DecryptionFailed)
+            );
+            assert_eq!(decrypt(&token, &secret(16)), Err(JwtError::InvalidKey));
+        }
+
+        let token = encrypt(
+            &header(KeyManagement::A256Kw, ContentEncryption::A128Gcm),
+            CLAIMS,
+            &secret(32),
+        )
+        .unwrap();
+        let wrong_keys = JwtKeys {
+            symmetric_key: STANDARD.encode([8; 32]),
+            ..secret(32)
+        };
+        assert_eq!(
+            decrypt(&token, &wrong_keys),
+            Err(JwtError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn requires_supported_algorithms() {
+        assert_eq!(
+            parse_header(r#"{"alg":"dir"}"#).unwrap_err(),
+            JwtError::InvalidHeader
+        );
+        assert_eq!(
+            parse_header(r#"{"alg":"PBES2-HS256+A128KW","enc":"A128GCM"}"#).unwrap_err(),
+            JwtError::UnsupportedAlgorithm
+        );
+        assert!(is_nested(
+            &parse_header(r#"{"alg":"dir","enc":"A128GCM","cty":"jwt"}"#)
+                .unwrap()
+                .0
+        ));
+        assert!(!is_jwe("a.b.c"));
+    }
+
+    // The key agreement example of RFC 7518 appendix C.
+    #[test]
+    fn derives_rfc_7518_ecdh_es_key() {
+        let coordinate = |coordinate: &str| URL_SAFE_NO_PAD.decode(coordinate).unwrap();
+        let bob_private_key =
+            p256::SecretKey::from_slice(&coordinate("VEmDZpDXXK8p8N0Cndsxs924q6nS1RXFASRl6BfUqdw"))
+                .unwrap();
+        let alice_public_key =
+            p256::PublicKey::from_encoded_point(&p256::EncodedPoint::from_affine_coordinates(
+                coordinate("gI0GAILBdu7T53akrFmMyGcsF3n5dO7MmwNBHKW5SV0")
+                    .as_slice()
+                    .into(),
+                coordinate("SLW_xSffzlPWrHEVI30DHM_4egVwt3NQqeUD7nMFpps")
+                    .as_slice()
+                    .into(),
+                false,
+            ))
+            .unwrap();
+        let shared_secret = p256::ecdh::diffie_hellman(
+            bob_private_key.to_nonzero_scalar(),
+            alice_public_key.as_affine(),
+        );
+        let header = serde_json::from_str(r#"{"apu":"QWxpY2U","apv":"Qm9i"}"#).unwrap();
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(
+                derive_key(
+                    shared_secret.raw_secret_bytes(),
+                    ContentEncryption::A128Gcm,
+                    &header
+                )
+                .unwrap()
+            ),
+            "VqqN6vgjbSBcIijNcacQGg"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! JSON Web Keys: picking the keys of a key set that may have signed a token, and naming keys by
+//! their `kid`.
+
+use {crate::jwt::JwtError, jsonwebtoken::jwk::ThumbprintHash};
+
+pub use jsonwebtoken::jwk::{Jwk, JwkSet};
+
+/// Whether a key is written as JSON, a JWK or a JWK Set, rather than PEM.
+pub fn is_jwk(key: &str) -> bool {
+    key.trim_start().starts_with('{')
+}
+
+/// Parses a JWK Set, or a single JWK as a set of that key.
+///
+/// Keys this app cannot use, such as encryption keys of other algorithms, are left out rather
+/// than failing the whole set.
+pub fn parse_key_set(input: &str) -> Result<JwkSet, JwtError> {
+    let value =
+        serde_json::from_str::<serde_json::Value>(input).map_err(|_| JwtError::InvalidKeySet)?;
+    let keys = match value.get("keys") {
+        Some(serde_json::Value::Array(keys)) => keys
+            .iter()
+            .filter_map(|key| serde_json::from_value(key.clone()).ok())
+            .collect(),
+        Some(_) => return Err(JwtError::InvalidKeySet),
+        None => vec![serde_json::from_value(value).map_err(|_| JwtError::InvalidKeySet)?],
+    };
+    Ok(JwkSet { keys })
+}
+
+/// The keys of a set that may have signed a token with the key id `kid`: those with that id, or
+/// every key when the token names none.
+pub fn candidates<'a>(key_set: &'a JwkSet, kid: Option<&str>) -> Vec<&'a Jwk> {
+    key_set
+        .keys
+        .iter()
+        .filter(|jwk| kid.is_none_or(|kid| jwk.common.key_id.as_deref() == Some(kid)))
+        .collect()
+}
+
+/// The `kid` of a key, or its RFC 7638 thumbprint when it has none.
+pub fn key_id(jwk: &Jwk) -> String {
+    jwk.common
+        .key_id
+        .clone()
+        .unwrap_or_else(|| jwk.thumbprint(ThumbprintHash::SHA256))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example key of RFC 7638 section 3.1, whose thumbprint the RFC gives.
+    const RFC_7638_KEY: &str = r#"{
+        "kty": "RSA",
+        "n": "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw",
+        "e": "AQAB",
+        "alg": "RS256",
+        "kid": "2011-04-29"
+    }"#;
+
+    #[test]
+    fn computes_rfc_7638_thumbprints() {
+        let key_set = parse_key_set(RFC_7638_KEY).unwrap();
+        assert_eq!(
+            key_set.keys[0].thumbprint(ThumbprintHash::SHA256),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+        assert_eq!(key_id(&key_set.keys[0]), "2011-04-29");
+    }
+
+    #[test]
+    fn picks_candidates_by_kid() {
+        let key_set = parse_key_set(&format!(
+            r#"{{"keys": [{RFC_7638_KEY}, {{"kty": "oct", "k": "c2VjcmV0", "kid": "other"}}, {{"kty": "unknown"}}]}}"#
+        ))
+        .unwrap();
+        assert_eq!(key_set.keys.len(), 2);
+        assert_eq!(candidates(&key_set, Some("other")).len(), 1);
+        assert_eq!(candidates(&key_set, Some("missing")).len(), 0);
+        assert_eq!(candidates(&key_set, None).len(), 2);
+        assert_eq!(
+            parse_key_set(r#"{"keys": 1}"#).unwrap_err(),
+            JwtError::InvalidKeySet
+        );
+        assert!(is_jwk(" {\"keys\": []}"));
+        assert!(!is_jwk("-----BEGIN PUBLIC KEY-----"));
+    }
+}
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Decoding, verification and signing of JSON Web Tokens.
+
+use {
+    crate::{
+        jwk::{self, Jwk},
+        key::Key,
+    },
+    base64::{Engine, engine::general_purpose::STANDARD},
+    jsonwebtoken::{DecodingKey, EncodingKey, Validation},
+    std::borrow::Cow,
+};
+
+pub use jsonwebtoken::{Algorithm, Header};
+
+/// How the symmetric key text is turned into key bytes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum KeyEncoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+impl KeyEncoding {
+    pub const ALL: [KeyEncoding; 2] = [KeyEncoding::Utf8, KeyEncoding::Base64];
+}
+
+/// The key material used to sign and verify tokens.
+///
+/// HMAC algorithms use `symmetric_key`, every other algorithm uses the key pair, written in any
+/// format [`Key`] reads. The public key may also be a JWK Set, whose key is then picked by the
+/// `kid` of the token, or the private key it belongs to.
+#[derive(Clone, Debug, Default)]
+pub struct JwtKeys {
+    pub symmetric_key: String,
+    pub key_encoding: KeyEncoding,
+    pub public_key: String,
+    pub private_key: String,
+}
+
+impl JwtKeys {
+    /// The public key as SPKI PEM, or as given when it is not a key, to fail as PEM.
+    pub(crate) fn public_pem(&self) -> Cow<'_, str> {
+        Key::parse(&self.public_key)
+            .and_then(|key| key.public_key().to_pem())
+            .map_or(Cow::Borrowed(&self.public_key), Cow::Owned)
+    }
+
+    /// The private key as PKCS#8 PEM, or as given when it is not a key, to fail as PEM.
+    pub(crate) fn private_pem(&self) -> Cow<'_, str> {
+        Key::parse(&self.private_key)
+            .and_then(|key| key.to_pem())
+            .map_or(Cow::Borrowed(&self.private_key), Cow::Owned)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum JwtError {
+    #[error("Invalid token")]
+    InvalidToken,
+    #[error("Invalid key")]
+    InvalidKey,
+    #[error("Invalid header")]
+    InvalidHeader,
+    #[error("Invalid claims")]
+    InvalidClaims,
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Invalid key set")]
+    InvalidKeySet,
+    #[error("No key of the key set matches the token")]
+    NoMatchingKey,
+    #[error("Encoding failed")]
+    EncodingFailed,
+    #[error("Unsupported algorithm")]
+    UnsupportedAlgorithm,
+    #[error("Decryption failed")]
+    DecryptionFailed,
+}
+
+impl JwtError {
+    /// The Fluent attribute of `jwt-debugger` describing this error.
+    pub fn message_id(self) -> &'static str {
+        match self {
+            JwtError::InvalidToken => "invalid-token",
+            JwtError::InvalidKey => "invalid-key",
+            JwtError::InvalidHeader => "invalid-header",
+            JwtError::InvalidClaims => "invalid-claims",
+            JwtError::InvalidSignature => "invalid-signature",
+            JwtError::InvalidKeySet => "invalid-key-set",
+            JwtError::NoMatchingKey => "no-matching-key",
+            JwtError::EncodingFailed => "encoding-failed",
+            JwtError::UnsupportedAlgorithm => "unsupported-algorithm",
+            JwtError::DecryptionFailed => "decryption-failed",
+        }
+    }
+}
+
+/// Whether the algorithm signs with a shared secret rather than a key pair.
+pub fn is_symmetric(algorithm: Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    )
+}
+
+/// Decodes the header of `token` without verifying its signature.
+pub fn decode_header(token: &str) -> Result<Header, JwtError> {
+    jsonwebtoken::decode_header(token.trim().as_bytes()).map_err(|_| JwtError::InvalidToken)
+}
+
+/// Decodes the claims of `token` without verifying its signature.
+pub fn decode_claims(token: &str) -> Result<serde_json::Value, JwtError> {
+    jsonwebtoken::dangerous::insecure_decode::<serde_json::Value>(token.trim().as_bytes())
+        .map(|token_data| token_data.claims)
+        .map_err(|_| JwtError::InvalidToken)
+}
+
+/// The bytes of the symmetric key, which also serves as the key of `dir` and `A256KW` encryption.
+pub(crate) fn symmetric_key_bytes(keys: &JwtKeys) -> Result<Vec<u8>, JwtError> {
+    match keys.key_encoding {
+        KeyEncoding::Utf8 => Ok(keys.symmetric_key.as_bytes().to_vec()),
+        KeyEncoding::Base64 => STANDARD
+            .decode(keys.symmetric_key.trim())
+            .map_err(|_| JwtError::InvalidKey),
+    }
+}
+
+fn decoding_key(algorithm: Algorithm, keys: &JwtKeys) -> Result<DecodingKey, JwtError> {
+    match algorithm {
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => match keys.key_encoding {
+            KeyEncoding::Utf8 => Ok(DecodingKey::from_secret(keys.symmetric_key.as_bytes())),
+            KeyEncoding::Base64 => DecodingKey::from_base64_secret(keys.symmetric_key.trim()),
+        },
+        Algorithm::RS256
+        | Algorithm::RS384
+        | Algorithm::RS512
+        | Algorithm::PS256
+        | Algorithm::PS384
+        | Algorithm::PS512 => DecodingKey::from_rsa_pem(keys.public_pem().as_bytes()),
+        Algorithm::ES256 | Algorithm::ES384 => {
+            DecodingKey::from_ec_pem(keys.public_pem().as_bytes())
+        }
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(keys.public_pem().as_bytes()),
+    }
+    .map_err(|_| JwtError::InvalidKey)
+}
+
+fn encoding_key(algorithm: Algorithm, keys: &JwtKeys) -> Result<EncodingKey, JwtError> {
+    match algorithm {
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => match keys.key_encoding {
+            KeyEncoding::Utf8 => Ok(EncodingKey::from_secret(keys.symmetric_key.as_bytes())),
+            KeyEncoding::Base64 => EncodingKey::from_base64_secret(keys.symmetric_key.trim()),
+        },
+        Algorithm::RS256
+        | Algorithm::RS384
+        | Algorithm::RS512
+        | Algorithm::PS256
+        | Algorithm::PS384
+        | Algorithm::PS512 => EncodingKey::from_rsa_pem(keys.private_pem().as_bytes()),
+        Algorithm::ES256 | Algorithm::ES384 => {
+            EncodingKey::from_ec_pem(keys.private_pem().as_bytes())
+        }
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(keys.private_pem().as_bytes()),
+    }
+    .map_err(|_| JwtError::InvalidKey)
+}
+
+/// Verifies the signature of `token` with `algorithm`, ignoring every registered claim.
+pub fn verify_signature(token: &str, algorithm: Algorithm, keys: &JwtKeys) -> Result<(), JwtError> {
+    find_verifying_key(token, algorithm, keys).map(|_| ())
+}
+
+/// Verifies the signature of `token` like [`verify_signature`], returning the JWK that verified
+/// it when the public key is a JWK or a JWK Set.
+///
+/// Every key of the set with the `kid` of the token is tried, or every key when it has none.
+pub fn find_verifying_key(
+    token: &str,
+    algorithm: Algorithm,
+    keys: &JwtKeys,
+) -> Result<Option<Jwk>, JwtError> {
+    if is_symmetric(algorithm) || !jwk::is_jwk(&keys.public_key) {
+        let decoding_key = decoding_key(algorithm, keys)?;
+        return verify_with(token, algorithm, &decoding_key).map(|()| None);
+    }
+
+    let key_set = jwk::parse_key_set(&keys.public_key)?;
+    let kid = decode_header(token)?.kid;
+    let candidates = jwk::candidates(&key_set, kid.as_deref());
+    if candidates.is_empty() {
+        return Err(JwtError::NoMatchingKey);
+    }
+    candidates
+        .into_iter()
+        .find(|jwk| {
+            DecodingKey::from_jwk(jwk)
+                .is_ok_and(|decoding_key| verify_with(token, algorithm, &decoding_key).is_ok())
+        })
+        .map(|jwk| Some(jwk.clone()))
+        .ok_or(JwtError::InvalidSignature)
+}
+
+fn verify_with(
+    token: &str,
+    algorithm: Algorithm,
+    decoding_key: &DecodingKey,
+) -> Result<(), JwtError> {
+    let mut validation = Validation::new(algorithm);
+    validation.validate_aud = false;
+    validation.validate_exp = false;
+    validation.set_required_spec_claims::<&str>(&[]);
+    jsonwebtoken::decode::<serde_json::Value>(token.trim().as_bytes(), decoding_key, &validation)
+        .map(|_| ())
+        .map_err(|_| JwtError::InvalidSignature)
+}
+
+/// Parses a JSON token header, which must be an object containing `alg`.
+pub fn parse_header(header_input: &str) -> Result<Header, JwtError> {
+    let header_value = serde_json::from_str::<serde_json::Value>(header_input)
+        .map_err(|_| JwtError::InvalidHeader)?;
+    if !header_value
+        .as_object()
+        .is_some_and(|header_object| header_object.contains_key("alg"))
+    {
+        return Err(JwtError::InvalidHeader);
+    }
+    serde_json::from_value::<Header>(header_value).map_err(|_| JwtError::InvalidHeader)
+}
+
+/// Signs a token with the given header and JSON claims object.
+pub fn encode_token(
+    header: &Header,
+    claims_input: &str,
+    keys: &JwtKeys,
+) -> Result<String, JwtError> {
+    let claims_value = serde_json::from_str::<serde_json::Value>(claims_input)
+        .map_err(|_| JwtError::InvalidClaims)?;
+    if !claims_value.is_object() {
+        return Err(JwtError::InvalidClaims);
+    }
+    let encoding_key = encoding_key(header.alg, keys)?;
+    jsonwebtoken::encode(header, &claims_value, &encoding_key).map_err(|_| JwtError::EncodingFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(symmetric_key: &str, key_encoding: KeyEncoding) -> JwtKeys {
+        JwtKeys {
+            symmetric_key: symmetric_key.to_string(),
+            key_encoding,
+            ..JwtKeys::default()
+        }
+    }
+
+    #[test]
+    fn requires_alg_in_header() {
+        assert_eq!(
+            parse_header(r#"{"typ":"JWT"}"#).unwrap_err(),
+            JwtError::InvalidHeader
+        );
+        assert_eq!(parse_header("[]").unwrap_err(), JwtError::InvalidHeader);
+        assert_eq!(
+            parse_header(r#"{"alg":"none"}"#).unwrap_err(),
+            JwtError::InvalidHeader
+        );
+        assert_eq!(
+            parse_header(r#"{"alg":"HS384"}"#).unwrap().alg,
+            Algorithm::HS384
+        );
+    }
+
+    #[test]
+    fn requires_claims_object() {
+        let header = Header::default();
+        let keys = secret("secret", KeyEncoding::Utf8);
+        assert_eq!(
+            encode_token(&header, "[1]", &keys).unwrap_err(),
+            JwtError::InvalidClaims
+        );
+        assert_eq!(
+            encode_token(&header, "{", &keys).unwrap_err(),
+            JwtError::InvalidClaims
+        );
+    }
+
+    #[test]
+    fn base64_secret_matches_utf8_secret() {
+        let header = Header::default();
+        let utf8 = encode_token(&header, "{}", &secret("secret", KeyEncoding::Utf8)).unwrap();
+        let base64 = encode_token(&header, "{}", &secret("c2VjcmV0", KeyEncoding::Base64)).unwrap();
+        assert_eq!(utf8, base64);
+        assert_eq!(
+            encode_token(&header, "{}", &secret("not base64!", KeyEncoding::Base64)).unwrap_err(),
+            JwtError::InvalidKey
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        assert_eq!(decode_header("abc").unwrap_err(), JwtError::InvalidToken);
+        assert_eq!(decode_claims("a.b.c").unwrap_err(), JwtError::InvalidToken);
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        let keys = secret("secret", KeyEncoding::Utf8);
+        let token = encode_token(&Header::default(), r#"{"admin":false}"#, &keys).unwrap();
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let tampered_claims = "eyJhZG1pbiI6dHJ1ZX0";
+        parts[1] = tampered_claims;
+        assert_eq!(
+            verify_signature(&parts.join("."), Algorithm::HS256, &keys).unwrap_err(),
+            JwtError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn classifies_algorithms() {
+        assert!(is_symmetric(Algorithm::HS512));
+        assert!(!is_symmetric(Algorithm::RS256));
+        assert!(!is_symmetric(Algorithm::EdDSA));
+    }
+}
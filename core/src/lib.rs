@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! GUI-free conversions behind the dev-toolbox utility pages.
+//!
+//! Every function takes typed inputs and returns typed outputs and errors, so the same logic
+//! can drive the COSMIC pages, the command-line interface and the test suite.
+
+pub mod base64;
+pub mod bytes;
+pub mod claims;
+pub mod data_format;
+pub mod detect;
+pub mod diff;
+pub mod fuzzy;
+pub mod gzip;
+pub mod history;
+pub mod jwe;
+pub mod jwk;
+pub mod jwt;
+pub mod key;
+pub mod lorem;
+pub mod pipeline;
+pub mod service;
+pub mod unix_time;
+pub mod url;
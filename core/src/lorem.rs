@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Lorem ipsum placeholder text generation.
+
+use {
+    lipsum::{lipsum_with_rng, lipsum_words_with_rng},
+    rand::{self, Rng},
+};
+
+/// The unit in which the amount of generated text is measured.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LoremUnit {
+    #[default]
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+impl LoremUnit {
+    pub const ALL: [LoremUnit; 3] = [
+        LoremUnit::Words,
+        LoremUnit::Sentences,
+        LoremUnit::Paragraphs,
+    ];
+}
+
+/// Generates `amount` units of lorem ipsum, starting with "Lorem ipsum".
+pub fn generate(unit: LoremUnit, amount: usize) -> String {
+    match unit {
+        LoremUnit::Words => generate_words(amount),
+        LoremUnit::Sentences => generate_sentences(amount),
+        LoremUnit::Paragraphs => generate_paragraphs(amount),
+    }
+}
+
+pub fn generate_words(n: usize) -> String {
+    lipsum_with_rng(rand::thread_rng(), n)
+}
+
+pub fn generate_sentences(n: usize) -> String {
+    generate_sentences_custom(n, true)
+}
+
+pub fn generate_paragraphs(n: usize) -> String {
+    (0..n)
+        .map(|i| {
+            let s_count = 3 + (rand::thread_rng().r#gen::<usize>() / (usize::MAX / 3));
+            generate_sentences_custom(s_count, i == 0)
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn generate_sentences_custom(n: usize, start_with_lorem: bool) -> String {
+    let mut sentences = Vec::with_capacity(n);
+    // An average sentence is ~15-20 words so each chunk should make at least one sentence.
+    while sentences.len() < n {
+        let is_first = start_with_lorem && sentences.is_empty();
+        let chunk = if is_first {
+            lipsum_with_rng(rand::thread_rng(), 25)
+        } else {
+            lipsum_words_with_rng(rand::thread_rng(), 25)
+        };
+        let parts: Vec<&str> = chunk.split_inclusive(['.', '!', '?']).collect();
+        for part in parts {
+            if sentences.len() < n {
+                sentences.push(part.trim().to_string());
+            }
+        }
+    }
+    sentences.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_requested_number_of_words() {
+        let words = generate(LoremUnit::Words, 20);
+        assert!(words.starts_with("Lorem ipsum"));
+        assert_eq!(words.split_whitespace().count(), 20);
+    }
+
+    #[test]
+    fn generates_requested_number_of_paragraphs() {
+        let paragraphs = generate(LoremUnit::Paragraphs, 4);
+        assert!(paragraphs.starts_with("Lorem ipsum"));
+        assert_eq!(paragraphs.split("\n\n").count(), 4);
+    }
+
+    #[test]
+    fn generates_nothing_for_zero() {
+        for unit in LoremUnit::ALL {
+            assert_eq!(generate(unit, 0), "");
+        }
+    }
+}
@@ -0,0 +1,288 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Recipes chaining the conversions of the other tools, each step taking the previous output.
+//!
+//! Steps pass bytes to each other, so binary data survives decoding and decompression.
+
+use {
+    crate::{
+        base64,
+        bytes::ByteBuffer,
+        data_format::{self, ConversionError, DataFormat, INDENTS},
+        gzip::{self, DecompressError},
+        jwt::{self, JwtError},
+        url,
+    },
+    serde::{Deserialize, Serialize},
+    std::str::Utf8Error,
+};
+
+/// One operation of a recipe, serialized with its name in the `operation` field.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "operation", rename_all = "kebab-case")]
+pub enum Step {
+    Base64Encode {
+        #[serde(default)]
+        url_safe: bool,
+    },
+    Base64Decode {
+        #[serde(default)]
+        url_safe: bool,
+    },
+    GzipCompress,
+    GzipDecompress,
+    UrlEncode,
+    UrlDecode,
+    ConvertFormat {
+        from: DataFormat,
+        to: DataFormat,
+        #[serde(default = "default_indent")]
+        indent: usize,
+    },
+    /// Prints the header and claims of a token as JSON, without verifying its signature.
+    JwtDecode,
+}
+
+fn default_indent() -> usize {
+    INDENTS[0]
+}
+
+impl Step {
+    /// Every operation with its default options.
+    pub const ALL: [Step; 8] = [
+        Step::Base64Encode { url_safe: false },
+        Step::Base64Decode { url_safe: false },
+        Step::GzipCompress,
+        Step::GzipDecompress,
+        Step::UrlEncode,
+        Step::UrlDecode,
+        Step::ConvertFormat {
+            from: DataFormat::Json,
+            to: DataFormat::Json,
+            indent: INDENTS[0],
+        },
+        Step::JwtDecode,
+    ];
+
+    /// The operation name, as written in recipe files.
+    pub fn id(self) -> &'static str {
+        match self {
+            Step::Base64Encode { .. } => "base64-encode",
+            Step::Base64Decode { .. } => "base64-decode",
+            Step::GzipCompress => "gzip-compress",
+            Step::GzipDecompress => "gzip-decompress",
+            Step::UrlEncode => "url-encode",
+            Step::UrlDecode => "url-decode",
+            Step::ConvertFormat { .. } => "convert-format",
+            Step::JwtDecode => "jwt-decode",
+        }
+    }
+
+    /// Applies the step to the output of the previous one.
+    pub fn run(self, input: &ByteBuffer) -> Result<ByteBuffer, StepError> {
+        Ok(match self {
+            Step::Base64Encode { url_safe } => {
+                base64::encode_bytes(input.as_bytes(), url_safe).into()
+            }
+            Step::Base64Decode { url_safe } => base64::decode_bytes(input.as_bytes(), url_safe)?,
+            Step::GzipCompress => gzip::compress_bytes(input.as_bytes()).into(),
+            Step::GzipDecompress => gzip::decompress_bytes(input.as_bytes())?,
+            Step::UrlEncode => url::encode_bytes(input.as_bytes()).into(),
+            Step::UrlDecode => url::decode_bytes(input.as_bytes()),
+            Step::ConvertFormat { from, to, indent } => {
+                data_format::convert(input.as_text()?, from, to, indent)?.into()
+            }
+            Step::JwtDecode => {
+                let token = input.as_text()?.trim();
+                let header = jwt::decode_header(token)?;
+                let claims = jwt::decode_claims(token)?;
+                serde_json::to_string_pretty(
+                    &serde_json::json!({ "header": header, "claims": claims }),
+                )
+                .expect("a JSON value always serializes")
+                .into()
+            }
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StepError {
+    #[error("Base64 decoding error: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error(transparent)]
+    Gzip(#[from] DecompressError),
+    #[error("Input is not UTF-8 text: {0}")]
+    NotText(#[from] Utf8Error),
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+    #[error(transparent)]
+    Jwt(#[from] JwtError),
+}
+
+/// A list of steps that can be shared as a TOML or JSON file.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Recipe {
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecipeError {
+    #[error(transparent)]
+    Parse(#[from] ConversionError),
+    #[error("Invalid recipe: {0}")]
+    Invalid(#[from] serde_json::Error),
+}
+
+impl Recipe {
+    /// Runs the steps in order, stopping after the first one that fails.
+    ///
+    /// Returns one result per step that ran.
+    pub fn run(&self, mut input: ByteBuffer) -> Vec<Result<ByteBuffer, StepError>> {
+        let mut results: Vec<Result<ByteBuffer, StepError>> = Vec::with_capacity(self.steps.len());
+        for step in &self.steps {
+            match step.run(&input) {
+                Ok(output) => {
+                    input.clone_from(&output);
+                    results.push(Ok(output));
+                }
+                Err(err) => {
+                    results.push(Err(err));
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    /// Reads a recipe written in the given document format.
+    pub fn parse(input: &str, format: DataFormat) -> Result<Self, RecipeError> {
+        Ok(serde_json::from_value(data_format::parse(input, format)?)?)
+    }
+
+    /// Writes the recipe in the given document format.
+    pub fn serialize(&self, format: DataFormat) -> Result<String, RecipeError> {
+        let value = serde_json::to_value(self)?;
+        Ok(data_format::serialize(&value, format, INDENTS[0])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe() -> Recipe {
+        Recipe {
+            steps: vec![
+                Step::UrlDecode,
+                Step::GzipDecompress,
+                Step::Base64Decode { url_safe: false },
+                Step::ConvertFormat {
+                    from: DataFormat::Json,
+                    to: DataFormat::Yaml,
+                    indent: 2,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn runs_steps_in_order() {
+        let payload = url::encode(&gzip::compress(&base64::encode(r#"{"a":[1,2]}"#, false)));
+        let results = recipe().run(payload.into());
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results[3].as_ref().unwrap().as_text(),
+            Ok("a:\n  - 1\n  - 2\n")
+        );
+    }
+
+    #[test]
+    fn stops_at_the_first_failing_step() {
+        let results = recipe().run("not%20gzip!".into());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().as_text(), Ok("not gzip!"));
+        assert!(matches!(results[1], Err(StepError::Gzip(_))));
+    }
+
+    #[test]
+    fn decodes_jwt() {
+        let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxMjM0NTY3ODkwIn0.sig";
+        let output = Step::JwtDecode.run(&token.into()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(output.as_bytes()).unwrap();
+        assert_eq!(value["header"]["alg"], "HS256");
+        assert_eq!(value["claims"]["sub"], "1234567890");
+    }
+
+    #[test]
+    fn passes_binary_data_between_steps() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let recipe = Recipe {
+            steps: vec![
+                Step::GzipCompress,
+                Step::Base64Decode { url_safe: false },
+                Step::GzipDecompress,
+                Step::UrlEncode,
+                Step::UrlDecode,
+            ],
+        };
+        let results = recipe.run(bytes.clone().into());
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[4].as_ref().unwrap().as_bytes(), bytes);
+        assert!(matches!(
+            Step::JwtDecode.run(&bytes.into()),
+            Err(StepError::NotText(_))
+        ));
+    }
+
+    #[test]
+    fn round_trips_recipe_files() {
+        for format in [DataFormat::Toml, DataFormat::Json] {
+            let serialized = recipe().serialize(format).unwrap();
+            assert_eq!(
+                Recipe::parse(&serialized, format).unwrap(),
+                recipe(),
+                "{format}:\n{serialized}"
+            );
+        }
+    }
+
+    #[test]
+    fn reads_hand_written_toml() {
+        let recipe = Recipe::parse(
+            "[[steps]]\noperation = \"base64-decode\"\n\n[[steps]]\noperation = \"convert-format\"\nfrom = \"yaml\"\nto = \"json\"\n",
+            DataFormat::Toml,
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.steps,
+            [
+                Step::Base64Decode { url_safe: false },
+                Step::ConvertFormat {
+                    from: DataFormat::Yaml,
+                    to: DataFormat::Json,
+                    indent: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_operations() {
+        assert!(matches!(
+            Recipe::parse(r#"{"steps":[{"operation":"rot13"}]}"#, DataFormat::Json),
+            Err(RecipeError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn lists_every_operation_once() {
+        for (index, step) in Step::ALL.iter().enumerate() {
+            assert_eq!(
+                Step::ALL.iter().position(|other| other.id() == step.id()),
+                Some(index)
+            );
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Conversions requested by other programs through the names of a tool and one of its
+//! operations, with options given as strings.
+//!
+//! Tools are named like the subcommands of the command line, and can be opened with their input
+//! through `devtoolbox://<tool>/<operation>?data=<input>` URIs.
+
+use {
+    crate::{
+        bytes::ByteBuffer,
+        data_format::{DataFormat, INDENTS},
+        lorem::{self, LoremUnit},
+        pipeline::{Step, StepError},
+        unix_time::{self, TimestampUnit, UnixTimeError},
+        url,
+    },
+    chrono::{FixedOffset, Local},
+    std::{collections::HashMap, fmt, str::FromStr},
+};
+
+/// The scheme of the URIs opening a tool.
+pub const URI_SCHEME: &str = "devtoolbox";
+
+/// Units generated by the Lorem tool unless the `amount` option says otherwise.
+const DEFAULT_LOREM_AMOUNT: usize = 20;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tool {
+    Base64,
+    Convert,
+    Gzip,
+    Url,
+    Jwt,
+    Lorem,
+    Time,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 7] = [
+        Tool::Base64,
+        Tool::Convert,
+        Tool::Gzip,
+        Tool::Url,
+        Tool::Jwt,
+        Tool::Lorem,
+        Tool::Time,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Base64 => "base64",
+            Tool::Convert => "convert",
+            Tool::Gzip => "gzip",
+            Tool::Url => "url",
+            Tool::Jwt => "jwt",
+            Tool::Lorem => "lorem",
+            Tool::Time => "time",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Tool::ALL.into_iter().find(|tool| tool.name() == name)
+    }
+
+    /// The names of the operations of the tool, the default one first.
+    ///
+    /// The operations of Convert are the formats it converts to, and those of Lorem the units
+    /// it generates.
+    pub fn operations(self) -> &'static [&'static str] {
+        match self {
+            Tool::Base64 | Tool::Url => &["encode", "decode"],
+            Tool::Convert => &["json", "yaml", "xml", "toml"],
+            Tool::Gzip => &["compress", "decompress"],
+            Tool::Jwt => &["decode"],
+            Tool::Lorem => &["words", "sentences", "paragraphs"],
+            Tool::Time => &["format"],
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("Unknown tool {0:?}")]
+    UnknownTool(String),
+    #[error("Unknown operation {operation:?} of {tool}, expected one of {expected}")]
+    UnknownOperation {
+        tool: &'static str,
+        operation: String,
+        expected: String,
+    },
+    #[error("Invalid value {value:?} of option {name}")]
+    InvalidOption { name: String, value: String },
+    #[error(transparent)]
+    Step(#[from] StepError),
+    #[error(transparent)]
+    UnixTime(#[from] UnixTimeError),
+    #[error("Output is binary data, not UTF-8 text")]
+    BinaryOutput,
+    #[error("Invalid URI {0:?}, expected {URI_SCHEME}://<tool>/<operation>?data=<input>")]
+    InvalidUri(String),
+}
+
+/// A tool to show with one of its operations selected and an input filled in.
+///
+/// Displays as the URI it is parsed from, with the input percent-encoded in the `data` query
+/// parameter.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ToolRequest {
+    pub tool: Tool,
+    pub operation: String,
+    pub input: String,
+}
+
+impl ToolRequest {
+    /// Checks that the operation belongs to the tool, selecting its default one when omitted.
+    pub fn new(tool: Tool, operation: Option<&str>, input: String) -> Result<Self, ServiceError> {
+        let operation = operation.unwrap_or(tool.operations()[0]);
+        check_operation(tool, operation)?;
+        Ok(Self {
+            tool,
+            operation: operation.to_string(),
+            input,
+        })
+    }
+}
+
+impl fmt::Display for ToolRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{URI_SCHEME}://{}/{}?data={}",
+            self.tool.name(),
+            self.operation,
+            url::encode(&self.input)
+        )
+    }
+}
+
+impl FromStr for ToolRequest {
+    type Err = ServiceError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let invalid = || ServiceError::InvalidUri(uri.to_string());
+        let rest = uri
+            .strip_prefix(URI_SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(invalid)?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let query = query.split_once('#').map_or(query, |(query, _)| query);
+        let (tool, operation) = match path.trim_end_matches('/').split_once('/') {
+            Some((tool, operation)) => (tool, Some(operation)),
+            None => (path.trim_end_matches('/'), None),
+        };
+        let tool =
+            Tool::from_name(tool).ok_or_else(|| ServiceError::UnknownTool(tool.to_string()))?;
+
+        let mut input = String::new();
+        for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            if name == "data" {
+                input = url::decode(value).map_err(|_| invalid())?;
+            }
+        }
+        Self::new(tool, operation, input)
+    }
+}
+
+/// Runs an operation of a tool on `input`, returning the output text.
+///
+/// The options are `url-safe` for Base64, `from` and `indent` for Convert, `amount` for Lorem,
+/// and `unit` and `offset` for Time. Time formats the current time when the input is empty.
+pub fn convert(
+    tool: &str,
+    operation: &str,
+    options: &HashMap<String, String>,
+    input: &str,
+) -> Result<String, ServiceError> {
+    let tool = Tool::from_name(tool).ok_or_else(|| ServiceError::UnknownTool(tool.to_string()))?;
+    check_operation(tool, operation)?;
+
+    let step = match (tool, operation) {
+        (Tool::Base64, "encode") => Step::Base64Encode {
+            url_safe: option(options, "url-safe", str::parse)?.unwrap_or_default(),
+        },
+        (Tool::Base64, _) => Step::Base64Decode {
+            url_safe: option(options, "url-safe", str::parse)?.unwrap_or_default(),
+        },
+        (Tool::Convert, to) => Step::ConvertFormat {
+            from: option(options, "from", parse_format)?.unwrap_or_default(),
+            to: parse_format(to).expect("operations of Convert are formats"),
+            indent: option(options, "indent", |indent| {
+                indent
+                    .parse()
+                    .ok()
+                    .filter(|indent| INDENTS.contains(indent))
+                    .ok_or(())
+            })?
+            .unwrap_or(INDENTS[0]),
+        },
+        (Tool::Gzip, "compress") => Step::GzipCompress,
+        (Tool::Gzip, _) => Step::GzipDecompress,
+        (Tool::Url, "encode") => Step::UrlEncode,
+        (Tool::Url, _) => Step::UrlDecode,
+        (Tool::Jwt, _) => Step::JwtDecode,
+        (Tool::Lorem, unit) => {
+            let unit = match unit {
+                "words" => LoremUnit::Words,
+                "sentences" => LoremUnit::Sentences,
+                _ => LoremUnit::Paragraphs,
+            };
+            let amount = option(options, "amount", str::parse)?.unwrap_or(DEFAULT_LOREM_AMOUNT);
+            return Ok(lorem::generate(unit, amount));
+        }
+        (Tool::Time, _) => return format_time(input, options),
+    };
+
+    let output = step.run(&ByteBuffer::from(input))?;
+    output
+        .as_text()
+        .map(str::to_string)
+        .map_err(|_| ServiceError::BinaryOutput)
+}
+
+fn check_operation(tool: Tool, operation: &str) -> Result<(), ServiceError> {
+    if tool.operations().contains(&operation) {
+        Ok(())
+    } else {
+        Err(ServiceError::UnknownOperation {
+            tool: tool.name(),
+            operation: operation.to_string(),
+            expected: tool.operations().join(", "),
+        })
+    }
+}
+
+/// Formats a timestamp like the Unix Time Converter, one format per line.
+fn format_time(input: &str, options: &HashMap<String, String>) -> Result<String, ServiceError> {
+    let unit = option(options, "unit", |unit| match unit {
+        "seconds" => Ok(TimestampUnit::Seconds),
+        "milliseconds" => Ok(TimestampUnit::Milliseconds),
+        _ => Err(()),
+    })?
+    .unwrap_or_default();
+    let offset =
+        option(options, "offset", FixedOffset::from_str)?.unwrap_or_else(|| *Local::now().offset());
+    let timestamp = match input.trim() {
+        "" => unit.now(),
+        timestamp => timestamp.parse().map_err(|_| ServiceError::InvalidOption {
+            name: "input".to_string(),
+            value: timestamp.to_string(),
+        })?,
+    };
+
+    let formatted_time = unix_time::format_timestamp(timestamp, unit, offset.local_minus_utc())?;
+    Ok([
+        formatted_time.iso_8601,
+        formatted_time.email,
+        formatted_time.dmy,
+        formatted_time.human_readable,
+    ]
+    .join("\n"))
+}
+
+/// Parses an option when it is given.
+fn option<T, E>(
+    options: &HashMap<String, String>,
+    name: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<Option<T>, ServiceError> {
+    options
+        .get(name)
+        .map(|value| {
+            parse(value).map_err(|_| ServiceError::InvalidOption {
+                name: name.to_string(),
+                value: value.clone(),
+            })
+        })
+        .transpose()
+}
+
+/// A format by its file extension, as used in recipes.
+fn parse_format(name: &str) -> Result<DataFormat, ()> {
+    DataFormat::ALL
+        .into_iter()
+        .find(|format| format.extension() == name)
+        .ok_or(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(options: &[(&str, &str)]) -> HashMap<String, String> {
+        options
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn runs_operations_by_name() {
+        let none = HashMap::new();
+        assert_eq!(
+            convert("base64", "encode", &none, "hello").unwrap(),
+            "aGVsbG8="
+        );
+        assert_eq!(
+            convert(
+                "base64",
+                "decode",
+                &options(&[("url-safe", "true")]),
+                "aGk_"
+            )
+            .unwrap(),
+            "hi?"
+        );
+        assert_eq!(convert("url", "decode", &none, "a%20b").unwrap(), "a b");
+        assert_eq!(
+            convert(
+                "convert",
+                "yaml",
+                &options(&[("from", "json")]),
+                r#"{"a":[1,2]}"#
+            )
+            .unwrap(),
+            "a:\n  - 1\n  - 2\n"
+        );
+        let compressed = convert("gzip", "compress", &none, "text").unwrap();
+        assert_eq!(
+            convert("gzip", "decompress", &none, &compressed).unwrap(),
+            "text"
+        );
+        assert_eq!(
+            convert("lorem", "words", &options(&[("amount", "2")]), "").unwrap(),
+            "Lorem ipsum."
+        );
+        assert!(
+            convert("time", "format", &options(&[("offset", "+00:00")]), "0")
+                .unwrap()
+                .starts_with("1970-01-01T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_names_and_options() {
+        let none = HashMap::new();
+        assert!(matches!(
+            convert("rot13", "encode", &none, ""),
+            Err(ServiceError::UnknownTool(_))
+        ));
+        assert!(matches!(
+            convert("gzip", "encode", &none, ""),
+            Err(ServiceError::UnknownOperation { .. })
+        ));
+        assert!(matches!(
+            convert("convert", "json", &options(&[("indent", "3")]), "{}"),
+            Err(ServiceError::InvalidOption { .. })
+        ));
+        assert!(matches!(
+            convert("base64", "decode", &none, "/w=="),
+            Err(ServiceError::BinaryOutput)
+        ));
+    }
+
+    #[test]
+    fn parses_and_displays_uris() {
+        let request: ToolRequest = "devtoolbox://base64/decode?data=aGk%3D".parse().unwrap();
+        assert_eq!(
+            request,
+            ToolRequest {
+                tool: Tool::Base64,
+                operation: "decode".to_string(),
+                input: "aGk=".to_string(),
+            }
+        );
+        assert_eq!(
+            request.to_string(),
+            "devtoolbox://base64/decode?data=aGk%3D"
+        );
+
+        let request: ToolRequest = "devtoolbox://jwt".parse().unwrap();
+        assert_eq!(request.operation, "decode");
+        assert_eq!(request.input, "");
+
+        let request = ToolRequest::new(Tool::Url, None, "a b&c=d".to_string()).unwrap();
+        assert_eq!(request.to_string().parse::<ToolRequest>().unwrap(), request);
+
+        assert!(matches!(
+            "https://base64/decode".parse::<ToolRequest>(),
+            Err(ServiceError::InvalidUri(_))
+        ));
+        assert!(matches!(
+            "devtoolbox://gzip/encode".parse::<ToolRequest>(),
+            Err(ServiceError::UnknownOperation { .. })
+        ));
+    }
+}
@@ -0,0 +1,315 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Conversion of Unix timestamps into human readable dates.
+
+use chrono::{DateTime, Datelike, FixedOffset, Local};
+
+/// Offset seconds east of UTC
+pub const UTC_OFFSET_SECONDS: &[i32; 38] = &[
+    -12 * 3600,
+    -11 * 3600,
+    -10 * 3600,
+    -9 * 3600 - 30 * 60,
+    -9 * 3600,
+    -8 * 3600,
+    -7 * 3600,
+    -6 * 3600,
+    -5 * 3600,
+    -4 * 3600,
+    -3 * 3600 - 30 * 60,
+    -3 * 3600,
+    -2 * 3600,
+    -3600,
+    0,
+    3600,
+    2 * 3600,
+    3 * 3600,
+    3 * 3600 + 30 * 60,
+    4 * 3600,
+    4 * 3600 + 30 * 60,
+    5 * 3600,
+    5 * 3600 + 30 * 60,
+    5 * 3600 + 45 * 60,
+    6 * 3600,
+    6 * 3600 + 30 * 60,
+    7 * 3600,
+    8 * 3600,
+    8 * 3600 + 45 * 60,
+    9 * 3600,
+    9 * 3600 + 30 * 60,
+    10 * 3600,
+    10 * 3600 + 30 * 60,
+    11 * 3600,
+    12 * 3600,
+    12 * 3600 + 45 * 60,
+    13 * 3600,
+    14 * 3600,
+];
+
+/// The resolution of a Unix timestamp.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TimestampUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+}
+
+impl TimestampUnit {
+    pub const ALL: [TimestampUnit; 2] = [TimestampUnit::Seconds, TimestampUnit::Milliseconds];
+
+    /// Rescales a timestamp in this unit to `target`, truncating sub-second precision.
+    pub fn convert(self, timestamp: i64, target: TimestampUnit) -> i64 {
+        match (self, target) {
+            (TimestampUnit::Seconds, TimestampUnit::Milliseconds) => timestamp.saturating_mul(1000),
+            (TimestampUnit::Milliseconds, TimestampUnit::Seconds) => timestamp / 1000,
+            _ => timestamp,
+        }
+    }
+
+    /// The current time in this unit.
+    pub fn now(self) -> i64 {
+        match self {
+            TimestampUnit::Seconds => Local::now().timestamp(),
+            TimestampUnit::Milliseconds => Local::now().timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum UnixTimeError {
+    #[error("Timestamp out of range: {0}")]
+    OutOfRange(i64),
+    #[error("Invalid UTC offset: {0} seconds")]
+    InvalidOffset(i32),
+}
+
+/// A Unix timestamp rendered in each of the formats shown on the page.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FormattedTime {
+    pub iso_8601: String,
+    /// Empty for years that RFC 2822 cannot represent.
+    pub email: String,
+    pub dmy: String,
+    pub human_readable: String,
+}
+
+/// Formats `offset` as `UTC±HH:MM`.
+pub fn format_offset(offset: FixedOffset) -> String {
+    let secs = offset.local_minus_utc();
+
+    let sign = if secs >= 0 { '+' } else { '-' };
+    let secs = secs.abs();
+
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+
+    format!("UTC{}{:02}:{:02}", sign, hours, minutes)
+}
+
+/// The position in [`UTC_OFFSET_SECONDS`] closest to the local timezone.
+pub fn find_current_timezone_position() -> usize {
+    let current_offset = Local::now().offset().local_minus_utc();
+    match UTC_OFFSET_SECONDS.binary_search(&current_offset) {
+        Ok(position) => position,
+        Err(position) => position.min(UTC_OFFSET_SECONDS.len() - 1),
+    }
+}
+
+/// Formats a Unix timestamp in the timezone `offset_seconds` east of UTC.
+pub fn format_timestamp(
+    timestamp: i64,
+    unit: TimestampUnit,
+    offset_seconds: i32,
+) -> Result<FormattedTime, UnixTimeError> {
+    let date_time = match unit {
+        TimestampUnit::Seconds => DateTime::from_timestamp_secs(timestamp),
+        TimestampUnit::Milliseconds => DateTime::from_timestamp_millis(timestamp),
+    }
+    .ok_or(UnixTimeError::OutOfRange(timestamp))?;
+    let offset = FixedOffset::east_opt(offset_seconds)
+        .ok_or(UnixTimeError::InvalidOffset(offset_seconds))?;
+    let date_time_with_offset = date_time.with_timezone(&offset);
+
+    let email = if date_time_with_offset.year() >= 0 && date_time_with_offset.year() < 10000 {
+        date_time_with_offset.to_rfc2822()
+    } else {
+        String::new()
+    };
+
+    Ok(FormattedTime {
+        iso_8601: date_time_with_offset.to_rfc3339(),
+        email,
+        dmy: date_time_with_offset.format("%d/%m/%Y").to_string(),
+        human_readable: date_time_with_offset.format("%b %e, %l:%M %p").to_string(),
+    })
+}
+
+/// A unit of the lengths of time split by [`duration_parts`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DurationUnit {
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl DurationUnit {
+    pub const ALL: [DurationUnit; 4] = [
+        DurationUnit::Days,
+        DurationUnit::Hours,
+        DurationUnit::Minutes,
+        DurationUnit::Seconds,
+    ];
+
+    pub fn seconds(self) -> u64 {
+        match self {
+            DurationUnit::Days => 86_400,
+            DurationUnit::Hours => 3600,
+            DurationUnit::Minutes => 60,
+            DurationUnit::Seconds => 1,
+        }
+    }
+}
+
+/// Splits a length of time into its two largest units, such as 3 hours and 12 minutes, leaving
+/// out the second one when it counts zero.
+pub fn duration_parts(seconds: u64) -> Vec<(u64, DurationUnit)> {
+    let Some(largest) = DurationUnit::ALL
+        .iter()
+        .position(|unit| seconds >= unit.seconds())
+    else {
+        return vec![(0, DurationUnit::Seconds)];
+    };
+    let unit = DurationUnit::ALL[largest];
+    let mut parts = vec![(seconds / unit.seconds(), unit)];
+    if let Some(&next_unit) = DurationUnit::ALL.get(largest + 1) {
+        let count = seconds % unit.seconds() / next_unit.seconds();
+        if count > 0 {
+            parts.push((count, next_unit));
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_epoch() {
+        assert_eq!(
+            format_timestamp(0, TimestampUnit::Seconds, 0).unwrap(),
+            FormattedTime {
+                iso_8601: "1970-01-01T00:00:00+00:00".to_string(),
+                email: "Thu, 1 Jan 1970 00:00:00 +0000".to_string(),
+                dmy: "01/01/1970".to_string(),
+                human_readable: "Jan  1, 12:00 AM".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn applies_fractional_offsets() {
+        let formatted =
+            format_timestamp(1_700_000_000, TimestampUnit::Seconds, 5 * 3600 + 45 * 60).unwrap();
+        assert_eq!(formatted.iso_8601, "2023-11-15T03:58:20+05:45");
+        assert_eq!(formatted.email, "Wed, 15 Nov 2023 03:58:20 +0545");
+    }
+
+    #[test]
+    fn offsets_can_cross_date_boundaries() {
+        let formatted = format_timestamp(0, TimestampUnit::Seconds, -12 * 3600).unwrap();
+        assert_eq!(formatted.dmy, "31/12/1969");
+        assert_eq!(formatted.human_readable, "Dec 31, 12:00 PM");
+    }
+
+    #[test]
+    fn keeps_milliseconds() {
+        let formatted =
+            format_timestamp(1_700_000_000_123, TimestampUnit::Milliseconds, 0).unwrap();
+        assert_eq!(formatted.iso_8601, "2023-11-14T22:13:20.123+00:00");
+    }
+
+    #[test]
+    fn formats_negative_timestamps() {
+        let formatted = format_timestamp(-1, TimestampUnit::Seconds, 0).unwrap();
+        assert_eq!(formatted.iso_8601, "1969-12-31T23:59:59+00:00");
+    }
+
+    #[test]
+    fn omits_email_date_outside_four_digit_years() {
+        let year_10000 = 253_402_300_800;
+        let formatted = format_timestamp(year_10000, TimestampUnit::Seconds, 0).unwrap();
+        assert_eq!(formatted.iso_8601, "+10000-01-01T00:00:00+00:00");
+        assert_eq!(formatted.email, "");
+    }
+
+    #[test]
+    fn rejects_out_of_range_timestamps() {
+        assert_eq!(
+            format_timestamp(i64::MAX, TimestampUnit::Seconds, 0),
+            Err(UnixTimeError::OutOfRange(i64::MAX))
+        );
+        assert!(format_timestamp(i64::MIN, TimestampUnit::Milliseconds, 0).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_offsets() {
+        assert_eq!(
+            format_timestamp(0, TimestampUnit::Seconds, 86_400),
+            Err(UnixTimeError::InvalidOffset(86_400))
+        );
+    }
+
+    #[test]
+    fn converts_between_units() {
+        assert_eq!(
+            TimestampUnit::Seconds.convert(1_700_000_000, TimestampUnit::Milliseconds),
+            1_700_000_000_000
+        );
+        assert_eq!(
+            TimestampUnit::Milliseconds.convert(1_700_000_000_999, TimestampUnit::Seconds),
+            1_700_000_000
+        );
+        assert_eq!(
+            TimestampUnit::Seconds.convert(i64::MAX, TimestampUnit::Milliseconds),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn formats_offsets() {
+        assert_eq!(
+            format_offset(FixedOffset::east_opt(0).unwrap()),
+            "UTC+00:00"
+        );
+        assert_eq!(
+            format_offset(FixedOffset::east_opt(-(3 * 3600 + 30 * 60)).unwrap()),
+            "UTC-03:30"
+        );
+        assert_eq!(
+            format_offset(FixedOffset::east_opt(12 * 3600 + 45 * 60).unwrap()),
+            "UTC+12:45"
+        );
+    }
+
+    #[test]
+    fn offsets_are_sorted_for_binary_search() {
+        assert!(UTC_OFFSET_SECONDS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn splits_durations_into_their_two_largest_units() {
+        assert_eq!(duration_parts(0), [(0, DurationUnit::Seconds)]);
+        assert_eq!(duration_parts(45), [(45, DurationUnit::Seconds)]);
+        assert_eq!(
+            duration_parts(3 * 3600 + 12 * 60 + 5),
+            [(3, DurationUnit::Hours), (12, DurationUnit::Minutes)]
+        );
+        assert_eq!(duration_parts(2 * 86_400 + 30), [(2, DurationUnit::Days)]);
+        assert_eq!(
+            duration_parts(86_400 + 4 * 3600),
+            [(1, DurationUnit::Days), (4, DurationUnit::Hours)]
+        );
+    }
+}
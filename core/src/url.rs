@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Percent-encoding and decoding of URL components.
+
+use crate::bytes::ByteBuffer;
+
+pub use std::string::FromUtf8Error as DecodeError;
+
+/// Percent-encodes every character outside the unreserved set.
+pub fn encode(input: &str) -> String {
+    urlencoding::encode(input).into_owned()
+}
+
+/// Percent-encodes every byte outside the unreserved set.
+pub fn encode_bytes(input: &[u8]) -> String {
+    urlencoding::encode_binary(input).into_owned()
+}
+
+/// Decodes percent-encoded input, failing if the result is not valid UTF-8.
+pub fn decode(input: &str) -> Result<String, DecodeError> {
+    urlencoding::decode(input).map(|decoded_url| decoded_url.into_owned())
+}
+
+/// Decodes percent-encoded input into the bytes it escapes.
+pub fn decode_bytes(input: &[u8]) -> ByteBuffer {
+    urlencoding::decode_binary(input).into_owned().into()
+}
+
+/// Whether `encoded` decodes to `plain` and `plain` encodes to `encoded`.
+///
+/// Fails for input escaping characters that need no escaping, or written with lowercase hex
+/// digits, as the encoder writes neither.
+pub fn is_round_trip(encoded: &str, plain: &[u8]) -> bool {
+    encode_bytes(plain) == encoded && decode_bytes(encoded.as_bytes()).as_bytes() == plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_reserved_characters() {
+        assert_eq!(
+            encode("https://example.com/a b?q=1&r=ü"),
+            "https%3A%2F%2Fexample.com%2Fa%20b%3Fq%3D1%26r%3D%C3%BC"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let input = "name=Jöhn Doe&tags=[a,b]";
+        assert_eq!(decode(&encode(input)).unwrap(), input);
+    }
+
+    #[test]
+    fn leaves_plus_signs_alone() {
+        assert_eq!(decode("a+b%2Bc").unwrap(), "a+b+c");
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        assert!(decode("%FF%FE").is_err());
+    }
+
+    #[test]
+    fn round_trips_binary_data() {
+        let bytes = [0xff, 0xfe, b'a', b' '];
+        assert_eq!(encode_bytes(&bytes), "%FF%FEa%20");
+        assert_eq!(
+            decode_bytes(encode_bytes(&bytes).as_bytes()).as_bytes(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn checks_round_trips() {
+        assert!(is_round_trip("a%20b", b"a b"));
+        assert!(!is_round_trip("%61%20b", b"a b"));
+        assert!(!is_round_trip("a%2fb", b"a/b"));
+    }
+}
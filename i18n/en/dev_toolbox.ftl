@@ -2,17 +2,107 @@ app-title = OmniDev
 about = About
 repository = Repository
 view = View
+edit = Edit
+tools = Tools
+remember-inputs = Remember Inputs
 options = Options
 input = Input
 output = Output
-status = Status
 copy = Copy
 paste = Paste
 convert = Convert
+swap = Swap Input and Output
+copy-output = Copy Output
+paste-input = Paste into Input
+clear = Clear
+binary-output = Binary output cannot be swapped into the input
+round-trip = Round Trip
+    .ok = The output converts back to the input
+    .changed = The output converts back to a different input
+next-tool = Next Tool
+previous-tool = Previous Tool
+open-in-new-window = Open in New Window
+new-tab = New Tab
+close-tab = Close Tab
+rename-tab = Rename Tab
+    .title = Title
+    .untitled = Tab { $number }
+rename = Rename
+sessions = Sessions
+    .name = Session name
+    .save = Save
+    .empty = No saved sessions.
+    .restore = Restore
+    .delete = Delete
+    .saved = Session saved
+    .restored = Session restored
+    .save-failed = Cannot save the session
+    .open-failed = Cannot open the session
+    .invalid-name = Session names cannot be empty, start with a dot or contain slashes
+    .no-data-directory = No data directory to keep sessions in
+manage-tools = Manage Tools
+    .move-up = Move Up
+    .move-down = Move Down
+keyboard-shortcuts = Keyboard Shortcuts
+live-convert = Convert as You Type
+converting = Converting…
+conversion-failed = The conversion failed
+cancel = Cancel
+large-input = Large input, not converted automatically
+open-file = Open File…
+open-failed = Cannot open the file
+save-as-file = Save as File…
+saved-to-file = Saved to file
+save-failed = Cannot save the file
+not-a-local-file = The selected location is not a local file
+rendering = Rendering
+    .text = Text
+    .hex-dump = Hex Dump
+    .escaped = Escaped
+paste-anywhere = Paste Anywhere
+    .did-you-mean = Did you mean
+    .open = Open
+    .empty = The clipboard is empty.
+    .unrecognized = No tool recognizes the clipboard content.
+    .jwt = JSON Web Token
+    .gzip = GZip data (Base64)
+    .percent-encoded = Percent-encoded text
+    .unix-seconds = Unix timestamp in seconds
+    .unix-milliseconds = Unix timestamp in milliseconds
+    .base64 = Base64 text
+command-palette = Command Palette
+    .placeholder = Search tools and actions
+    .no-results = No matching tools or actions.
+    .convert = Convert { $from } to { $to }
+    .format = Format { $format }
+    .sign = Sign with { $algorithm }
+    .compare = Compare { $mode }
+    .generate-key = Generate { $key_type } Key
+    .convert-key = Convert Key to { $format }
+history = History
+    .keep = Keep History for This Tool
+    .empty = Nothing converted yet.
+    .clear = Clear History
+    .pin = Pin
+    .unpin = Unpin
+    .restore = Restore
+    .remove = Remove
+document = { $format } document
 encode = Encode
 decode = Decode
 compress = Compress
 decompress = Decompress
+invalid-document = Invalid { $format }
+invalid-document-at = Invalid { $format } at line { $line }, column { $column }
+conversion-failed = Cannot convert to { $format }
+invalid-base64 = Invalid Base64
+invalid-gzip = Invalid GZip data
+invalid-utf-8 = Decoded text is not valid UTF-8
+not-text = Input is binary data, not UTF-8 text
+invalid-number = Not a valid number
+invalid-timestamp = Not a valid Unix timestamp
+invalid-timezone = Invalid timezone
+timestamp-out-of-range = Timestamp is outside the supported date range
 unix-time-converter = Unix Time Converter
     .unix-timestamp = Unix Timestamp
     .epoch-seconds = Epoch seconds
@@ -24,6 +114,7 @@ unix-time-converter = Unix Time Converter
     .now = Now
 data-converter-formatter = Data Converter/Formatter
     .spaces = spaces
+    .show-diff = Show Diff
 base64-string-encoder-decoder = Base64 String Encoder/Decoder
     .url-safe = URL-Safe
 gzip-compressor-decompressor = GZip Compressor/Decompressor
@@ -33,12 +124,15 @@ jwt-debugger = JWT Debugger
     .token = Token
     .header = Header
     .claims = Claims
+    .encryption-header = Encryption Header
+    .encrypt = Encrypt (JWE)
+    .remember-token = Remember Token
     .key-encoding = Key Encoding
     .utf-8 = UTF-8
     .base64 = Base64
     .symmetric-key = Symmetric Key
-    .public-key = Public Key (PEM)
-    .private-key = Private Key (PEM)
+    .public-key = Public Key (PEM, DER, JWK, JWKS or OpenSSH)
+    .private-key = Private Key (PEM, DER, JWK or OpenSSH)
     .ok = OK
     .invalid-key = Invalid key
     .invalid-token = Invalid token
@@ -46,8 +140,118 @@ jwt-debugger = JWT Debugger
     .invalid-claims = Invalid claims
     .invalid-signature = Invalid signature
     .encoding-failed = Encoding failed
+    .invalid-key-set = Invalid key set
+    .no-matching-key = No key in the key set matches the token's kid
+    .verified-with-key = Signature verified with key { $kid }
+    .copy-jwk = Copy Key as JWK
+    .jwk-copied = Copied the key as a JWK with kid { $kid }
+    .generate-key = Generate Key
+    .key-size = RSA Key Size
+    .unsupported-algorithm = Unsupported algorithm
+    .decryption-failed = Decryption failed
+    .validate-claims = Validate Claims
+    .validation = Claims Validation
+    .expected-audience = Expected audience
+    .expected-issuer = Expected issuer
+    .expected-subject = Expected subject
+    .clock-skew = Clock skew (seconds)
+    .required = Required
+    .exp = Expiration Time
+    .nbf = Not Before
+    .iat = Issued At
+    .aud = Audience
+    .iss = Issuer
+    .sub = Subject
+    .claim-missing = Not in the token
+    .claim-malformed = Malformed
+    .expires-in = Expires in { $duration }, { $date }
+    .expired = Expired { $duration } ago, { $date }
+    .not-yet-valid = Not valid for another { $duration }, { $date }
+    .valid-since = Valid since { $duration } ago, { $date }
+    .issued-in-future = Issued { $duration } in the future, { $date }
+    .issued-ago = Issued { $duration } ago, { $date }
+    .days = { $count }d
+    .hours = { $count }h
+    .minutes = { $count }m
+    .seconds = { $count }s
+    .unexpected-value = { $values }, expected { $expected }
+    .claims-valid = The claims are valid
+    .claims-invalid = Some claims are invalid
 lorem-ipsum-generator = Lorem Ipsum Generator
     .amount = Amount
     .words = Words
     .sentences = Sentences
     .paragraphs = Paragraphs
+pipeline = Pipeline
+    .run = Run
+    .add-step = Add Step
+    .empty = Add steps to transform the input one after another.
+    .move-up = Move Up
+    .move-down = Move Down
+    .remove = Remove
+    .to = to
+    .open-recipe = Open Recipe…
+    .save-recipe = Save Recipe…
+    .recipes = Recipes
+    .saved = Recipe saved
+    .open-failed = Cannot open the recipe
+    .save-failed = Cannot save the recipe
+    .base64-encode = Base64 Encode
+    .base64-decode = Base64 Decode
+    .gzip-compress = GZip Compress
+    .gzip-decompress = GZip Decompress
+    .url-encode = URL Encode
+    .url-decode = URL Decode
+    .convert-format = Convert Format
+    .jwt-decode = JWT Decode
+text-diff = Text Diff
+    .lines = Lines
+    .json = JSON Structure
+    .ignore-whitespace = Ignore Whitespace
+    .compare = Compare
+    .swap = Swap Sides
+    .original = Original
+    .changed = Changed
+    .differences = Differences
+    .empty = Enter two texts and compare them.
+    .no-differences = No differences
+    .equivalent = The documents are equivalent
+    .lines-changed = { $removed ->
+        [one] 1 line removed
+       *[other] { $removed } lines removed
+    }, { $added ->
+        [one] 1 line added
+       *[other] { $added } lines added
+    }
+    .values-changed = { $count ->
+        [one] 1 value changed
+       *[other] { $count } values changed
+    }
+    .unchanged-lines = { $count ->
+        [one] 1 unchanged line
+       *[other] { $count } unchanged lines
+    }
+    .invalid-original = The original text is not valid JSON
+    .invalid-changed = The changed text is not valid JSON
+key-creator-converter = Key Creator/Converter
+    .key-type = Key Type
+    .generate = Generate
+    .private-key = Private Key
+    .public-key = Public Key
+    .derive-public-key = Derive Public Key
+    .private-format = Private { $format }
+    .public-format = Public { $format }
+    .details = Details
+    .key = Key
+    .format = Format
+    .sha256-fingerprint = SHA-256 Fingerprint
+    .openssh-fingerprint = OpenSSH Fingerprint
+    .jwk-thumbprint = JWK Thumbprint
+    .read-key = Read { $key } key from { $format }
+    .keys-match = The public key belongs to the private key
+    .keys-mismatch = The public key does not belong to the private key
+    .generation-failed = Key generation failed
+    .invalid-key = Not a PEM, DER, JWK or OpenSSH key
+    .encrypted-key = Encrypted keys are not supported
+    .unsupported-format = The key cannot be written in this format
+    .encoding-failed = The key could not be encoded
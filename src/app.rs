@@ -3,35 +3,60 @@
 use {
     crate::{
         ContextPage, Message,
+        command_palette::{self, CommandPalette, Entry},
         config::Config,
-        context_drawer_pages::{ContextDrawerPage, about_page::AboutPage},
-        fl,
-        utility_pages::{
-            UtilityPage, base64_string_encoder_decoder_page::Base64StringEncoderDecoderPage,
-            data_converter_formatter_page::DataConverterFormatterPage,
-            gzip_compressor_decompressor_page::GZipCompressorDecompressorPage,
-            jwt_debugger_page::JwtDebuggerPage,
-            lorem_ipsum_generator_page::LoremIpsumGeneratorPage,
-            unix_time_converter_page::UnixTimeConverterPage,
-            url_encoder_decoder_page::UrlEncoderDecoderPage,
+        context_drawer_pages::{
+            ContextDrawerPage,
+            about_page::AboutPage,
+            history_page::HistoryPage,
+            paste_suggestions_page::PasteSuggestionsPage,
+            sessions_page::{Session, SessionTab, SessionsPage},
+            shortcuts_page, tools_page,
         },
+        dbus, fl,
+        i18n::LANGUAGE_LOADER,
+        tabs::{self, Tab, Tabs},
+        utility_pages::{EditAction, UtilityPage, conversion::Debounce, registry},
     },
     cosmic::{
         self, ApplicationExt, Apply, Element, Task,
         app::context_drawer,
         cosmic_config::{self, CosmicConfigEntry},
         iced::{
-            Length, Padding, Subscription,
+            Event, Length, Padding, Size, Subscription,
             alignment::{Horizontal, Vertical},
+            clipboard, event,
+            keyboard::{self, Key, key},
+            window,
         },
-        widget::{self, icon, menu, nav_bar, responsive_menu_bar},
+        widget::{
+            self,
+            menu::{
+                self,
+                action::MenuAction as _,
+                key_bind::{KeyBind, Modifier},
+            },
+            nav_bar, responsive_menu_bar,
+        },
+    },
+    dev_toolbox_core::{
+        detect::{self, ContentKind},
+        history::HistoryEntry,
+        service::{Tool, ToolRequest},
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        hash::Hash,
+        sync::LazyLock,
     },
-    std::{collections::HashMap, hash::Hash, sync::LazyLock},
 };
 
 static MENU_ID: LazyLock<cosmic::widget::Id> =
     LazyLock::new(|| cosmic::widget::Id::new("responsive-menu"));
 
+/// Key bindings for the application's menu bar, which the text editors leave to it.
+static KEY_BINDS: LazyLock<HashMap<KeyBind, MenuAction>> = LazyLock::new(key_binds);
+
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
 pub struct AppModel {
@@ -41,11 +66,52 @@ pub struct AppModel {
     context_page: ContextPage,
     /// Contains items assigned to the nav bar panel.
     nav: nav_bar::Model,
-    /// Key bindings for the application's menu bar.
-    key_binds: HashMap<menu::KeyBind, MenuAction>,
     /// Configuration data that persists between application runs.
     config: Config,
-    utility_pages: HashMap<Page, Box<dyn UtilityPage>>,
+    /// Writes configuration changes, unavailable when the config directory cannot be created.
+    config_handler: Option<cosmic_config::Config>,
+    /// Delays saving the pages changed until they stay unchanged for a moment.
+    config_save: Debounce,
+    /// Pages whose options or input changed since they were last saved.
+    unsaved_pages: HashSet<Page>,
+    /// The open tabs of every tool.
+    tabs: HashMap<Page, Tabs>,
+    /// Identifies the next tab opened.
+    next_tab_id: u64,
+    /// The title being entered for the active tab, shown as a dialog while renaming it.
+    tab_title: Option<String>,
+    /// The last content read by the paste-anywhere action.
+    paste_suggestions: Option<PasteSuggestionsPage>,
+    /// Recent conversions of every page.
+    history: HistoryPage,
+    /// Named snapshots of the open tabs.
+    sessions: SessionsPage,
+    /// Shown as a dialog while open.
+    command_palette: Option<CommandPalette>,
+    /// Tools opened in windows of their own, besides the main window.
+    windows: HashMap<window::Id, ToolWindow>,
+}
+
+/// Arguments of the application, forwarded to the instance already running if there is one.
+#[derive(Clone, Debug)]
+pub struct Flags {
+    pub request: Option<ToolRequest>,
+}
+
+impl cosmic::app::CosmicFlags for Flags {
+    type SubCommand = ToolRequest;
+    type Args = Vec<String>;
+
+    /// Sent as the URI of the tool to open.
+    fn action(&self) -> Option<&ToolRequest> {
+        self.request.as_ref()
+    }
+}
+
+/// A tool shown in a window of its own, with inputs separate from the main window's page.
+struct ToolWindow {
+    page: Page,
+    utility_page: Box<dyn UtilityPage>,
 }
 
 /// Create a COSMIC application from the app model
@@ -54,7 +120,7 @@ impl cosmic::Application for AppModel {
     type Executor = cosmic::executor::Default;
 
     /// Data that your application receives to its init method.
-    type Flags = ();
+    type Flags = Flags;
 
     /// Messages which the application and its widgets will emit.
     type Message = Message;
@@ -71,108 +137,61 @@ impl cosmic::Application for AppModel {
     }
 
     /// Initializes the application with any given flags and startup commands.
-    fn init(
-        core: cosmic::Core,
-        _flags: Self::Flags,
-    ) -> (Self, Task<cosmic::Action<Self::Message>>) {
-        // Create a nav bar with three page items.
-        let mut nav = nav_bar::Model::default();
-
-        nav.insert()
-            .text(fl!("unix-time-converter"))
-            .data::<Page>(Page::UnixTimeConverter)
-            .icon(icon::from_name("accessories-clock-symbolic"))
-            .activate();
-
-        nav.insert()
-            .text(fl!("data-converter-formatter"))
-            .data::<Page>(Page::DataConverterFormatter)
-            .icon(icon::from_name("x-office-document-symbolic"));
-
-        nav.insert()
-            .text(fl!("base64-string-encoder-decoder"))
-            .data::<Page>(Page::Base64StringEncoderDecoder)
-            .icon(icon::from_name("object-flip-horizontal-symbolic"));
-
-        nav.insert()
-            .text(fl!("gzip-compressor-decompressor"))
-            .data::<Page>(Page::GZipCompressorDecompressor)
-            .icon(icon::from_name("media-zip-symbolic"));
-
-        nav.insert()
-            .text(fl!("url-encoder-decoder"))
-            .data::<Page>(Page::UrlEncoderDecoder)
-            .icon(icon::from_name("link-symbolic"));
-
-        nav.insert()
-            .text(fl!("jwt-debugger"))
-            .data::<Page>(Page::JwtDebugger)
-            .icon(
-                icon::from_svg_bytes(include_bytes!("../resources/icons/jwt-symbolic.svg"))
-                    .symbolic(true)
-                    .icon(),
-            );
+    fn init(core: cosmic::Core, flags: Self::Flags) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        // Optional configuration file for an application.
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let config = config_handler
+            .as_ref()
+            .map(|context| match Config::get_entry(context) {
+                Ok(config) => config,
+                Err((_errors, config)) => {
+                    // for why in errors {
+                    //     tracing::error!(%why, "error loading app config");
+                    // }
 
-        nav.insert()
-            .text(fl!("lorem-ipsum-generator"))
-            .data::<Page>(Page::LoremIpsumGenerator)
-            .icon(
-                icon::from_svg_bytes(include_bytes!("../resources/icons/lorem-ipsum.svg"))
-                    .symbolic(true)
-                    .icon(),
-            );
+                    config
+                }
+            })
+            .unwrap_or_default();
 
-        let mut utility_pages = HashMap::<Page, Box<dyn UtilityPage>>::new();
-        utility_pages.insert(
-            Page::UnixTimeConverter,
-            Box::new(UnixTimeConverterPage::default()),
-        );
-        utility_pages.insert(
-            Page::DataConverterFormatter,
-            Box::new(DataConverterFormatterPage::default()),
-        );
-        utility_pages.insert(
-            Page::Base64StringEncoderDecoder,
-            Box::new(Base64StringEncoderDecoderPage::default()),
-        );
-        utility_pages.insert(
-            Page::GZipCompressorDecompressor,
-            Box::new(GZipCompressorDecompressorPage::default()),
-        );
-        utility_pages.insert(
-            Page::UrlEncoderDecoder,
-            Box::new(UrlEncoderDecoderPage::default()),
-        );
-        utility_pages.insert(Page::JwtDebugger, Box::new(JwtDebuggerPage::default()));
-        utility_pages.insert(
-            Page::LoremIpsumGenerator,
-            Box::new(LoremIpsumGeneratorPage::default()),
-        );
+        // Create a nav bar with the tools enabled in the config.
+        let nav = build_nav(&config);
 
         // Construct the app model with the runtime's core.
         let mut app = AppModel {
             core,
             context_page: ContextPage::default(),
             nav,
-            key_binds: HashMap::new(),
-            // Optional configuration file for an application.
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
-                    Ok(config) => config,
-                    Err((_errors, config)) => {
-                        // for why in errors {
-                        //     tracing::error!(%why, "error loading app config");
-                        // }
-
-                        config
-                    }
-                })
-                .unwrap_or_default(),
-            utility_pages,
+            config,
+            config_handler,
+            config_save: Debounce::default(),
+            unsaved_pages: HashSet::new(),
+            tabs: HashMap::new(),
+            next_tab_id: 0,
+            tab_title: None,
+            paste_suggestions: None,
+            history: HistoryPage::load(),
+            sessions: SessionsPage::new(),
+            command_palette: None,
+            windows: HashMap::new(),
+        };
+
+        // Restore the workspace as it was left on the previous run.
+        let mut load_tasks = Vec::new();
+        for page in registry::TOOLS.iter().map(|tool| tool.page) {
+            let mut tab = app.new_tab(page);
+            load_tasks.push(app.load_tab(&mut tab, &app.config));
+            app.tabs.insert(page, Tabs::new(tab));
+        }
+
+        // Open the tool given on the command line, which also sets the window title.
+        let title_task = match flags.request {
+            Some(request) => app.prefill(request),
+            None => app.update_title(),
         };
 
-        // Create a startup command that sets the window title.
-        let command = app.update_title();
+        // Create a startup command that sets the window title and converts the restored inputs.
+        let command = Task::batch(load_tasks.into_iter().chain([title_task]));
 
         (app, command)
     }
@@ -185,22 +204,81 @@ impl cosmic::Application for AppModel {
             .spacing(4.0)
             .into_element(
                 &self.core,
-                &self.key_binds,
+                &KEY_BINDS,
                 MENU_ID.clone(),
                 Message::Surface,
-                vec![(
-                    fl!("view"),
-                    vec![menu::Item::Button(
-                        format!("{} {}", fl!("about"), fl!("app-title")),
-                        None,
-                        MenuAction::About,
-                    )],
-                )],
+                vec![
+                    (
+                        fl!("edit"),
+                        vec![
+                            MenuAction::Edit(EditAction::Convert).button(&self.nav),
+                            MenuAction::Edit(EditAction::Swap).button(&self.nav),
+                            menu::Item::Divider,
+                            MenuAction::Edit(EditAction::CopyOutput).button(&self.nav),
+                            MenuAction::Edit(EditAction::PasteInput).button(&self.nav),
+                            MenuAction::PasteAnywhere.button(&self.nav),
+                            menu::Item::Divider,
+                            MenuAction::Edit(EditAction::Clear).button(&self.nav),
+                        ],
+                    ),
+                    (
+                        fl!("view"),
+                        vec![
+                            menu::Item::CheckBox(
+                                MenuAction::RememberInputs.title(&self.nav),
+                                None,
+                                self.config.remember_inputs,
+                                MenuAction::RememberInputs,
+                            ),
+                            MenuAction::History.button(&self.nav),
+                            MenuAction::Sessions.button(&self.nav),
+                            MenuAction::Tools.button(&self.nav),
+                            MenuAction::Shortcuts.button(&self.nav),
+                            menu::Item::Divider,
+                            MenuAction::About.button(&self.nav),
+                        ],
+                    ),
+                    (fl!("tools"), self.tools_menu()),
+                ],
             );
 
         vec![menu_bar.into()]
     }
 
+    /// Elements to pack at the end of the header bar.
+    fn header_end(&self) -> Vec<Element<'_, Self::Message>> {
+        vec![
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
+                    .on_press(Message::PasteAnywhere),
+                widget::text(fl!("paste-anywhere")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("tab-new-symbolic"))
+                    .on_press(Message::NewTab),
+                widget::text(fl!("new-tab")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("window-new-symbolic"))
+                    .on_press(Message::OpenWindow),
+                widget::text(fl!("open-in-new-window")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-recent-symbolic"))
+                    .on_press(Message::ToggleContextPage(ContextPage::History)),
+                widget::text(fl!("history")),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        ]
+    }
+
     /// Enables the COSMIC application to create a nav bar with this model.
     fn nav_model(&self) -> Option<&nav_bar::Model> {
         Some(&self.nav)
@@ -217,29 +295,99 @@ impl cosmic::Application for AppModel {
                 AboutPage::get_context_drawer_page(),
                 Message::ToggleContextPage(ContextPage::About),
             ),
+            ContextPage::PasteSuggestions => context_drawer::context_drawer(
+                self.paste_suggestions.as_ref()?.get_context_drawer_page(),
+                Message::ToggleContextPage(ContextPage::PasteSuggestions),
+            )
+            .title(fl!("paste-anywhere")),
+            ContextPage::History => {
+                let page = *self.nav.active_data::<Page>()?;
+                context_drawer::context_drawer(
+                    self.history
+                        .get_context_drawer_page(page, self.keeps_history(page)),
+                    Message::ToggleContextPage(ContextPage::History),
+                )
+                .title(fl!("history"))
+            }
+            ContextPage::Shortcuts => context_drawer::context_drawer(
+                shortcuts_page::get_context_drawer_page(self.shortcuts()),
+                Message::ToggleContextPage(ContextPage::Shortcuts),
+            )
+            .title(fl!("keyboard-shortcuts")),
+            ContextPage::Sessions => context_drawer::context_drawer(
+                self.sessions.get_context_drawer_page(),
+                Message::ToggleContextPage(ContextPage::Sessions),
+            )
+            .title(fl!("sessions")),
+            ContextPage::Tools => context_drawer::context_drawer(
+                tools_page::get_context_drawer_page(&self.config),
+                Message::ToggleContextPage(ContextPage::Tools),
+            )
+            .title(fl!("manage-tools")),
         })
     }
 
+    /// Display a dialog over the window while one is requested.
+    fn dialog(&self) -> Option<Element<'_, Self::Message>> {
+        if let Some(title) = &self.tab_title {
+            return Some(tabs::rename_dialog(title));
+        }
+        self.command_palette.as_ref().map(CommandPalette::view)
+    }
+
     /// Describes the interface based on the current state of the application model.
     ///
     /// Application events will be processed through the view. Any messages emitted by
     /// events received by widgets will be passed to the update method.
     fn view(&self) -> Element<'_, Self::Message> {
-        let content = self
-            .utility_pages
-            .get(self.nav.active_data::<Page>().unwrap())
-            .unwrap()
-            .get_utility_page();
-
-        widget::container(content)
-            .max_width(1000)
-            .height(Length::Fill)
-            .apply(widget::container)
-            .width(Length::Fill)
-            .padding(Padding::new(0.0).horizontal(8.0).bottom(8.0))
-            .align_x(Horizontal::Center)
-            .align_y(Vertical::Center)
-            .into()
+        let tabs = &self.tabs[self.nav.active_data::<Page>().unwrap()];
+        let content = widget::column::with_capacity(2)
+            .push_maybe(tabs.view())
+            .push(tabs.active().utility_page.get_utility_page())
+            .spacing(cosmic::theme::spacing().space_xxs);
+
+        page_view(content.into())
+    }
+
+    /// Describes the interface of the windows opened besides the main window.
+    fn view_window(&self, id: window::Id) -> Element<'_, Self::Message> {
+        let Some(tool_window) = self.windows.get(&id) else {
+            return widget::horizontal_space().into();
+        };
+        let content = tool_window
+            .utility_page
+            .get_utility_page()
+            .map(move |message| Message::Window(id, Box::new(message)));
+
+        page_view(content)
+    }
+
+    /// Opens the tool given to another launch of the app, or the `devtoolbox://` URI opened.
+    fn dbus_activation(
+        &mut self,
+        msg: cosmic::dbus_activation::Message,
+    ) -> Task<cosmic::Action<Self::Message>> {
+        let uris = match msg.msg {
+            cosmic::dbus_activation::Details::ActivateAction { action, .. } => vec![action],
+            cosmic::dbus_activation::Details::Open { url } => {
+                url.iter().map(ToString::to_string).collect()
+            }
+            cosmic::dbus_activation::Details::Activate => Vec::new(),
+        };
+
+        let mut tasks = Vec::new();
+        for uri in uris {
+            match uri.parse() {
+                Ok(request) => tasks.push(self.prefill(request)),
+                Err(err) => eprintln!("failed to open {uri}: {err}"),
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    /// Forgets the page of a tool window once it is closed.
+    fn on_close_requested(&self, id: window::Id) -> Option<Self::Message> {
+        Some(Message::WindowClosed(id))
     }
 
     /// Register subscriptions for this application.
@@ -250,7 +398,7 @@ impl cosmic::Application for AppModel {
     /// indefinitely.
     fn subscription(&self) -> Subscription<Self::Message> {
         // Add subscriptions which are always active.
-        let subscriptions = vec![
+        let mut subscriptions = vec![
             // Watch for application configuration changes.
             self.core()
                 .watch_config::<Config>(Self::APP_ID)
@@ -261,7 +409,30 @@ impl cosmic::Application for AppModel {
 
                     Message::UpdateConfig(update.config)
                 }),
+            // Forward the key presses the widgets leave alone to match them against the key
+            // bindings. Escape still dismisses the command palette and the rename prompt while
+            // their text input, which captures it, has the focus.
+            event::listen_with(|event, status, window_id| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. })
+                    if status == event::Status::Ignored
+                        || key == Key::Named(key::Named::Escape) =>
+                {
+                    Some(Message::Key(window_id, modifiers, key))
+                }
+                _ => None,
+            }),
+            // Serve conversions and requests to open a tool over D-Bus.
+            dbus::subscription(),
         ];
+        // Listen to the events of the pages shown, in the main window and in tool windows.
+        if let Some(&page) = self.nav.active_data::<Page>() {
+            subscriptions.push(self.active_page(page).subscription());
+        }
+        subscriptions.extend(
+            self.windows
+                .values()
+                .map(|tool_window| tool_window.utility_page.subscription()),
+        );
 
         Subscription::batch(subscriptions)
     }
@@ -281,10 +452,421 @@ impl cosmic::Application for AppModel {
                     self.context_page = context_page;
                     self.core.window.show_context = true;
                 }
+                if context_page == ContextPage::Sessions {
+                    self.sessions.refresh();
+                }
             }
 
             Message::UpdateConfig(config) => {
+                let nav_changed = config.tool_order != self.config.tool_order
+                    || config.disabled_tools != self.config.disabled_tools;
                 self.config = config;
+                if nav_changed {
+                    return self.refresh_nav();
+                }
+            }
+
+            Message::SaveConfig(edit) => {
+                if self.config_save.is_settled(edit) {
+                    self.save_unsaved_pages();
+                }
+            }
+
+            // The windows are redrawn after every message.
+            Message::Redraw => {}
+
+            Message::ToggleRememberInputs => {
+                if let Some(config_handler) = &self.config_handler {
+                    let remember_inputs = !self.config.remember_inputs;
+                    if let Err(err) = self
+                        .config
+                        .set_remember_inputs(config_handler, remember_inputs)
+                    {
+                        eprintln!("failed to save config: {err}");
+                    }
+                    // Clear or store the inputs of every page right away.
+                    for tabs in self.tabs.values() {
+                        if let Err(err) = tabs
+                            .first()
+                            .utility_page
+                            .save_config(&mut self.config, config_handler)
+                        {
+                            eprintln!("failed to save config: {err}");
+                        }
+                    }
+                }
+            }
+
+            Message::Key(window_id, modifiers, key) => {
+                // The palette takes the keys moving through its matches.
+                if let Some(command_palette) = &mut self.command_palette {
+                    match &key {
+                        Key::Named(key::Named::Escape) => self.command_palette = None,
+                        Key::Named(key::Named::ArrowUp) => command_palette.move_selection(-1),
+                        Key::Named(key::Named::ArrowDown) => command_palette.move_selection(1),
+                        _ => {}
+                    }
+                }
+                if key == Key::Named(key::Named::Escape) {
+                    self.tab_title = None;
+                }
+                let Some(&action) = KEY_BINDS.iter().find_map(|(key_bind, action)| {
+                    key_bind.matches(modifiers, &key).then_some(action)
+                }) else {
+                    return Task::none();
+                };
+                if self.windows.contains_key(&window_id) {
+                    return self.tool_window_action(window_id, action);
+                }
+                return self.update(action.message());
+            }
+
+            Message::PasteAnywhere => {
+                return clipboard::read().map(|optional_data| {
+                    cosmic::Action::App(Message::PasteDetected(optional_data.unwrap_or_default()))
+                });
+            }
+
+            Message::PasteDetected(content) => {
+                return Task::perform(
+                    async move {
+                        let detections = tokio::task::spawn_blocking({
+                            let content = content.clone();
+                            move || detect::detect(&content)
+                        })
+                        .await
+                        .unwrap_or_default();
+                        (content, detections)
+                    },
+                    |(content, detections)| {
+                        cosmic::Action::App(Message::PasteDetections(content, detections))
+                    },
+                );
+            }
+
+            Message::PasteDetections(content, detections) => {
+                let is_ambiguous = detections.len() != 1;
+                self.paste_suggestions = Some(PasteSuggestionsPage {
+                    content,
+                    detections,
+                    selected: 0,
+                });
+                // Offer the other matches, or explain why nothing was pasted.
+                if is_ambiguous {
+                    self.context_page = ContextPage::PasteSuggestions;
+                    self.core.window.show_context = true;
+                } else if self.context_page == ContextPage::PasteSuggestions {
+                    self.core.window.show_context = false;
+                }
+                return self.paste_as(0);
+            }
+
+            Message::PasteAs(index) => {
+                return self.paste_as(index);
+            }
+
+            Message::OpenCommandPalette => {
+                let mut entries = Vec::new();
+                for id in self.nav.iter() {
+                    let (Some(&page), Some(title)) = (self.nav.data::<Page>(id), self.nav.text(id))
+                    else {
+                        continue;
+                    };
+                    entries.push(Entry {
+                        page,
+                        title: title.to_string(),
+                        messages: Vec::new(),
+                    });
+                    entries.extend(
+                        self.active_page(page)
+                            .commands()
+                            .into_iter()
+                            .map(|command| Entry {
+                                page,
+                                title: format!("{title}: {}", command.title),
+                                messages: command.messages,
+                            }),
+                    );
+                }
+                self.command_palette = Some(CommandPalette::new(entries));
+                return widget::text_input::focus(command_palette::INPUT_ID.clone());
+            }
+
+            Message::CommandPaletteQueryChanged(query) => {
+                if let Some(command_palette) = &mut self.command_palette {
+                    command_palette.search(query);
+                }
+            }
+
+            Message::RunCommand(index) => {
+                let Some(entry) = self
+                    .command_palette
+                    .take()
+                    .and_then(|command_palette| command_palette.into_entry(index))
+                else {
+                    return Task::none();
+                };
+                let recorded = self.leave_active_page(entry.page);
+                self.activate_page(entry.page);
+                let tasks: Vec<_> = entry
+                    .messages
+                    .into_iter()
+                    .map(|message| self.update(message))
+                    .collect();
+                return Task::batch(tasks.into_iter().chain([recorded, self.update_title()]));
+            }
+
+            Message::CloseCommandPalette => {
+                self.command_palette = None;
+            }
+
+            Message::Edit(action) => {
+                let page = self.nav.active_data::<Page>().copied();
+                if let Some(message) =
+                    page.and_then(|page| self.active_page(page).edit_message(action))
+                {
+                    return self.update(message);
+                }
+            }
+
+            Message::CycleTool(offset) => {
+                let ids: Vec<_> = self.nav.iter().collect();
+                if let Some(position) = ids.iter().position(|id| *id == self.nav.active()) {
+                    let next = (position as isize + offset).rem_euclid(ids.len() as isize);
+                    return self.on_nav_select(ids[next as usize]);
+                }
+            }
+
+            Message::ActivateTool(index) => {
+                if let Some(id) = self.nav.iter().nth(index) {
+                    return self.on_nav_select(id);
+                }
+            }
+
+            Message::OpenWindow => {
+                let Some(&page) = self.nav.active_data::<Page>() else {
+                    return Task::none();
+                };
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(1000.0, 720.0),
+                    ..Default::default()
+                });
+                // The window starts from the saved options and inputs but does not save its own,
+                // which stay with the page of the main window.
+                let mut utility_page = page.new_utility_page();
+                let load = utility_page.load_config(&self.config);
+                self.windows.insert(id, ToolWindow { page, utility_page });
+
+                let title = format!("{} — {}", fl!("app-title"), LANGUAGE_LOADER.get(page.id()));
+                return Task::batch([
+                    open.map(|_| cosmic::Action::None),
+                    load.map(move |action| routed(action, |message| Message::Window(id, message))),
+                    self.set_window_title(title, id),
+                ]);
+            }
+
+            Message::Window(id, message) => {
+                let Some(tool_window) = self.windows.get_mut(&id) else {
+                    return Task::none();
+                };
+                // Messages of the application itself, such as recording history, are handled as if
+                // they came from the main window.
+                if Page::for_message(&message) != Some(tool_window.page) {
+                    return self.update(*message);
+                }
+                return tool_window
+                    .utility_page
+                    .handle_message(*message)
+                    .map(move |action| routed(action, |message| Message::Window(id, message)));
+            }
+
+            Message::WindowClosed(id) => {
+                self.save_unsaved_pages();
+                if let Some(tool_window) = self.windows.remove(&id) {
+                    let entry = tool_window.utility_page.history_entry();
+                    return Task::batch([
+                        self.record_history(tool_window.page, entry),
+                        window::close(id),
+                    ]);
+                }
+            }
+
+            Message::NewTab => {
+                if let Some(&page) = self.nav.active_data::<Page>() {
+                    let mut tab = self.new_tab(page);
+                    let id = tab.id;
+                    let task = self.load_tab(&mut tab, &self.config);
+                    let tabs = self.tabs.get_mut(&page).unwrap();
+                    tabs.push(tab);
+                    tabs.activate(id);
+                    return task;
+                }
+            }
+
+            Message::ActivateTab(id) => {
+                if let Some(&page) = self.nav.active_data::<Page>() {
+                    let tabs = &self.tabs[&page];
+                    if tabs.active().id != id {
+                        let entry = tabs.active().utility_page.history_entry();
+                        self.tabs.get_mut(&page).unwrap().activate(id);
+                        return self.record_history(page, entry);
+                    }
+                }
+            }
+
+            Message::CloseTab(id) => {
+                if let Some(&page) = self.nav.active_data::<Page>() {
+                    let tabs = self.tabs.get_mut(&page).unwrap();
+                    let id = id.unwrap_or(tabs.active().id);
+                    let entry = (tabs.len() > 1)
+                        .then(|| tabs.iter().find(|tab| tab.id == id))
+                        .flatten()
+                        .and_then(|tab| tab.utility_page.history_entry());
+                    tabs.close(id);
+                    return self.record_history(page, entry);
+                }
+            }
+
+            Message::RenameTab => {
+                if let Some(page) = self.nav.active_data::<Page>() {
+                    self.tab_title = Some(self.tabs[page].active().title.clone());
+                    return widget::text_input::focus(tabs::RENAME_INPUT_ID.clone());
+                }
+            }
+
+            Message::TabTitleChanged(title) => {
+                if let Some(tab_title) = &mut self.tab_title {
+                    *tab_title = title;
+                }
+            }
+
+            Message::ConfirmRenameTab => {
+                if let (Some(title), Some(page)) =
+                    (self.tab_title.take(), self.nav.active_data::<Page>())
+                    && !title.trim().is_empty()
+                {
+                    self.tabs.get_mut(page).unwrap().active_mut().title = title.trim().to_string();
+                }
+            }
+
+            Message::CancelRenameTab => {
+                self.tab_title = None;
+            }
+
+            Message::Tab(id, message) => {
+                // Messages of the application itself, such as recording history, are handled as if
+                // they came from the active tab.
+                let Some(page) = Page::for_message(&message) else {
+                    return self.update(*message);
+                };
+                let Some(tab) = self.tabs.get_mut(&page).unwrap().get_mut(id) else {
+                    return Task::none();
+                };
+                let task = tab
+                    .utility_page
+                    .handle_message(*message)
+                    .map(move |action| routed(action, |message| Message::Tab(id, message)));
+                return Task::batch([task, self.schedule_save(page)]);
+            }
+
+            Message::Prefill(request) => {
+                return self.prefill(request);
+            }
+
+            Message::SessionNameChanged(name) => {
+                self.sessions.name = name;
+            }
+
+            Message::SaveSession => {
+                let tabs = registry::TOOLS
+                    .iter()
+                    .flat_map(|tool| self.tabs[&tool.page].iter())
+                    .map(|tab| {
+                        // Inputs that are credentials stay out of sessions as out of the config.
+                        let mut page = tab.utility_page.page_config();
+                        if !page.keeps_input() {
+                            page.clear_input();
+                        }
+                        SessionTab {
+                            title: tab.title.clone(),
+                            page,
+                        }
+                    })
+                    .collect();
+                self.sessions.save(&Session { tabs });
+            }
+
+            Message::RestoreSession(name) => {
+                if let Some(session) = self.sessions.open(&name) {
+                    return self.restore_session(session);
+                }
+            }
+
+            Message::DeleteSession(name) => {
+                self.sessions.delete(&name);
+            }
+
+            Message::ToggleTool(page, enabled) => {
+                let mut disabled_tools = self.config.disabled_tools.clone();
+                disabled_tools.retain(|id| id != page.id());
+                if !enabled {
+                    disabled_tools.push(page.id().to_string());
+                }
+                return self.update_tools(self.config.tool_order.clone(), disabled_tools);
+            }
+
+            Message::MoveToolUp(index) => {
+                if index > 0 {
+                    return self.swap_tools(index - 1, index);
+                }
+            }
+
+            Message::MoveToolDown(index) => {
+                return self.swap_tools(index, index + 1);
+            }
+
+            Message::RecordHistory(page, input, output) => {
+                return self.record_history(page, Some((input, output)));
+            }
+
+            Message::RestoreHistory(index) => {
+                return self.restore_history(index);
+            }
+
+            Message::PinHistory(index, pinned) => {
+                self.history.history.set_pinned(index, pinned);
+                return self.history.save();
+            }
+
+            Message::RemoveHistory(index) => {
+                self.history.history.remove(index);
+                return self.history.save();
+            }
+
+            Message::ClearHistory => {
+                if let Some(page) = self.nav.active_data::<Page>() {
+                    self.history.history.clear(page.id());
+                    return self.history.save();
+                }
+            }
+
+            Message::ToggleHistory(keep) => {
+                let (Some(config_handler), Some(page)) =
+                    (&self.config_handler, self.nav.active_data::<Page>())
+                else {
+                    return Task::none();
+                };
+                let mut excluded_pages = self.config.history_excluded_pages.clone();
+                excluded_pages.retain(|id| id != page.id());
+                if !keep {
+                    excluded_pages.push(page.id().to_string());
+                }
+                if let Err(err) = self
+                    .config
+                    .set_history_excluded_pages(config_handler, excluded_pages)
+                {
+                    eprintln!("failed to save config: {err}");
+                }
             }
 
             Message::LaunchUrl(url) => match open::that_detached(&url) {
@@ -293,74 +875,335 @@ impl cosmic::Application for AppModel {
                     eprintln!("failed to open {url:?}: {err}");
                 }
             },
-            Message::UnixTimeConverterMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::UnixTimeConverter)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::DataConverterFormatterMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::DataConverterFormatter)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::Base64StringEncoderDecoderMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::Base64StringEncoderDecoder)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::GZipCompressorDecompressorMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::GZipCompressorDecompressor)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::UrlEncoderDecoderMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::UrlEncoderDecoder)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::JwtDebuggerMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::JwtDebugger)
-                    .unwrap()
-                    .handle_message(message);
-            }
-            Message::LoremIpsumGeneratorMessage(_) => {
-                return self
-                    .utility_pages
-                    .get_mut(&Page::LoremIpsumGenerator)
-                    .unwrap()
-                    .handle_message(message);
-            }
             Message::Surface(a) => {
                 return cosmic::task::message(cosmic::Action::Cosmic(
                     cosmic::app::Action::Surface(a),
                 ));
             }
+            // Every other message belongs to a tool.
+            message => {
+                if let Some(page) = Page::for_message(&message) {
+                    return self.update_page(page, message);
+                }
+            }
         }
         Task::none()
     }
 
     /// Called when a nav item is selected.
     fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
-        // Activate the page in the model.
+        // Save the page left before activating the selected one.
+        self.save_unsaved_pages();
+        let recorded = self
+            .nav
+            .data::<Page>(id)
+            .copied()
+            .map_or_else(Task::none, |next| self.leave_active_page(next));
         self.nav.activate(id);
 
-        self.update_title()
+        Task::batch([recorded, self.update_title()])
     }
 }
 
 impl AppModel {
+    /// Fills the tool of a pasted content's detection with it and switches to that tool.
+    fn paste_as(&mut self, index: usize) -> Task<cosmic::Action<Message>> {
+        let Some(paste_suggestions) = &mut self.paste_suggestions else {
+            return Task::none();
+        };
+        let Some(&detection) = paste_suggestions.detections.get(index) else {
+            return Task::none();
+        };
+        paste_suggestions.selected = index;
+        let page = Page::for_content(detection.kind);
+        let content = paste_suggestions.content.clone();
+        let task = self.update_active_tab(page, |utility_page| {
+            utility_page.paste_detected(content, detection.kind)
+        });
+
+        let recorded = self.leave_active_page(page);
+        self.activate_page(page);
+        Task::batch([task, recorded, self.update_title()])
+    }
+
+    /// A menu item for every tool, in the order of the nav bar.
+    fn tools_menu(&self) -> Vec<menu::Item<MenuAction, String>> {
+        let mut items = vec![
+            MenuAction::CommandPalette.button(&self.nav),
+            menu::Item::Divider,
+            MenuAction::NextTool.button(&self.nav),
+            MenuAction::PreviousTool.button(&self.nav),
+            menu::Item::Divider,
+            MenuAction::NewTab.button(&self.nav),
+            MenuAction::CloseTab.button(&self.nav),
+            MenuAction::RenameTab.button(&self.nav),
+            MenuAction::OpenWindow.button(&self.nav),
+            menu::Item::Divider,
+        ];
+        items.extend(
+            (0..self.nav.iter().count()).map(|index| MenuAction::Tool(index).button(&self.nav)),
+        );
+        items
+    }
+
+    /// The title and keys of every key binding, in the order of their actions.
+    fn shortcuts(&self) -> Vec<(String, String)> {
+        let mut key_binds: Vec<_> = KEY_BINDS.iter().collect();
+        key_binds.sort_by_key(|(_, action)| **action);
+        key_binds
+            .into_iter()
+            .map(|(key_bind, action)| (action.title(&self.nav), key_bind.to_string()))
+            .collect()
+    }
+
+    /// Selects the nav item of a page.
+    fn activate_page(&mut self, page: Page) {
+        if let Some(id) = self
+            .nav
+            .iter()
+            .find(|id| self.nav.data::<Page>(*id) == Some(&page))
+        {
+            self.nav.activate(id);
+        }
+    }
+
+    /// Keeps the conversion shown by the active tab of the active page in the history, as the
+    /// page is left for another one.
+    fn leave_active_page(&mut self, next: Page) -> Task<cosmic::Action<Message>> {
+        let Some(&page) = self.nav.active_data::<Page>() else {
+            return Task::none();
+        };
+        if page == next {
+            return Task::none();
+        }
+        let entry = self.tabs[&page].active().utility_page.history_entry();
+        self.record_history(page, entry)
+    }
+
+    /// Keeps a conversion of a page in the history, unless the page is excluded from it.
+    fn record_history(
+        &mut self,
+        page: Page,
+        entry: Option<(String, String)>,
+    ) -> Task<cosmic::Action<Message>> {
+        let Some((input, output)) = entry else {
+            return Task::none();
+        };
+        if !self.keeps_history(page) {
+            return Task::none();
+        }
+        let entry = HistoryEntry::new(page.id(), input, output, chrono::Utc::now().timestamp());
+        if self.history.history.record(entry) {
+            return self.history.save();
+        }
+        Task::none()
+    }
+
+    /// Fills the active tab of a tool with an input and switches to it.
+    fn prefill(&mut self, request: ToolRequest) -> Task<cosmic::Action<Message>> {
+        let page = Page::for_tool(request.tool);
+        let task = self.update_active_tab(page, |utility_page| {
+            utility_page.prefill(&request.operation, request.input)
+        });
+
+        let recorded = self.leave_active_page(page);
+        self.activate_page(page);
+        Task::batch([task, recorded, self.update_title()])
+    }
+
+    /// Swaps two tools in the order of the nav bar.
+    fn swap_tools(&mut self, first: usize, second: usize) -> Task<cosmic::Action<Message>> {
+        let mut tool_order: Vec<_> = registry::ordered(&self.config)
+            .iter()
+            .map(|tool| tool.id.to_string())
+            .collect();
+        if second >= tool_order.len() {
+            return Task::none();
+        }
+        tool_order.swap(first, second);
+        self.update_tools(tool_order, self.config.disabled_tools.clone())
+    }
+
+    /// Saves the order of the tools and the tools hidden, then rebuilds the nav bar.
+    fn update_tools(
+        &mut self,
+        tool_order: Vec<String>,
+        disabled_tools: Vec<String>,
+    ) -> Task<cosmic::Action<Message>> {
+        match &self.config_handler {
+            Some(config_handler) => {
+                if let Err(err) = self.config.set_tool_order(config_handler, tool_order) {
+                    eprintln!("failed to save config: {err}");
+                }
+                if let Err(err) = self
+                    .config
+                    .set_disabled_tools(config_handler, disabled_tools)
+                {
+                    eprintln!("failed to save config: {err}");
+                }
+            }
+            None => {
+                self.config.tool_order = tool_order;
+                self.config.disabled_tools = disabled_tools;
+            }
+        }
+        self.refresh_nav()
+    }
+
+    /// Rebuilds the nav bar from the config, keeping the active tool when it is still shown.
+    fn refresh_nav(&mut self) -> Task<cosmic::Action<Message>> {
+        let active = self.nav.active_data::<Page>().copied();
+        self.nav = build_nav(&self.config);
+        if let Some(page) = active {
+            self.activate_page(page);
+        }
+        self.update_title()
+    }
+
+    /// Whether the conversions of a page are kept in the history.
+    fn keeps_history(&self, page: Page) -> bool {
+        !self
+            .config
+            .history_excluded_pages
+            .iter()
+            .any(|id| id == page.id())
+    }
+
+    /// Fills a page with the input of a history entry and switches to it.
+    fn restore_history(&mut self, index: usize) -> Task<cosmic::Action<Message>> {
+        let Some(entry) = self.history.history.entries().get(index) else {
+            return Task::none();
+        };
+        let Some(page) = Page::from_id(&entry.tool) else {
+            return Task::none();
+        };
+        let input = entry.input.clone();
+        let task = self.update_active_tab(page, |utility_page| utility_page.restore_input(input));
+
+        let recorded = self.leave_active_page(page);
+        self.activate_page(page);
+        Task::batch([task, recorded, self.update_title()])
+    }
+
+    /// The page of the active tab of a tool.
+    fn active_page(&self, page: Page) -> &dyn UtilityPage {
+        self.tabs[&page].active().utility_page.as_ref()
+    }
+
+    /// Performs an action bound to a key pressed in a tool window: the Edit actions apply to
+    /// the window's page and closing a tab closes the window, the other actions are the main
+    /// window's.
+    fn tool_window_action(
+        &mut self,
+        id: window::Id,
+        action: MenuAction,
+    ) -> Task<cosmic::Action<Message>> {
+        match action {
+            MenuAction::Edit(edit_action) => {
+                match self.windows[&id].utility_page.edit_message(edit_action) {
+                    Some(message) => self.update(Message::Window(id, Box::new(message))),
+                    None => Task::none(),
+                }
+            }
+            MenuAction::CloseTab => self.update(Message::WindowClosed(id)),
+            action => self.update(action.message()),
+        }
+    }
+
+    /// Handles a message of a page in the active tab of its tool.
+    fn update_page(&mut self, page: Page, message: Message) -> Task<cosmic::Action<Message>> {
+        self.update_active_tab(page, |utility_page| utility_page.handle_message(message))
+    }
+
+    /// Updates the page of the active tab of a tool and schedules saving its options, routing
+    /// the messages of the returned task back to that tab.
+    fn update_active_tab(
+        &mut self,
+        page: Page,
+        update: impl FnOnce(&mut dyn UtilityPage) -> Task<cosmic::Action<Message>>,
+    ) -> Task<cosmic::Action<Message>> {
+        let tab = self.tabs.get_mut(&page).unwrap().active_mut();
+        let id = tab.id;
+        let task = update(tab.utility_page.as_mut())
+            .map(move |action| routed(action, |message| Message::Tab(id, message)));
+        Task::batch([task, self.schedule_save(page)])
+    }
+
+    /// A tab with a new instance of a page, titled after its position among the tool's tabs.
+    fn new_tab(&mut self, page: Page) -> Tab {
+        let id = self.next_tab_id;
+        self.next_tab_id += 1;
+        let number = self.tabs.get(&page).map_or(0, Tabs::len) + 1;
+        Tab {
+            id,
+            title: fl!("rename-tab", "untitled", number = number),
+            utility_page: page.new_utility_page(),
+        }
+    }
+
+    /// Loads the options and input of a tab from a config.
+    fn load_tab(&self, tab: &mut Tab, config: &Config) -> Task<cosmic::Action<Message>> {
+        let id = tab.id;
+        tab.utility_page
+            .load_config(config)
+            .map(move |action| routed(action, |message| Message::Tab(id, message)))
+    }
+
+    /// Replaces the tabs of the tools in a session with the session's tabs.
+    fn restore_session(&mut self, session: Session) -> Task<cosmic::Action<Message>> {
+        let mut restored = HashMap::<Page, Tabs>::new();
+        let mut tasks = Vec::new();
+        for session_tab in session.tabs {
+            let page = session_tab.page.page();
+            let mut config = self.config.clone();
+            config.replace_page_config(session_tab.page);
+            let mut tab = self.new_tab(page);
+            tab.title = session_tab.title;
+            tasks.push(self.load_tab(&mut tab, &config));
+            match restored.get_mut(&page) {
+                Some(tabs) => tabs.push(tab),
+                None => {
+                    restored.insert(page, Tabs::new(tab));
+                }
+            }
+        }
+        let pages: Vec<_> = restored.keys().copied().collect();
+        self.tabs.extend(restored);
+        for page in &pages {
+            self.save_config(page);
+        }
+        Task::batch(tasks)
+    }
+
+    /// Marks a page as changed, saving it once no page has changed for a moment so that typing
+    /// does not write the config on every key press.
+    fn schedule_save(&mut self, page: Page) -> Task<cosmic::Action<Message>> {
+        self.unsaved_pages.insert(page);
+        self.config_save.edit(Message::SaveConfig)
+    }
+
+    /// Saves the pages changed since they were last saved.
+    fn save_unsaved_pages(&mut self) {
+        for page in std::mem::take(&mut self.unsaved_pages) {
+            self.save_config(&page);
+        }
+    }
+
+    /// Persists the options and inputs of the first tab of a page, only writing the keys that
+    /// changed.
+    fn save_config(&mut self, page: &Page) {
+        let Some(config_handler) = &self.config_handler else {
+            return;
+        };
+        if let Err(err) = self.tabs[page]
+            .first()
+            .utility_page
+            .save_config(&mut self.config, config_handler)
+        {
+            eprintln!("failed to save config: {err}");
+        }
+    }
+
     /// Updates the header and window titles.
     pub fn update_title(&mut self) -> Task<cosmic::Action<Message>> {
         let mut window_title = fl!("app-title");
@@ -378,8 +1221,47 @@ impl AppModel {
     }
 }
 
+/// A nav item for every tool enabled in a config, with the first one active.
+fn build_nav(config: &Config) -> nav_bar::Model {
+    let mut nav = nav_bar::Model::default();
+    for tool in registry::enabled(config) {
+        nav.insert()
+            .text(LANGUAGE_LOADER.get(tool.id))
+            .data::<Page>(tool.page)
+            .icon(tool.icon.icon());
+    }
+    if let Some(id) = nav.iter().next() {
+        nav.activate(id);
+    }
+    nav
+}
+
+/// Lays out the page of a tool in the middle of a window.
+fn page_view(content: Element<'_, Message>) -> Element<'_, Message> {
+    widget::container(content)
+        .max_width(1000)
+        .height(Length::Fill)
+        .apply(widget::container)
+        .width(Length::Fill)
+        .padding(Padding::new(0.0).horizontal(8.0).bottom(8.0))
+        .align_x(Horizontal::Center)
+        .align_y(Vertical::Center)
+        .into()
+}
+
+/// Routes the messages of a task started by a tab or a tool window back to where it started.
+fn routed(
+    action: cosmic::Action<Message>,
+    route: impl Fn(Box<Message>) -> Message,
+) -> cosmic::Action<Message> {
+    match action {
+        cosmic::Action::App(message) => cosmic::Action::App(route(Box::new(message))),
+        action => action,
+    }
+}
+
 /// The page to display in the application.
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Page {
     UnixTimeConverter,
     DataConverterFormatter,
@@ -388,7 +1270,9 @@ pub enum Page {
     UrlEncoderDecoder,
     JwtDebugger,
     LoremIpsumGenerator,
-    // Key Creation/Conversion
+    Pipeline,
+    TextDiff,
+    KeyCreatorConverter,
     // Hash Generator
     // UUID generator
     // Password creator
@@ -399,16 +1283,230 @@ pub enum Page {
     // CRON Generator
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+impl Page {
+    /// The registration of the page's tool.
+    fn tool(self) -> &'static registry::ToolInfo {
+        registry::TOOLS
+            .iter()
+            .copied()
+            .find(|tool| tool.page == self)
+            .expect("every page is registered")
+    }
+
+    /// A stable name of the page, which is also the id of its title in the translations.
+    pub fn id(self) -> &'static str {
+        self.tool().id
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        registry::TOOLS
+            .iter()
+            .find(|tool| tool.id == id)
+            .map(|tool| tool.page)
+    }
+
+    /// A new instance of the page, with its options at their defaults.
+    fn new_utility_page(self) -> Box<dyn UtilityPage> {
+        (self.tool().new_page)()
+    }
+
+    /// The page handling a message, `None` for messages of the application itself.
+    fn for_message(message: &Message) -> Option<Self> {
+        match message {
+            Message::Tool(page, _) => Some(*page),
+            _ => None,
+        }
+    }
+
+    /// The page of a tool addressed by name.
+    fn for_tool(tool: Tool) -> Self {
+        registry::TOOLS
+            .iter()
+            .find(|info| info.service == Some(tool))
+            .map(|info| info.page)
+            .expect("every tool is served by a page")
+    }
+
+    /// The page whose tool takes content of the given kind as input.
+    fn for_content(kind: ContentKind) -> Self {
+        registry::TOOLS
+            .iter()
+            .find(|tool| (tool.takes)(kind))
+            .map(|tool| tool.page)
+            .expect("every kind of content is taken by a page")
+    }
+}
+
+/// Actions of the menu bar, ordered as they are listed in the shortcuts drawer.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum MenuAction {
+    Edit(EditAction),
+    PasteAnywhere,
+    CommandPalette,
+    NextTool,
+    PreviousTool,
+    NewTab,
+    CloseTab,
+    RenameTab,
+    OpenWindow,
+    /// Switches to the tool at this position of the nav bar.
+    Tool(usize),
+    History,
+    Sessions,
+    Tools,
+    Shortcuts,
+    RememberInputs,
     About,
 }
 
+impl MenuAction {
+    /// The label of the action in the menus and the shortcuts drawer.
+    fn title(self, nav: &nav_bar::Model) -> String {
+        match self {
+            MenuAction::Edit(EditAction::Convert) => fl!("convert"),
+            MenuAction::Edit(EditAction::Swap) => fl!("swap"),
+            MenuAction::Edit(EditAction::CopyOutput) => fl!("copy-output"),
+            MenuAction::Edit(EditAction::PasteInput) => fl!("paste-input"),
+            MenuAction::Edit(EditAction::Clear) => fl!("clear"),
+            MenuAction::PasteAnywhere => fl!("paste-anywhere"),
+            MenuAction::CommandPalette => fl!("command-palette"),
+            MenuAction::NextTool => fl!("next-tool"),
+            MenuAction::PreviousTool => fl!("previous-tool"),
+            MenuAction::NewTab => fl!("new-tab"),
+            MenuAction::CloseTab => fl!("close-tab"),
+            MenuAction::RenameTab => fl!("rename-tab"),
+            MenuAction::OpenWindow => fl!("open-in-new-window"),
+            MenuAction::Tool(index) => nav
+                .iter()
+                .nth(index)
+                .and_then(|id| nav.text(id))
+                .unwrap_or_default()
+                .to_string(),
+            MenuAction::History => fl!("history"),
+            MenuAction::Sessions => fl!("sessions"),
+            MenuAction::Tools => fl!("manage-tools"),
+            MenuAction::Shortcuts => fl!("keyboard-shortcuts"),
+            MenuAction::RememberInputs => fl!("remember-inputs"),
+            MenuAction::About => format!("{} {}", fl!("about"), fl!("app-title")),
+        }
+    }
+
+    fn button(self, nav: &nav_bar::Model) -> menu::Item<MenuAction, String> {
+        menu::Item::Button(self.title(nav), None, self)
+    }
+}
+
+/// Whether a key press is bound to an action of the app.
+pub(crate) fn is_key_bind(modifiers: keyboard::Modifiers, key: &Key) -> bool {
+    KEY_BINDS
+        .keys()
+        .any(|key_bind| key_bind.matches(modifiers, key))
+}
+
+fn key_binds() -> HashMap<KeyBind, MenuAction> {
+    let mut key_binds = HashMap::new();
+    let mut bind = |modifiers: &[Modifier], key: Key, action| {
+        key_binds.insert(
+            KeyBind {
+                modifiers: modifiers.to_vec(),
+                key,
+            },
+            action,
+        );
+    };
+
+    bind(
+        &[Modifier::Ctrl],
+        Key::Named(key::Named::Enter),
+        MenuAction::Edit(EditAction::Convert),
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Character("s".into()),
+        MenuAction::Edit(EditAction::Swap),
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Character("c".into()),
+        MenuAction::Edit(EditAction::CopyOutput),
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Character("v".into()),
+        MenuAction::Edit(EditAction::PasteInput),
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Named(key::Named::Delete),
+        MenuAction::Edit(EditAction::Clear),
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Alt],
+        Key::Character("v".into()),
+        MenuAction::PasteAnywhere,
+    );
+    bind(
+        &[Modifier::Ctrl],
+        Key::Character("k".into()),
+        MenuAction::CommandPalette,
+    );
+    bind(
+        &[Modifier::Ctrl],
+        Key::Named(key::Named::Tab),
+        MenuAction::NextTool,
+    );
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Named(key::Named::Tab),
+        MenuAction::PreviousTool,
+    );
+    bind(
+        &[Modifier::Ctrl],
+        Key::Character("t".into()),
+        MenuAction::NewTab,
+    );
+    bind(
+        &[Modifier::Ctrl],
+        Key::Character("w".into()),
+        MenuAction::CloseTab,
+    );
+    bind(&[], Key::Named(key::Named::F2), MenuAction::RenameTab);
+    bind(
+        &[Modifier::Ctrl, Modifier::Shift],
+        Key::Character("n".into()),
+        MenuAction::OpenWindow,
+    );
+    // Ctrl+1 to Ctrl+9 switch to the first nine tools.
+    for index in 0..registry::TOOLS.len().min(9) {
+        bind(
+            &[Modifier::Ctrl],
+            Key::Character((index + 1).to_string().into()),
+            MenuAction::Tool(index),
+        );
+    }
+    key_binds
+}
+
 impl menu::action::MenuAction for MenuAction {
     type Message = Message;
 
     fn message(&self) -> Self::Message {
         match self {
+            MenuAction::Edit(action) => Message::Edit(*action),
+            MenuAction::PasteAnywhere => Message::PasteAnywhere,
+            MenuAction::CommandPalette => Message::OpenCommandPalette,
+            MenuAction::NextTool => Message::CycleTool(1),
+            MenuAction::PreviousTool => Message::CycleTool(-1),
+            MenuAction::NewTab => Message::NewTab,
+            MenuAction::CloseTab => Message::CloseTab(None),
+            MenuAction::RenameTab => Message::RenameTab,
+            MenuAction::OpenWindow => Message::OpenWindow,
+            MenuAction::Tool(index) => Message::ActivateTool(*index),
+            MenuAction::History => Message::ToggleContextPage(ContextPage::History),
+            MenuAction::Sessions => Message::ToggleContextPage(ContextPage::Sessions),
+            MenuAction::Tools => Message::ToggleContextPage(ContextPage::Tools),
+            MenuAction::Shortcuts => Message::ToggleContextPage(ContextPage::Shortcuts),
+            MenuAction::RememberInputs => Message::ToggleRememberInputs,
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
         }
     }
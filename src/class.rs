@@ -1,4 +1,7 @@
-use cosmic::{iced::core::Color, prelude::ColorExt, widget::text_input::Appearance};
+use {
+    cosmic::{iced::core::Color, prelude::ColorExt, widget::text_input::Appearance},
+    dev_toolbox_core::diff::LineKind,
+};
 
 pub(crate) fn text_editor_class(
     theme: &cosmic::Theme,
@@ -88,3 +91,55 @@ pub(crate) fn text_input_style() -> cosmic::theme::TextInput {
         disabled: Box::new(text_input_default_appearance),
     }
 }
+
+pub(crate) fn status_banner_style(
+    theme: &cosmic::Theme,
+    is_error: bool,
+) -> cosmic::widget::container::Style {
+    let cosmic = theme.cosmic();
+
+    let color: Color = if is_error {
+        cosmic.destructive.base
+    } else {
+        cosmic.success.base
+    }
+    .into();
+    let mut background = color;
+    background.a = 0.15;
+
+    cosmic::widget::container::Style {
+        background: Some(background.into()),
+        border: cosmic::iced::Border {
+            radius: cosmic.corner_radii.radius_s.into(),
+            width: 1.0,
+            color,
+        },
+        ..Default::default()
+    }
+}
+
+pub(crate) fn diff_line_style(
+    theme: &cosmic::Theme,
+    kind: LineKind,
+    changed: bool,
+) -> cosmic::widget::container::Style {
+    let cosmic = theme.cosmic();
+
+    let mut background: Color = match kind {
+        LineKind::Unchanged => return cosmic::widget::container::Style::default(),
+        LineKind::Removed => cosmic.destructive.base,
+        LineKind::Added => cosmic.success.base,
+    }
+    .into();
+    // Changed words stand out from the line they are in.
+    background.a = if changed { 0.4 } else { 0.12 };
+
+    cosmic::widget::container::Style {
+        background: Some(background.into()),
+        border: cosmic::iced::Border {
+            radius: cosmic.corner_radii.radius_xs.into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Headless command-line interface exposing the conversions of every utility page.
+
+use {
+    chrono::{FixedOffset, Local},
+    clap::{Parser, Subcommand, ValueEnum},
+    dev_toolbox_core::{
+        base64,
+        bytes::{ByteBuffer, Rendering},
+        data_format::{self, INDENTS},
+        gzip,
+        jwt::{self, JwtKeys, KeyEncoding},
+        lorem,
+        pipeline::Recipe,
+        service::{Tool, ToolRequest},
+        unix_time::{self, TimestampUnit},
+        url,
+    },
+    std::{
+        fs,
+        io::{self, Read, Write},
+        path::PathBuf,
+    },
+};
+
+/// Everyday utilities for developers.
+///
+/// Runs the graphical application when no subcommand is given.
+#[derive(Debug, Parser)]
+#[command(
+    name = "dev-toolbox",
+    version,
+    about,
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub open: OpenArgs,
+}
+
+/// The tool the graphical application opens with, given as a URI or by name.
+#[derive(Debug, clap::Args)]
+pub struct OpenArgs {
+    /// URI of the tool to open, such as `devtoolbox://base64/decode?data=aGk%3D`
+    #[arg(conflicts_with = "tool")]
+    uri: Option<ToolRequest>,
+    /// Tool to open, named like the subcommands
+    #[arg(long, value_parser = parse_tool)]
+    tool: Option<Tool>,
+    /// Operation to select, the first one of the tool when omitted
+    #[arg(long, requires = "tool")]
+    operation: Option<String>,
+    /// Input to fill in
+    #[arg(long, requires = "tool", conflicts_with = "input_file")]
+    input: Option<String>,
+    /// File of the input to fill in, reads standard input when `-`
+    #[arg(long, requires = "tool")]
+    input_file: Option<PathBuf>,
+}
+
+impl OpenArgs {
+    /// The tool to open, with the input file read.
+    pub fn request(self) -> Result<Option<ToolRequest>, String> {
+        let Some(tool) = self.tool else {
+            return Ok(self.uri);
+        };
+        let input = match (self.input, self.input_file) {
+            (Some(input), _) => input,
+            (None, Some(path)) => read_input(Some(path))?,
+            (None, None) => String::new(),
+        };
+        ToolRequest::new(tool, self.operation.as_deref(), input)
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Encode or decode Base64 strings
+    Base64 {
+        #[arg(value_enum)]
+        operation: CodecOperation,
+        /// Use the URL-safe alphabet
+        #[arg(long)]
+        url_safe: bool,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Convert and format JSON, YAML, XML and TOML documents
+    Convert {
+        #[arg(long, value_enum)]
+        from: DataFormat,
+        #[arg(long, value_enum)]
+        to: DataFormat,
+        #[arg(long, default_value_t = INDENTS[0], value_parser = parse_indent)]
+        indent: usize,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Compress text to Base64-encoded GZip data or decompress it
+    Gzip {
+        #[arg(value_enum)]
+        operation: CompressionOperation,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Percent-encode or decode URLs
+    Url {
+        #[arg(value_enum)]
+        operation: CodecOperation,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Decode, verify and sign JSON Web Tokens
+    Jwt {
+        #[command(subcommand)]
+        operation: JwtOperation,
+    },
+    /// Generate placeholder text
+    Lorem {
+        #[arg(long, value_enum, default_value_t = LoremUnit::Words)]
+        unit: LoremUnit,
+        #[arg(long, default_value_t = 20)]
+        amount: usize,
+    },
+    /// Run the steps of a recipe saved by the Pipeline page, printing the last output
+    Pipeline {
+        /// Recipe file, read as JSON when its extension is `.json` and as TOML otherwise
+        #[arg(long)]
+        recipe: PathBuf,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Convert a Unix timestamp to human readable dates
+    Time {
+        /// Unix timestamp, defaults to the current time
+        timestamp: Option<i64>,
+        /// Treat the timestamp as epoch milliseconds
+        #[arg(long)]
+        milliseconds: bool,
+        /// Timezone offset such as `+05:30`, defaults to the local timezone
+        #[arg(long, allow_hyphen_values = true)]
+        offset: Option<FixedOffset>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum JwtOperation {
+    /// Print the header and claims of a token, verifying it when a key is given
+    Decode {
+        #[command(flatten)]
+        key: JwtKeyArgs,
+        /// Input file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+    /// Sign a token from a JSON header and claims
+    Encode {
+        /// Header as a JSON object containing `alg`
+        #[arg(long, default_value = r#"{"alg":"HS256","typ":"JWT"}"#)]
+        header: String,
+        #[command(flatten)]
+        key: JwtKeyArgs,
+        /// Claims file, reads standard input when omitted or `-`
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+pub struct JwtKeyArgs {
+    /// Symmetric key for HMAC algorithms
+    #[arg(long)]
+    secret: Option<String>,
+    /// Decode the symmetric key as Base64
+    #[arg(long)]
+    base64_secret: bool,
+    /// PEM, DER, JWK or OpenSSH file holding the public key for verification or private key for
+    /// signing, or a JWK Set to verify with
+    #[arg(long)]
+    key_file: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CodecOperation {
+    Encode,
+    Decode,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CompressionOperation {
+    Compress,
+    Decompress,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Xml,
+    Toml,
+}
+
+impl From<DataFormat> for data_format::DataFormat {
+    fn from(format: DataFormat) -> Self {
+        match format {
+            DataFormat::Json => data_format::DataFormat::Json,
+            DataFormat::Yaml => data_format::DataFormat::Yaml,
+            DataFormat::Xml => data_format::DataFormat::Xml,
+            DataFormat::Toml => data_format::DataFormat::Toml,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum LoremUnit {
+    Words,
+    Sentences,
+    Paragraphs,
+}
+
+impl From<LoremUnit> for lorem::LoremUnit {
+    fn from(unit: LoremUnit) -> Self {
+        match unit {
+            LoremUnit::Words => lorem::LoremUnit::Words,
+            LoremUnit::Sentences => lorem::LoremUnit::Sentences,
+            LoremUnit::Paragraphs => lorem::LoremUnit::Paragraphs,
+        }
+    }
+}
+
+/// Runs a command and returns the process exit code.
+pub fn run(command: Command) -> i32 {
+    match execute(command) {
+        Ok(output) => {
+            let mut stdout = io::stdout().lock();
+            let _ = stdout.write_all(output.as_bytes());
+            // Binary output is written untouched so that it can be redirected to a file.
+            if output.preferred_rendering() == Rendering::Text
+                && !output.as_bytes().ends_with(b"\n")
+            {
+                let _ = stdout.write_all(b"\n");
+            }
+            0
+        }
+        Err(err) => {
+            eprintln!("dev-toolbox: {}", err);
+            1
+        }
+    }
+}
+
+fn execute(command: Command) -> Result<ByteBuffer, String> {
+    match command {
+        Command::Base64 {
+            operation,
+            url_safe,
+            file,
+        } => {
+            let input = read_input_bytes(file)?;
+            match operation {
+                // Text is trimmed like on the page, binary files are encoded as they are.
+                CodecOperation::Encode => Ok(match String::from_utf8(input) {
+                    Ok(text) => base64::encode(&text, url_safe),
+                    Err(err) => base64::encode_bytes(err.as_bytes(), url_safe),
+                }
+                .into()),
+                CodecOperation::Decode => base64::decode_bytes(&input, url_safe)
+                    .map_err(|err| format!("Base64 decoding error: {}", err)),
+            }
+        }
+        Command::Convert {
+            from,
+            to,
+            indent,
+            file,
+        } => {
+            let input = read_input(file)?;
+            data_format::convert(&input, from.into(), to.into(), indent)
+                .map(ByteBuffer::from)
+                .map_err(|err| err.to_string())
+        }
+        Command::Gzip { operation, file } => {
+            let input = read_input_bytes(file)?;
+            match operation {
+                CompressionOperation::Compress => Ok(gzip::compress_bytes(&input).into()),
+                CompressionOperation::Decompress => {
+                    gzip::decompress_bytes(&input).map_err(|err| err.to_string())
+                }
+            }
+        }
+        Command::Url { operation, file } => {
+            let input = read_input(file)?;
+            let input = input.trim_end_matches(['\r', '\n']);
+            match operation {
+                CodecOperation::Encode => Ok(url::encode(input).into()),
+                CodecOperation::Decode => url::decode(input)
+                    .map(ByteBuffer::from)
+                    .map_err(|err| format!("URL decoding error: {}", err)),
+            }
+        }
+        Command::Jwt { operation } => match operation {
+            JwtOperation::Decode { key, file } => {
+                let token = read_input(file)?;
+                let header = jwt::decode_header(&token).map_err(|err| err.to_string())?;
+                let claims = jwt::decode_claims(&token).map_err(|err| err.to_string())?;
+                if key.secret.is_some() || key.key_file.is_some() {
+                    jwt::verify_signature(&token, header.alg, &key.keys()?)
+                        .map_err(|err| err.to_string())?;
+                }
+                Ok(serde_json::to_string_pretty(
+                    &serde_json::json!({ "header": header, "claims": claims }),
+                )
+                .unwrap()
+                .into())
+            }
+            JwtOperation::Encode { header, key, file } => {
+                let claims = read_input(file)?;
+                let header = jwt::parse_header(&header).map_err(|err| err.to_string())?;
+                jwt::encode_token(&header, &claims, &key.keys()?)
+                    .map(ByteBuffer::from)
+                    .map_err(|err| err.to_string())
+            }
+        },
+        Command::Lorem { unit, amount } => Ok(lorem::generate(unit.into(), amount).into()),
+        Command::Pipeline { recipe, file } => {
+            let format = match data_format::DataFormat::from_path(&recipe) {
+                Some(data_format::DataFormat::Json) => data_format::DataFormat::Json,
+                _ => data_format::DataFormat::Toml,
+            };
+            let recipe = fs::read_to_string(&recipe)
+                .map_err(|err| format!("{}: {}", recipe.display(), err))
+                .and_then(|input| Recipe::parse(&input, format).map_err(|err| err.to_string()))?;
+            let mut output = ByteBuffer::from(read_input_bytes(file)?);
+            for (index, result) in recipe.run(output.clone()).into_iter().enumerate() {
+                output = result.map_err(|err| format!("step {}: {}", index + 1, err))?;
+            }
+            Ok(output)
+        }
+        Command::Time {
+            timestamp,
+            milliseconds,
+            offset,
+        } => {
+            let unit = if milliseconds {
+                TimestampUnit::Milliseconds
+            } else {
+                TimestampUnit::Seconds
+            };
+            let offset_seconds = offset
+                .unwrap_or_else(|| *Local::now().offset())
+                .local_minus_utc();
+            let formatted_time = unix_time::format_timestamp(
+                timestamp.unwrap_or_else(|| unit.now()),
+                unit,
+                offset_seconds,
+            )
+            .map_err(|err| err.to_string())?;
+            Ok([
+                formatted_time.iso_8601,
+                formatted_time.email,
+                formatted_time.dmy,
+                formatted_time.human_readable,
+            ]
+            .join("\n")
+            .into())
+        }
+    }
+}
+
+impl JwtKeyArgs {
+    fn keys(&self) -> Result<JwtKeys, String> {
+        let pem = match &self.key_file {
+            Some(path) => {
+                let bytes = fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+                // Binary DER keys are read as Base64, like the key editors do.
+                String::from_utf8(bytes)
+                    .unwrap_or_else(|err| base64::encode_bytes(err.as_bytes(), false))
+            }
+            None => String::new(),
+        };
+        Ok(JwtKeys {
+            symmetric_key: self.secret.clone().unwrap_or_default(),
+            key_encoding: if self.base64_secret {
+                KeyEncoding::Base64
+            } else {
+                KeyEncoding::Utf8
+            },
+            public_key: pem.clone(),
+            private_key: pem,
+        })
+    }
+}
+
+fn parse_indent(indent: &str) -> Result<usize, String> {
+    indent
+        .parse::<usize>()
+        .ok()
+        .filter(|indent| INDENTS.contains(indent))
+        .ok_or_else(|| format!("expected one of {:?}", INDENTS))
+}
+
+fn parse_tool(name: &str) -> Result<Tool, String> {
+    Tool::from_name(name).ok_or_else(|| {
+        let names: Vec<_> = Tool::ALL.into_iter().map(Tool::name).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn read_input(file: Option<PathBuf>) -> Result<String, String> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        _ => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("stdin: {}", err))?;
+            Ok(input)
+        }
+    }
+}
+
+fn read_input_bytes(file: Option<PathBuf>) -> Result<Vec<u8>, String> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            fs::read(&path).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        _ => {
+            let mut input = Vec::new();
+            io::stdin()
+                .read_to_end(&mut input)
+                .map_err(|err| format!("stdin: {}", err))?;
+            Ok(input)
+        }
+    }
+}
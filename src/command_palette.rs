@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! The Ctrl+K palette searching the tools and the actions of their pages.
+
+use {
+    crate::{Message, app::Page, fl},
+    cosmic::{
+        self, Element,
+        iced::{Alignment, Length},
+        widget,
+    },
+    dev_toolbox_core::fuzzy,
+    std::sync::LazyLock,
+};
+
+/// Matches listed below the search field.
+const MAX_RESULTS: usize = 8;
+
+pub(crate) static INPUT_ID: LazyLock<widget::Id> =
+    LazyLock::new(|| widget::Id::new("command-palette-input"));
+
+/// An action of a page, applied by switching to the page and handling its messages in order.
+#[derive(Clone, Debug)]
+pub(crate) struct Command {
+    pub(crate) title: String,
+    pub(crate) messages: Vec<Message>,
+}
+
+impl Command {
+    pub(crate) fn new(title: String, messages: impl IntoIterator<Item = Message>) -> Self {
+        Self {
+            title,
+            messages: messages.into_iter().collect(),
+        }
+    }
+}
+
+/// A command of the palette and the page it switches to.
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    pub(crate) page: Page,
+    /// The title of the page, followed by the title of the command when there is one.
+    pub(crate) title: String,
+    pub(crate) messages: Vec<Message>,
+}
+
+pub(crate) struct CommandPalette {
+    query: String,
+    entries: Vec<Entry>,
+    /// Indices of the entries matching the query, best first.
+    matches: Vec<usize>,
+    /// The match run when the query is submitted.
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub(crate) fn new(entries: Vec<Entry>) -> Self {
+        let matches = (0..entries.len()).collect();
+        Self {
+            query: String::new(),
+            entries,
+            matches,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn search(&mut self, query: String) {
+        self.matches = if query.trim().is_empty() {
+            (0..self.entries.len()).collect()
+        } else {
+            fuzzy::rank(
+                &query,
+                self.entries.iter().map(|entry| entry.title.as_str()),
+            )
+        };
+        self.query = query;
+        self.selected = 0;
+    }
+
+    /// Moves the selection by `offset` matches, wrapping around at either end.
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        let shown = self.matches.len().min(MAX_RESULTS);
+        if shown > 0 {
+            self.selected = (self.selected as isize + offset).rem_euclid(shown as isize) as usize;
+        }
+    }
+
+    /// The entry of a match, or of the selected match when `index` is `None`.
+    pub(crate) fn into_entry(mut self, index: Option<usize>) -> Option<Entry> {
+        let entry = *self.matches.get(index.unwrap_or(self.selected))?;
+        Some(self.entries.swap_remove(entry))
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, Message> {
+        let space_xxs = cosmic::theme::spacing().space_xxs;
+
+        let search_input =
+            widget::text_input::search_input(fl!("command-palette", "placeholder"), &self.query)
+                .id(INPUT_ID.clone())
+                .on_input(Message::CommandPaletteQueryChanged)
+                .on_submit(|_| Message::RunCommand(None));
+
+        let mut results = widget::column::with_capacity(MAX_RESULTS).spacing(space_xxs);
+        for (index, &entry) in self.matches.iter().take(MAX_RESULTS).enumerate() {
+            results = results.push(
+                widget::button::custom(
+                    widget::text::body(&self.entries[entry].title)
+                        .width(Length::Fill)
+                        .align_y(Alignment::Center),
+                )
+                .class(if index == self.selected {
+                    cosmic::theme::Button::Suggested
+                } else {
+                    cosmic::theme::Button::MenuItem
+                })
+                .width(Length::Fill)
+                .on_press(Message::RunCommand(Some(index))),
+            );
+        }
+        if self.matches.is_empty() {
+            results = results.push(widget::text::body(fl!("command-palette", "no-results")));
+        }
+
+        widget::dialog()
+            .title(fl!("command-palette"))
+            .control(
+                widget::column::with_capacity(2)
+                    .push(search_input)
+                    .push(results)
+                    .spacing(cosmic::theme::spacing().space_s),
+            )
+            .secondary_action(
+                widget::button::standard(fl!("cancel")).on_press(Message::CloseCommandPalette),
+            )
+            .into()
+    }
+}
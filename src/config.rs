@@ -1,9 +1,297 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
+use {
+    crate::{app::Page, utility_pages::conversion},
+    cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry},
+    dev_toolbox_core::{claims::ClaimRules, jwt::Algorithm, key::KeyFormat, pipeline::Step},
+    serde::{Deserialize, Serialize},
+};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+/// Settings persisted between application runs, with one entry per utility page.
+///
+/// Dropdown selections are stored as indices into the options of their page.
+#[derive(Debug, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
 pub struct Config {
-    demo: String,
+    /// Whether the last input of every page is saved along with its options.
+    pub remember_inputs: bool,
+    /// Pages whose conversions are not kept in the history, by their id.
+    pub history_excluded_pages: Vec<String>,
+    /// The ids of the tools in the order of the nav bar, tools missing from it follow in their
+    /// default order.
+    pub tool_order: Vec<String>,
+    /// Tools hidden from the nav bar, by their id.
+    pub disabled_tools: Vec<String>,
+    pub unix_time_converter: UnixTimeConverterConfig,
+    pub data_converter_formatter: DataConverterFormatterConfig,
+    pub base64_string_encoder_decoder: Base64StringEncoderDecoderConfig,
+    pub gzip_compressor_decompressor: GZipCompressorDecompressorConfig,
+    pub url_encoder_decoder: UrlEncoderDecoderConfig,
+    pub jwt_debugger: JwtDebuggerConfig,
+    pub lorem_ipsum_generator: LoremIpsumGeneratorConfig,
+    pub pipeline: PipelineConfig,
+    pub text_diff: TextDiffConfig,
+    pub key_creator_converter: KeyCreatorConverterConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            remember_inputs: true,
+            // Tokens are credentials, so they are only kept when asked to.
+            history_excluded_pages: vec!["jwt-debugger".to_string()],
+            tool_order: Vec::new(),
+            disabled_tools: Vec::new(),
+            unix_time_converter: UnixTimeConverterConfig::default(),
+            data_converter_formatter: DataConverterFormatterConfig::default(),
+            base64_string_encoder_decoder: Base64StringEncoderDecoderConfig::default(),
+            gzip_compressor_decompressor: GZipCompressorDecompressorConfig::default(),
+            url_encoder_decoder: UrlEncoderDecoderConfig::default(),
+            jwt_debugger: JwtDebuggerConfig::default(),
+            lorem_ipsum_generator: LoremIpsumGeneratorConfig::default(),
+            pipeline: PipelineConfig::default(),
+            text_diff: TextDiffConfig::default(),
+            key_creator_converter: KeyCreatorConverterConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Replaces the entry of a page without writing it, such as to load a tab of a session.
+    pub fn replace_page_config(&mut self, page_config: PageConfig) {
+        match page_config {
+            PageConfig::UnixTimeConverter(config) => self.unix_time_converter = config,
+            PageConfig::DataConverterFormatter(config) => self.data_converter_formatter = config,
+            PageConfig::Base64StringEncoderDecoder(config) => {
+                self.base64_string_encoder_decoder = config;
+            }
+            PageConfig::GZipCompressorDecompressor(config) => {
+                self.gzip_compressor_decompressor = config;
+            }
+            PageConfig::UrlEncoderDecoder(config) => self.url_encoder_decoder = config,
+            PageConfig::JwtDebugger(config) => self.jwt_debugger = config,
+            PageConfig::LoremIpsumGenerator(config) => self.lorem_ipsum_generator = config,
+            PageConfig::Pipeline(config) => self.pipeline = config,
+            PageConfig::TextDiff(config) => self.text_diff = config,
+            PageConfig::KeyCreatorConverter(config) => self.key_creator_converter = config,
+        }
+    }
+
+    /// Writes the entry of a page, returning whether it changed.
+    pub fn set_page_config(
+        &mut self,
+        config_handler: &cosmic_config::Config,
+        page_config: PageConfig,
+    ) -> Result<bool, cosmic_config::Error> {
+        match page_config {
+            PageConfig::UnixTimeConverter(config) => {
+                self.set_unix_time_converter(config_handler, config)
+            }
+            PageConfig::DataConverterFormatter(config) => {
+                self.set_data_converter_formatter(config_handler, config)
+            }
+            PageConfig::Base64StringEncoderDecoder(config) => {
+                self.set_base64_string_encoder_decoder(config_handler, config)
+            }
+            PageConfig::GZipCompressorDecompressor(config) => {
+                self.set_gzip_compressor_decompressor(config_handler, config)
+            }
+            PageConfig::UrlEncoderDecoder(config) => {
+                self.set_url_encoder_decoder(config_handler, config)
+            }
+            PageConfig::JwtDebugger(config) => self.set_jwt_debugger(config_handler, config),
+            PageConfig::LoremIpsumGenerator(config) => {
+                self.set_lorem_ipsum_generator(config_handler, config)
+            }
+            PageConfig::Pipeline(config) => self.set_pipeline(config_handler, config),
+            PageConfig::TextDiff(config) => self.set_text_diff(config_handler, config),
+            PageConfig::KeyCreatorConverter(config) => {
+                self.set_key_creator_converter(config_handler, config)
+            }
+        }
+    }
+}
+
+/// The entry of a single page, tagged with the id of the page when serialized.
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "tool", rename_all = "kebab-case")]
+pub enum PageConfig {
+    UnixTimeConverter(UnixTimeConverterConfig),
+    DataConverterFormatter(DataConverterFormatterConfig),
+    Base64StringEncoderDecoder(Base64StringEncoderDecoderConfig),
+    #[serde(rename = "gzip-compressor-decompressor")]
+    GZipCompressorDecompressor(GZipCompressorDecompressorConfig),
+    UrlEncoderDecoder(UrlEncoderDecoderConfig),
+    JwtDebugger(JwtDebuggerConfig),
+    LoremIpsumGenerator(LoremIpsumGeneratorConfig),
+    Pipeline(PipelineConfig),
+    TextDiff(TextDiffConfig),
+    KeyCreatorConverter(KeyCreatorConverterConfig),
+}
+
+impl PageConfig {
+    pub fn page(&self) -> Page {
+        match self {
+            PageConfig::UnixTimeConverter(_) => Page::UnixTimeConverter,
+            PageConfig::DataConverterFormatter(_) => Page::DataConverterFormatter,
+            PageConfig::Base64StringEncoderDecoder(_) => Page::Base64StringEncoderDecoder,
+            PageConfig::GZipCompressorDecompressor(_) => Page::GZipCompressorDecompressor,
+            PageConfig::UrlEncoderDecoder(_) => Page::UrlEncoderDecoder,
+            PageConfig::JwtDebugger(_) => Page::JwtDebugger,
+            PageConfig::LoremIpsumGenerator(_) => Page::LoremIpsumGenerator,
+            PageConfig::Pipeline(_) => Page::Pipeline,
+            PageConfig::TextDiff(_) => Page::TextDiff,
+            PageConfig::KeyCreatorConverter(_) => Page::KeyCreatorConverter,
+        }
+    }
+
+    /// Drops the input, keeping the options.
+    pub fn clear_input(&mut self) {
+        match self {
+            PageConfig::UnixTimeConverter(config) => config.timestamp.clear(),
+            PageConfig::DataConverterFormatter(config) => config.input.clear(),
+            PageConfig::Base64StringEncoderDecoder(config) => config.input.clear(),
+            PageConfig::GZipCompressorDecompressor(config) => config.input.clear(),
+            PageConfig::UrlEncoderDecoder(config) => config.input.clear(),
+            PageConfig::JwtDebugger(config) => config.token.clear(),
+            // The amount is an option of the generator rather than an input.
+            PageConfig::LoremIpsumGenerator(_) => {}
+            PageConfig::Pipeline(config) => config.input.clear(),
+            PageConfig::TextDiff(config) => {
+                config.original.clear();
+                config.changed.clear();
+            }
+            // Keys are never persisted.
+            PageConfig::KeyCreatorConverter(_) => {}
+        }
+    }
+
+    /// Whether the input may be saved with the options: when it is small enough to write
+    /// without stalling the window, and for a token only when asked to.
+    pub fn keeps_input(&self) -> bool {
+        let inputs = match self {
+            PageConfig::UnixTimeConverter(config) => vec![&config.timestamp],
+            PageConfig::DataConverterFormatter(config) => vec![&config.input],
+            PageConfig::Base64StringEncoderDecoder(config) => vec![&config.input],
+            PageConfig::GZipCompressorDecompressor(config) => vec![&config.input],
+            PageConfig::UrlEncoderDecoder(config) => vec![&config.input],
+            PageConfig::JwtDebugger(config) if !config.remember_token => return false,
+            PageConfig::JwtDebugger(config) => vec![&config.token],
+            PageConfig::LoremIpsumGenerator(_) | PageConfig::KeyCreatorConverter(_) => Vec::new(),
+            PageConfig::Pipeline(config) => vec![&config.input],
+            PageConfig::TextDiff(config) => vec![&config.original, &config.changed],
+        };
+        inputs.into_iter().all(|input| conversion::is_live(input))
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct UnixTimeConverterConfig {
+    pub timestamp_type: usize,
+    /// The UTC offset of the selected timezone, the local timezone is used when unset.
+    pub utc_offset_seconds: Option<i32>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DataConverterFormatterConfig {
+    pub input_format: usize,
+    pub output_format: usize,
+    pub indent: usize,
+    pub live_convert: bool,
+    /// Whether the changes between the input and the output are shown instead of the output.
+    pub show_diff: bool,
+    pub input: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Base64StringEncoderDecoderConfig {
+    pub operation: usize,
+    pub url_safe: bool,
+    pub live_convert: bool,
+    pub input: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct GZipCompressorDecompressorConfig {
+    pub operation: usize,
+    pub live_convert: bool,
+    pub input: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct UrlEncoderDecoderConfig {
+    pub operation: usize,
+    pub live_convert: bool,
+    pub input: String,
+}
+
+impl Default for UrlEncoderDecoderConfig {
+    fn default() -> Self {
+        Self {
+            // Decoding is the more common use of the page.
+            operation: 1,
+            live_convert: false,
+            input: String::default(),
+        }
+    }
+}
+
+/// Keys are never persisted, and the token they apply to only when `remember_token` is set.
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct JwtDebuggerConfig {
+    pub algorithm: Algorithm,
+    pub key_encoding: usize,
+    pub rsa_key_size: usize,
+    /// Whether the registered claims are checked against `claim_rules`.
+    pub validate_claims: bool,
+    pub claim_rules: ClaimRules,
+    /// Whether the token is saved along with the options, as asked to since it is a credential.
+    pub remember_token: bool,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct LoremIpsumGeneratorConfig {
+    pub unit: usize,
+    pub amount: String,
+}
+
+impl Default for LoremIpsumGeneratorConfig {
+    fn default() -> Self {
+        Self {
+            unit: 0,
+            amount: "20".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    pub steps: Vec<Step>,
+    pub input: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TextDiffConfig {
+    pub mode: usize,
+    pub ignore_whitespace: bool,
+    pub original: String,
+    pub changed: String,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct KeyCreatorConverterConfig {
+    pub key_type: usize,
+    pub output_format: KeyFormat,
 }
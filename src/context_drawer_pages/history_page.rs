@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Recent inputs of the current tool, kept on disk between runs.
+
+use {
+    crate::{Message, app::Page, context_drawer_pages::data_dir, fl},
+    chrono::{DateTime, Local},
+    cosmic::{
+        self, Element, Task,
+        iced::{Alignment, Length},
+        widget,
+    },
+    dev_toolbox_core::history::{History, HistoryEntry, MAX_ENTRY_SIZE},
+    std::{
+        fs::{OpenOptions, Permissions},
+        io::Write,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, PoisonError},
+    },
+};
+
+/// Characters of an input or output shown in the drawer.
+const PREVIEW_LENGTH: usize = 80;
+
+/// The history of every tool and the file it is stored in.
+pub(crate) struct HistoryPage {
+    pub(crate) history: History,
+    /// Unavailable when there is no data directory, the history then lasts until the app closes.
+    path: Option<PathBuf>,
+    /// Counts the saves, so that a save finishing after a newer one does not overwrite it.
+    saves: u64,
+    /// The number of the last save written to the file.
+    written: Arc<Mutex<u64>>,
+}
+
+impl HistoryPage {
+    /// Reads the history left by the previous run, starting over when it cannot be read.
+    pub(crate) fn load() -> Self {
+        let path = data_dir().map(|data_dir| data_dir.join("history.json"));
+        let history = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| History::from_json(&json).ok())
+            .unwrap_or_default();
+        Self {
+            history,
+            path,
+            saves: 0,
+            written: Arc::default(),
+        }
+    }
+
+    /// Writes the history to its file in the background.
+    pub(crate) fn save(&mut self) -> Task<cosmic::Action<Message>> {
+        let Some(path) = self.path.clone() else {
+            return Task::none();
+        };
+        self.saves += 1;
+        let save = self.saves;
+        let written = self.written.clone();
+        let json = self.history.to_json();
+        Task::future(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let mut written = written.lock().unwrap_or_else(PoisonError::into_inner);
+                if *written > save {
+                    return Ok(());
+                }
+                write_private(&path, &json)?;
+                *written = save;
+                Ok::<_, std::io::Error>(())
+            })
+            .await;
+            if let Ok(Err(err)) = result {
+                eprintln!("failed to save history: {err}");
+            }
+            cosmic::Action::None
+        })
+    }
+
+    pub(crate) fn get_context_drawer_page(
+        &self,
+        page: Page,
+        enabled: bool,
+    ) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let mut column = widget::column::with_capacity(3).spacing(space_s).push(
+            widget::checkbox(enabled)
+                .label(fl!("history", "keep"))
+                .on_toggle(Message::ToggleHistory),
+        );
+
+        let mut entries = self
+            .history
+            .entries()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.tool == page.id())
+            .peekable();
+        if entries.peek().is_none() {
+            return column
+                .push(widget::text::body(fl!("history", "empty")))
+                .into();
+        }
+
+        let mut section = widget::settings::section();
+        for (index, entry) in entries {
+            section = section.add(entry_view(index, entry));
+        }
+        column
+            .push(section)
+            .push(
+                widget::button::standard(fl!("history", "clear"))
+                    .leading_icon(widget::icon::from_name("edit-clear-all-symbolic"))
+                    .on_press(Message::ClearHistory),
+            )
+            .into()
+    }
+}
+
+fn entry_view(index: usize, entry: &HistoryEntry) -> Element<'_, Message> {
+    let space_xxs = cosmic::theme::spacing().space_xxs;
+
+    let time = DateTime::from_timestamp(entry.timestamp, 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    let text = widget::column::with_capacity(3)
+        .push(widget::text::monotext(preview(&entry.input)))
+        .push(widget::text::caption(format!(
+            "→ {}",
+            preview(&entry.output)
+        )))
+        .push(widget::text::caption(time))
+        .width(Length::Fill);
+
+    let pin_tooltip = if entry.pinned {
+        fl!("history", "unpin")
+    } else {
+        fl!("history", "pin")
+    };
+    let controls = widget::row::with_capacity(3)
+        .push(widget::tooltip(
+            widget::button::icon(widget::icon::from_name("view-pin-symbolic"))
+                .selected(entry.pinned)
+                .on_press(Message::PinHistory(index, !entry.pinned)),
+            widget::text(pin_tooltip),
+            widget::tooltip::Position::Bottom,
+        ))
+        .push(widget::tooltip(
+            widget::button::icon(widget::icon::from_name("edit-undo-symbolic"))
+                .on_press(Message::RestoreHistory(index)),
+            widget::text(fl!("history", "restore")),
+            widget::tooltip::Position::Bottom,
+        ))
+        .push(widget::tooltip(
+            widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                .on_press(Message::RemoveHistory(index)),
+            widget::text(fl!("history", "remove")),
+            widget::tooltip::Position::Bottom,
+        ))
+        .spacing(space_xxs);
+
+    widget::row::with_capacity(2)
+        .push(text)
+        .push(controls)
+        .spacing(space_xxs)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+/// Writes a file only its owner can read, as the history holds the inputs of the user.
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // The mode only applies to new files, older ones may be readable by others.
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
+
+/// The first line of a text, shortened to fit the drawer.
+fn preview(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    match line.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None if text.trim().contains('\n') => format!("{line}…"),
+        None => line.to_string(),
+    }
+}
+
+/// When the conversion of a page goes into the history: when it was asked for, when its output is
+/// copied or when the page is left, rather than on every conversion as you type.
+#[derive(Default)]
+pub(crate) struct Recording {
+    /// Whether the output shown converts the current input.
+    converted: bool,
+    /// Whether the running conversion was asked for, so that it is recorded once it finishes.
+    requested: bool,
+}
+
+impl Recording {
+    /// Notes a conversion asked for, rather than run as you type.
+    pub(crate) fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Notes the outcome of a conversion, returning whether it was asked for and is to be recorded
+    /// now.
+    pub(crate) fn finish(&mut self, succeeded: bool) -> bool {
+        self.converted = succeeded;
+        std::mem::take(&mut self.requested) && succeeded
+    }
+
+    /// Notes a change of the input or a new conversion of it, which the output no longer
+    /// converts.
+    pub(crate) fn clear(&mut self) {
+        self.converted = false;
+    }
+
+    /// Whether the output converts the current input, so that the two can be recorded.
+    pub(crate) fn is_converted(&self) -> bool {
+        self.converted
+    }
+}
+
+/// Asks the app to record the input and output of a page, unless there are none or they are too
+/// large to keep.
+pub(crate) fn record(page: Page, entry: Option<(String, String)>) -> Task<cosmic::Action<Message>> {
+    let Some((input, output)) = entry else {
+        return Task::none();
+    };
+    if input.len() + output.len() > MAX_ENTRY_SIZE {
+        return Task::none();
+    }
+    Task::done(cosmic::Action::App(Message::RecordHistory(
+        page, input, output,
+    )))
+}
@@ -1,7 +1,27 @@
 pub(crate) mod about_page;
+pub(crate) mod history_page;
+pub(crate) mod paste_suggestions_page;
+pub(crate) mod sessions_page;
+pub(crate) mod shortcuts_page;
+pub(crate) mod tools_page;
 
-use {crate::Message, cosmic::Element};
+use {
+    crate::{Message, app::AppModel},
+    cosmic::{Application, Element},
+    std::path::PathBuf,
+};
 
 pub(crate) trait ContextDrawerPage {
     fn get_context_drawer_page() -> Element<'static, Message>;
 }
+
+/// `$XDG_DATA_HOME/<app id>`, where `XDG_DATA_HOME` defaults to `~/.local/share`.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|data_home| !data_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(data_home.join(AppModel::APP_ID))
+}
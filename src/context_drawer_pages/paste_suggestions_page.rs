@@ -0,0 +1,62 @@
+use {
+    crate::{Message, fl},
+    cosmic::{Element, widget},
+    dev_toolbox_core::detect::{ContentKind, Detection},
+};
+
+/// The content read by the paste-anywhere action and the tools it was recognized for.
+pub(crate) struct PasteSuggestionsPage {
+    pub(crate) content: String,
+    /// Ranked from the most to the least confident detection.
+    pub(crate) detections: Vec<Detection>,
+    /// The detection whose tool received the content.
+    pub(crate) selected: usize,
+}
+
+impl PasteSuggestionsPage {
+    pub(crate) fn get_context_drawer_page(&self) -> Element<'_, Message> {
+        if self.detections.is_empty() {
+            return widget::text::body(if self.content.trim().is_empty() {
+                fl!("paste-anywhere", "empty")
+            } else {
+                fl!("paste-anywhere", "unrecognized")
+            })
+            .into();
+        }
+
+        let mut section = widget::settings::section().title(fl!("paste-anywhere", "did-you-mean"));
+        for (index, detection) in self.detections.iter().enumerate() {
+            let control: Element<'_, Message> = if index == self.selected {
+                widget::icon::from_name("object-select-symbolic")
+                    .size(16)
+                    .icon()
+                    .into()
+            } else {
+                widget::button::text(fl!("paste-anywhere", "open"))
+                    .on_press(Message::PasteAs(index))
+                    .into()
+            };
+            section = section.add(widget::settings::item(
+                format!(
+                    "{} ({}%)",
+                    content_kind_name(detection.kind),
+                    detection.confidence
+                ),
+                control,
+            ));
+        }
+        section.into()
+    }
+}
+
+fn content_kind_name(kind: ContentKind) -> String {
+    match kind {
+        ContentKind::Jwt => fl!("paste-anywhere", "jwt"),
+        ContentKind::Gzip => fl!("paste-anywhere", "gzip"),
+        ContentKind::PercentEncoded => fl!("paste-anywhere", "percent-encoded"),
+        ContentKind::UnixSeconds => fl!("paste-anywhere", "unix-seconds"),
+        ContentKind::UnixMilliseconds => fl!("paste-anywhere", "unix-milliseconds"),
+        ContentKind::Document(format) => fl!("document", format = format.name()),
+        ContentKind::Base64 => fl!("paste-anywhere", "base64"),
+    }
+}
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Named snapshots of the open tabs of every tool, kept on disk until they are deleted.
+
+use {
+    crate::{
+        Message, config::PageConfig, context_drawer_pages::data_dir, fl,
+        utility_pages::status::Status,
+    },
+    cosmic::{
+        self, Element,
+        iced::{Alignment, Length},
+        widget,
+    },
+    serde::{Deserialize, Serialize},
+    std::path::PathBuf,
+};
+
+/// The tabs open when the session was saved, tool by tool in the order of the nav bar.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Session {
+    pub(crate) tabs: Vec<SessionTab>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct SessionTab {
+    pub(crate) title: String,
+    pub(crate) page: PageConfig,
+}
+
+/// The saved sessions and the name the open tabs are saved under.
+pub(crate) struct SessionsPage {
+    pub(crate) name: String,
+    /// The names of the saved sessions, sorted.
+    names: Vec<String>,
+    pub(crate) status: Status,
+    /// Unavailable when there is no data directory, sessions then cannot be saved.
+    dir: Option<PathBuf>,
+}
+
+impl SessionsPage {
+    pub(crate) fn new() -> Self {
+        let mut sessions_page = Self {
+            name: String::new(),
+            names: Vec::new(),
+            status: Status::Idle,
+            dir: data_dir().map(|data_dir| data_dir.join("sessions")),
+        };
+        sessions_page.refresh();
+        sessions_page
+    }
+
+    /// Lists the sessions in the sessions directory again.
+    pub(crate) fn refresh(&mut self) {
+        self.names = self
+            .dir
+            .as_deref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == "json").then_some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        self.names.sort();
+    }
+
+    /// Saves the open tabs under the entered name, replacing a session of the same name.
+    pub(crate) fn save(&mut self, session: &Session) {
+        let result = self.path(&self.name).and_then(|path| {
+            let json = serde_json::to_string_pretty(session).map_err(|err| err.to_string())?;
+            path.parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, json))
+                .map_err(|err| err.to_string())
+        });
+        self.status = match result {
+            Ok(()) => Status::Success(fl!("sessions", "saved")),
+            Err(err) => Status::error(fl!("sessions", "save-failed"), err),
+        };
+        self.refresh();
+    }
+
+    /// Reads a saved session, reporting why it cannot be restored.
+    pub(crate) fn open(&mut self, name: &str) -> Option<Session> {
+        let result = self.path(name).and_then(|path| {
+            let json = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+            serde_json::from_str(&json).map_err(|err| err.to_string())
+        });
+        match result {
+            Ok(session) => {
+                self.name = name.to_string();
+                self.status = Status::Success(fl!("sessions", "restored"));
+                Some(session)
+            }
+            Err(err) => {
+                self.status = Status::error(fl!("sessions", "open-failed"), err);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn delete(&mut self, name: &str) {
+        if let Ok(path) = self.path(name)
+            && let Err(err) = std::fs::remove_file(path)
+        {
+            eprintln!("failed to delete session {name:?}: {err}");
+        }
+        self.status = Status::Idle;
+        self.refresh();
+    }
+
+    /// The file of a session, refusing names that would leave the sessions directory.
+    fn path(&self, name: &str) -> Result<PathBuf, String> {
+        let name = name.trim();
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(fl!("sessions", "invalid-name"));
+        }
+        self.dir
+            .as_deref()
+            .map(|dir| dir.join(format!("{name}.json")))
+            .ok_or_else(|| fl!("sessions", "no-data-directory"))
+    }
+
+    pub(crate) fn get_context_drawer_page(&self) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let save = widget::row::with_capacity(2)
+            .push(
+                widget::text_input(fl!("sessions", "name"), &self.name)
+                    .on_input(Message::SessionNameChanged)
+                    .on_submit(|_| Message::SaveSession)
+                    .width(Length::Fill),
+            )
+            .push(
+                widget::button::suggested(fl!("sessions", "save"))
+                    .on_press_maybe((!self.name.trim().is_empty()).then_some(Message::SaveSession)),
+            )
+            .spacing(space_s)
+            .align_y(Alignment::Center);
+
+        let column = widget::column::with_capacity(3)
+            .spacing(space_s)
+            .push(save)
+            .push_maybe(self.status.view());
+
+        if self.names.is_empty() {
+            return column
+                .push(widget::text::body(fl!("sessions", "empty")))
+                .into();
+        }
+
+        let mut section = widget::settings::section();
+        for name in &self.names {
+            section = section.add(session_view(name));
+        }
+        column.push(section).into()
+    }
+}
+
+fn session_view(name: &str) -> Element<'_, Message> {
+    let space_xxs = cosmic::theme::spacing().space_xxs;
+
+    widget::row::with_capacity(3)
+        .push(widget::text::body(name).width(Length::Fill))
+        .push(widget::tooltip(
+            widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                .on_press(Message::RestoreSession(name.to_string())),
+            widget::text(fl!("sessions", "restore")),
+            widget::tooltip::Position::Bottom,
+        ))
+        .push(widget::tooltip(
+            widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                .on_press(Message::DeleteSession(name.to_string())),
+            widget::text(fl!("sessions", "delete")),
+            widget::tooltip::Position::Bottom,
+        ))
+        .spacing(space_xxs)
+        .align_y(Alignment::Center)
+        .into()
+}
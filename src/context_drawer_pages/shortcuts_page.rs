@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! A cheat sheet of the keyboard shortcuts.
+
+use {
+    crate::Message,
+    cosmic::{Element, widget},
+};
+
+/// Lists the title and the keys of every shortcut, in the given order.
+pub(crate) fn get_context_drawer_page(
+    shortcuts: Vec<(String, String)>,
+) -> Element<'static, Message> {
+    let mut section = widget::settings::section();
+    for (title, keys) in shortcuts {
+        section = section.add(widget::settings::item(title, widget::text::monotext(keys)));
+    }
+    section.into()
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! The tools shown in the nav bar and their order.
+
+use {
+    crate::{Message, config::Config, fl, i18n::LANGUAGE_LOADER, utility_pages::registry},
+    cosmic::{
+        Element,
+        iced::Alignment,
+        widget::{self, icon},
+    },
+};
+
+/// Lists every tool with buttons moving it through the nav bar and a toggle hiding it.
+pub(crate) fn get_context_drawer_page(config: &Config) -> Element<'static, Message> {
+    let space_xxs = cosmic::theme::spacing().space_xxs;
+    let tools = registry::ordered(config);
+    let enabled = registry::enabled(config);
+
+    let mut section = widget::settings::section();
+    for (index, tool) in tools.iter().enumerate() {
+        let page = tool.page;
+        let is_enabled = enabled.iter().any(|other| other.page == page);
+
+        let move_up = widget::button::icon(icon::from_name("go-up-symbolic"))
+            .on_press_maybe((index > 0).then_some(Message::MoveToolUp(index)));
+        let move_down = widget::button::icon(icon::from_name("go-down-symbolic"))
+            .on_press_maybe((index + 1 < tools.len()).then_some(Message::MoveToolDown(index)));
+        let mut toggler = widget::toggler(is_enabled);
+        // The nav bar keeps at least one tool.
+        if !is_enabled || enabled.len() > 1 {
+            toggler = toggler.on_toggle(move |enabled| Message::ToggleTool(page, enabled));
+        }
+
+        let controls = widget::row::with_capacity(3)
+            .push(widget::tooltip(
+                move_up,
+                widget::text(fl!("manage-tools", "move-up")),
+                widget::tooltip::Position::Bottom,
+            ))
+            .push(widget::tooltip(
+                move_down,
+                widget::text(fl!("manage-tools", "move-down")),
+                widget::tooltip::Position::Bottom,
+            ))
+            .push(toggler)
+            .spacing(space_xxs)
+            .align_y(Alignment::Center);
+        section = section.add(widget::settings::item(
+            LANGUAGE_LOADER.get(tool.id),
+            controls,
+        ));
+    }
+    section.into()
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! The `io.github.avomar.DevToolbox` D-Bus interface, through which scripts, editor plugins and
+//! other apps run conversions in the running app or open one of its tools with their input.
+
+use {
+    crate::Message,
+    cosmic::iced::{
+        Subscription,
+        futures::{SinkExt, channel::mpsc},
+        stream,
+    },
+    dev_toolbox_core::service::{self, ServiceError, Tool, ToolRequest},
+    std::collections::HashMap,
+    zbus::{fdo, interface},
+};
+
+const BUS_NAME: &str = "io.github.avomar.DevToolbox";
+const OBJECT_PATH: &str = "/io/github/avomar/DevToolbox";
+/// The largest input converted, so that a caller cannot keep the app busy with one call.
+const MAX_INPUT_SIZE: usize = 8 * 1024 * 1024;
+
+struct DevToolbox {
+    /// Forwards requests to open a tool to the app.
+    output: mpsc::Sender<Message>,
+}
+
+#[interface(name = "io.github.avomar.DevToolbox")]
+impl DevToolbox {
+    /// Runs an operation of a tool on the input and returns the output, without showing the app.
+    /// The conversion runs on a blocking thread, so that the app stays responsive meanwhile.
+    async fn convert(
+        &self,
+        tool: String,
+        operation: String,
+        options: HashMap<String, String>,
+        input: String,
+    ) -> fdo::Result<String> {
+        if input.len() > MAX_INPUT_SIZE {
+            return Err(fdo::Error::InvalidArgs(format!(
+                "Input of {} bytes is larger than the limit of {MAX_INPUT_SIZE} bytes",
+                input.len()
+            )));
+        }
+        tokio::task::spawn_blocking(move || service::convert(&tool, &operation, &options, &input))
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))?
+            .map_err(|err| match err {
+                ServiceError::UnknownTool(_)
+                | ServiceError::UnknownOperation { .. }
+                | ServiceError::InvalidOption { .. } => fdo::Error::InvalidArgs(err.to_string()),
+                _ => fdo::Error::Failed(err.to_string()),
+            })
+    }
+
+    /// Shows a tool with the operation selected and the input filled in.
+    async fn open(&mut self, tool: &str, operation: &str, input: String) -> fdo::Result<()> {
+        let tool = Tool::from_name(tool)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("Unknown tool {tool:?}")))?;
+        let request = ToolRequest::new(tool, Some(operation), input)
+            .map_err(|err| fdo::Error::InvalidArgs(err.to_string()))?;
+        self.output
+            .send(Message::Prefill(request))
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+}
+
+/// Serves the interface on the session bus for as long as the app runs.
+pub(crate) fn subscription() -> Subscription<Message> {
+    Subscription::run(|| {
+        stream::channel(16, |output| async move {
+            if let Err(err) = serve(output).await {
+                eprintln!("failed to serve the D-Bus interface: {err}");
+            }
+        })
+    })
+}
+
+async fn serve(output: mpsc::Sender<Message>) -> zbus::Result<()> {
+    // Requests are handled until the connection is dropped.
+    let _connection = zbus::connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, DevToolbox { output })?
+        .build()
+        .await?;
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        cosmic::iced::futures::{StreamExt, try_join},
+        zbus::Connection,
+    };
+
+    /// A client connected to the interface served on a peer-to-peer connection, with the
+    /// server connection, which has to be kept for the interface to be served, and the messages
+    /// the interface sends to the app.
+    async fn connect() -> (Connection, Connection, mpsc::Receiver<Message>) {
+        let (server_stream, client_stream) = tokio::net::UnixStream::pair().unwrap();
+        let (output, messages) = mpsc::channel(1);
+        let server = zbus::connection::Builder::unix_stream(server_stream)
+            .server(zbus::Guid::generate())
+            .unwrap()
+            .p2p()
+            .serve_at(OBJECT_PATH, DevToolbox { output })
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client_stream)
+            .p2p()
+            .build();
+        let (server, client) = try_join!(server, client).unwrap();
+        (client, server, messages)
+    }
+
+    async fn call<B>(client: &Connection, method: &str, body: &B) -> zbus::Result<zbus::Message>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        client
+            .call_method(None::<&str>, OBJECT_PATH, Some(BUS_NAME), method, body)
+            .await
+    }
+
+    #[tokio::test]
+    async fn converts_and_maps_errors() {
+        let (client, _server, _messages) = connect().await;
+        let options = HashMap::<String, String>::new();
+
+        let reply = call(&client, "Convert", &("base64", "encode", &options, "hello"))
+            .await
+            .unwrap();
+        assert_eq!(reply.body().deserialize::<String>().unwrap(), "aGVsbG8=");
+
+        for (tool, operation) in [("rot13", "encode"), ("base64", "rot13")] {
+            let err = call(&client, "Convert", &(tool, operation, &options, "hello"))
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(fdo::Error::from(err), fdo::Error::InvalidArgs(_)),
+                "{tool} {operation}"
+            );
+        }
+        let err = call(
+            &client,
+            "Convert",
+            &("base64", "decode", &options, "not base64!"),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(fdo::Error::from(err), fdo::Error::Failed(_)));
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_input() {
+        let (client, _server, _messages) = connect().await;
+        let options = HashMap::<String, String>::new();
+
+        let input = "a".repeat(MAX_INPUT_SIZE);
+        call(&client, "Convert", &("base64", "encode", &options, &input))
+            .await
+            .unwrap();
+
+        let input = "a".repeat(MAX_INPUT_SIZE + 1);
+        let err = call(&client, "Convert", &("base64", "encode", &options, &input))
+            .await
+            .unwrap_err();
+        assert!(matches!(fdo::Error::from(err), fdo::Error::InvalidArgs(_)));
+    }
+
+    #[tokio::test]
+    async fn opens_tools_in_the_app() {
+        let (client, _server, mut messages) = connect().await;
+
+        call(&client, "Open", &("url", "decode", "a%20b"))
+            .await
+            .unwrap();
+        let Some(Message::Prefill(request)) = messages.next().await else {
+            panic!("expected a prefill request");
+        };
+        assert_eq!(request.tool, Tool::Url);
+        assert_eq!(request.operation, "decode");
+        assert_eq!(request.input, "a%20b");
+
+        let err = call(&client, "Open", &("url", "rot13", "a%20b"))
+            .await
+            .unwrap_err();
+        assert!(matches!(fdo::Error::from(err), fdo::Error::InvalidArgs(_)));
+    }
+}
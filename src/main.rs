@@ -2,25 +2,30 @@
 
 mod app;
 mod class;
+mod cli;
+mod command_palette;
 mod config;
 pub(crate) mod context_drawer_pages;
+mod dbus;
 mod i18n;
+mod tabs;
 pub(crate) mod utility_pages;
 
 use {
     crate::{
+        app::Page,
         config::Config,
-        utility_pages::{
-            base64_string_encoder_decoder_page::Base64StringEncoderDecoderMessage,
-            data_converter_formatter_page::DataConverterFormatterMessage,
-            gzip_compressor_decompressor_page::GZipCompressorDecompressorMessage,
-            jwt_debugger_page::JwtDebuggerMessage,
-            lorem_ipsum_generator_page::LoremIpsumGeneratorMessage,
-            unix_time_converter_page::UnixTimeConverterMessage,
-            url_encoder_decoder_page::UrlEncoderDecoderMessage,
+        utility_pages::{EditAction, registry::ToolMessage},
+    },
+    clap::Parser,
+    cosmic::{
+        iced::{
+            keyboard::{Key, Modifiers},
+            window,
         },
+        surface,
     },
-    cosmic::surface,
+    dev_toolbox_core::{detect::Detection, service::ToolRequest},
 };
 
 const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
@@ -32,6 +37,11 @@ const APP_ICON: &[u8] =
 pub enum ContextPage {
     #[default]
     About,
+    PasteSuggestions,
+    History,
+    Shortcuts,
+    Sessions,
+    Tools,
 }
 
 /// Messages emitted by the application and its widgets.
@@ -40,17 +50,83 @@ pub enum Message {
     LaunchUrl(String),
     ToggleContextPage(ContextPage),
     UpdateConfig(Config),
-    UnixTimeConverterMessage(UnixTimeConverterMessage),
-    DataConverterFormatterMessage(DataConverterFormatterMessage),
-    Base64StringEncoderDecoderMessage(Base64StringEncoderDecoderMessage),
-    GZipCompressorDecompressorMessage(GZipCompressorDecompressorMessage),
-    UrlEncoderDecoderMessage(UrlEncoderDecoderMessage),
-    JwtDebuggerMessage(JwtDebuggerMessage),
-    LoremIpsumGeneratorMessage(LoremIpsumGeneratorMessage),
+    /// Saves the pages changed, unless a page changed again since the given edit.
+    SaveConfig(u64),
+    /// Redraws the windows, so that what a page shows relative to the current time stays current.
+    Redraw,
+    ToggleRememberInputs,
+    /// A key pressed in a window and not handled by its widgets.
+    Key(window::Id, Modifiers, Key),
+    PasteAnywhere,
+    /// Detects the kind of pasted content in the background.
+    PasteDetected(String),
+    /// Fills the tool of the most confident detection with the pasted content.
+    PasteDetections(String, Vec<Detection>),
+    PasteAs(usize),
+    RecordHistory(Page, String, String),
+    RestoreHistory(usize),
+    PinHistory(usize, bool),
+    RemoveHistory(usize),
+    ClearHistory,
+    ToggleHistory(bool),
+    OpenCommandPalette,
+    CommandPaletteQueryChanged(String),
+    RunCommand(Option<usize>),
+    CloseCommandPalette,
+    Edit(EditAction),
+    /// Switches to the tool this many positions further down the nav bar, wrapping around.
+    CycleTool(isize),
+    ActivateTool(usize),
+    /// Opens the active tool in a window of its own.
+    OpenWindow,
+    /// A message emitted by the page of a tool window.
+    Window(window::Id, Box<Message>),
+    WindowClosed(window::Id),
+    NewTab,
+    ActivateTab(u64),
+    /// Closes a tab, or the active tab of the active tool when `None`.
+    CloseTab(Option<u64>),
+    /// Asks for a new title of the active tab.
+    RenameTab,
+    TabTitleChanged(String),
+    ConfirmRenameTab,
+    CancelRenameTab,
+    /// A message emitted by a task of a tab.
+    Tab(u64, Box<Message>),
+    /// Shows a tool with an operation selected and an input filled in, as asked on launch or
+    /// over D-Bus.
+    Prefill(ToolRequest),
+    SessionNameChanged(String),
+    SaveSession,
+    RestoreSession(String),
+    DeleteSession(String),
+    /// Shows or hides a tool in the nav bar.
+    ToggleTool(Page, bool),
+    /// Moves the tool at this position of the tools drawer one place up.
+    MoveToolUp(usize),
+    MoveToolDown(usize),
+    /// A message of the page of a tool.
+    Tool(Page, ToolMessage),
     Surface(surface::Action),
 }
 
 fn main() -> cosmic::iced::Result {
+    let cli = cli::Cli::parse();
+
+    // Run headless when a subcommand is given on the command line.
+    if let Some(command) = cli.command {
+        std::process::exit(cli::run(command));
+    }
+
+    // Read the tool to open before handing it to an instance that may already be running.
+    let request = match cli.open.request() {
+        Ok(request) => request,
+        Err(err) => {
+            eprintln!("dev-toolbox: {}", err);
+            std::process::exit(1);
+        }
+    };
+
     // Get the system's preferred languages.
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
 
@@ -64,6 +140,6 @@ fn main() -> cosmic::iced::Result {
             .min_height(180.0),
     );
 
-    // Starts the application's event loop with `()` as the application's flags.
-    cosmic::app::run::<app::AppModel>(settings, ())
+    // Starts the application's event loop, or opens the tool in the instance already running.
+    cosmic::app::run_single_instance::<app::AppModel>(settings, app::Flags { request })
 }
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Independent instances of the page of a tool, of which the active one is shown.
+
+use {
+    crate::{Message, fl, utility_pages::UtilityPage},
+    cosmic::{
+        self, Element,
+        iced::{Alignment, Length},
+        widget,
+    },
+    std::sync::LazyLock,
+};
+
+pub(crate) static RENAME_INPUT_ID: LazyLock<widget::Id> =
+    LazyLock::new(|| widget::Id::new("rename-tab-input"));
+
+/// An instance of the page of a tool, with a title of its own.
+pub(crate) struct Tab {
+    /// Unique among the tabs of every tool, so that the results of a task reach the tab that
+    /// started it.
+    pub(crate) id: u64,
+    pub(crate) title: String,
+    pub(crate) utility_page: Box<dyn UtilityPage>,
+}
+
+/// The tabs of a tool, of which there is always at least one.
+pub(crate) struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+}
+
+impl Tabs {
+    pub(crate) fn new(tab: Tab) -> Self {
+        Self {
+            tabs: vec![tab],
+            active: 0,
+        }
+    }
+
+    pub(crate) fn active(&self) -> &Tab {
+        &self.tabs[self.active]
+    }
+
+    pub(crate) fn active_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active]
+    }
+
+    /// The tab whose options and input are saved in the config.
+    pub(crate) fn first(&self) -> &Tab {
+        &self.tabs[0]
+    }
+
+    pub(crate) fn get_mut(&mut self, id: u64) -> Option<&mut Tab> {
+        self.tabs.iter_mut().find(|tab| tab.id == id)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Tab> {
+        self.tabs.iter()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Adds a tab after the others, leaving the active tab as it is.
+    pub(crate) fn push(&mut self, tab: Tab) {
+        self.tabs.push(tab);
+    }
+
+    pub(crate) fn activate(&mut self, id: u64) {
+        if let Some(index) = self.tabs.iter().position(|tab| tab.id == id) {
+            self.active = index;
+        }
+    }
+
+    /// Closes a tab unless it is the last one, activating its neighbour when it was active.
+    pub(crate) fn close(&mut self, id: u64) {
+        let Some(index) = self.tabs.iter().position(|tab| tab.id == id) else {
+            return;
+        };
+        if self.tabs.len() == 1 {
+            return;
+        }
+        self.tabs.remove(index);
+        if index < self.active || self.active == self.tabs.len() {
+            self.active -= 1;
+        }
+    }
+
+    /// A bar switching between the tabs, `None` while there is only one.
+    pub(crate) fn view(&self) -> Option<Element<'_, Message>> {
+        if self.tabs.len() == 1 {
+            return None;
+        }
+        let space_xxs = cosmic::theme::spacing().space_xxs;
+
+        let mut row = widget::row::with_capacity(self.tabs.len() + 2)
+            .spacing(space_xxs)
+            .align_y(Alignment::Center);
+        for (index, tab) in self.tabs.iter().enumerate() {
+            row = row.push(
+                widget::row::with_capacity(2)
+                    .push(
+                        widget::button::text(&tab.title)
+                            .class(if index == self.active {
+                                cosmic::theme::Button::Suggested
+                            } else {
+                                cosmic::theme::Button::Standard
+                            })
+                            .on_press(Message::ActivateTab(tab.id)),
+                    )
+                    .push(widget::tooltip(
+                        widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                            .on_press(Message::CloseTab(Some(tab.id))),
+                        widget::text(fl!("close-tab")),
+                        widget::tooltip::Position::Bottom,
+                    ))
+                    .align_y(Alignment::Center),
+            );
+        }
+        row = row
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-edit-symbolic"))
+                    .on_press(Message::RenameTab),
+                widget::text(fl!("rename-tab")),
+                widget::tooltip::Position::Bottom,
+            ))
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("tab-new-symbolic"))
+                    .on_press(Message::NewTab),
+                widget::text(fl!("new-tab")),
+                widget::tooltip::Position::Bottom,
+            ));
+
+        Some(
+            widget::scrollable::horizontal(row)
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+}
+
+/// Asks for the new title of the active tab.
+pub(crate) fn rename_dialog(title: &str) -> Element<'_, Message> {
+    widget::dialog()
+        .title(fl!("rename-tab"))
+        .control(
+            widget::text_input(fl!("rename-tab", "title"), title)
+                .id(RENAME_INPUT_ID.clone())
+                .on_input(Message::TabTitleChanged)
+                .on_submit(|_| Message::ConfirmRenameTab),
+        )
+        .primary_action(
+            widget::button::suggested(fl!("rename"))
+                .on_press_maybe((!title.trim().is_empty()).then_some(Message::ConfirmRenameTab)),
+        )
+        .secondary_action(
+            widget::button::standard(fl!("cancel")).on_press(Message::CancelRenameTab),
+        )
+        .into()
+}
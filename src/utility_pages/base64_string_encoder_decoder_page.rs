@@ -1,47 +1,101 @@
 use {
     crate::{
-        Message, app::AppModel, class::text_editor_class, fl, i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Base64StringEncoderDecoderConfig, Config, PageConfig},
+        context_drawer_pages::history_page::{self, Recording},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            byte_output::ByteOutput,
+            conversion::{self, Conversion, Debounce},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
-    base64::Engine,
     cosmic::{
         self, Application, Element, Task,
         iced::{
-            self, Alignment, Length, Padding, clipboard,
-            keyboard::{Key, key},
+            self, Alignment, Length, Padding, Subscription, clipboard,
             widget::{column, row},
         },
         widget::{
             self, Id,
-            text_editor::{self, Binding, TextEditor},
+            text_editor::{self, TextEditor},
         },
     },
-    std::sync::Arc,
+    dev_toolbox_core::{
+        base64::{decode, encode, is_round_trip},
+        bytes::ByteBuffer,
+        detect::ContentKind,
+        service::Tool,
+    },
+    std::{path::PathBuf, sync::Arc},
 };
 
 const INPUT_EDITOR_ID: &str = "input-editor";
 const OUTPUT_EDITOR_ID: &str = "output-editor";
 const OPERATIONS: [&str; 2] = ["encode", "decode"];
 
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::Base64StringEncoderDecoder,
+    id: "base64-string-encoder-decoder",
+    icon: ToolIcon::Named("object-flip-horizontal-symbolic"),
+    new_page: || Box::new(Base64StringEncoderDecoderPage::default()),
+    service: Some(Tool::Base64),
+    takes: |kind| kind == ContentKind::Base64,
+};
+
 #[derive(Debug, Clone)]
 pub enum Base64StringEncoderDecoderMessage {
     InputEditorAction(text_editor::Action),
     OutputEditorAction(text_editor::Action),
     OperationChanged(usize),
     UrlSafeToggled(bool),
+    RenderingChanged(usize),
+    LiveConvertToggled(bool),
+    InputSettled(u64),
     ConvertInput,
+    /// The output and whether it converts back to the input.
+    Converted(u64, Result<(ByteBuffer, bool), Status>),
+    CancelConversion,
+    Swap,
+    Clear,
     CopyText(Id),
     PasteText(Id),
     ReplaceText(Id, String),
+    OpenFile,
+    FileDropped(PathBuf),
+    FileOpened(Result<OpenedFile, String>),
+    SaveFile,
+    FileSaved(Result<(), String>),
     NoOp,
 }
 
+impl From<Base64StringEncoderDecoderMessage> for Message {
+    fn from(message: Base64StringEncoderDecoderMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct Base64StringEncoderDecoderPage {
     input_content: text_editor::Content,
-    output_content: text_editor::Content,
+    output: ByteOutput,
     selected_operation: usize,
     url_safe: bool,
+    live_convert: bool,
+    debounce: Debounce,
+    conversion: Conversion,
+    recording: Recording,
+    /// Whether the latest output converts back to the input.
+    round_trip: Option<bool>,
+    status: Status,
 }
 
 impl UtilityPage for Base64StringEncoderDecoderPage {
@@ -57,27 +111,38 @@ impl UtilityPage for Base64StringEncoderDecoderPage {
             widget::text::title4(fl!("input"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
-            widget::button::text(fl!("convert")).on_press(
-                Message::Base64StringEncoderDecoderMessage(
-                    Base64StringEncoderDecoderMessage::ConvertInput
-                )
-            ),
+            widget::checkbox(self.live_convert)
+                .label(fl!("live-convert"))
+                .on_toggle(|live_convert| {
+                    Message::from(Base64StringEncoderDecoderMessage::LiveConvertToggled(
+                        live_convert,
+                    ))
+                }),
+            widget::button::text(fl!("convert")).on_press(Message::from(
+                Base64StringEncoderDecoderMessage::ConvertInput
+            )),
             widget::dropdown(
                 OPERATIONS
                     .map(|operation| LANGUAGE_LOADER.get(operation))
                     .to_vec(),
                 Some(self.selected_operation),
                 |selection| {
-                    Message::Base64StringEncoderDecoderMessage(
-                        Base64StringEncoderDecoderMessage::OperationChanged(selection),
-                    )
+                    Message::from(Base64StringEncoderDecoderMessage::OperationChanged(
+                        selection,
+                    ))
                 },
             ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::from(Base64StringEncoderDecoderMessage::OpenFile)),
+                widget::text(fl!("open-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::Base64StringEncoderDecoderMessage(
-                        Base64StringEncoderDecoderMessage::PasteText(Id::new(INPUT_EDITOR_ID)),
-                    )
+                    Message::from(Base64StringEncoderDecoderMessage::PasteText(Id::new(
+                        INPUT_EDITOR_ID
+                    )),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -93,39 +158,53 @@ impl UtilityPage for Base64StringEncoderDecoderPage {
             )))
             .wrapping(cosmic::iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::Base64StringEncoderDecoderMessage(
-                    Base64StringEncoderDecoderMessage::InputEditorAction(action),
-                )
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
+                Message::from(Base64StringEncoderDecoderMessage::InputEditorAction(action))
             })
+            .key_binding(editor_key_binding)
             .into();
+        let input_editor = files::drop_target(
+            input_editor,
+            |path| Message::from(Base64StringEncoderDecoderMessage::FileDropped(path)),
+            Message::from(Base64StringEncoderDecoderMessage::NoOp),
+        );
 
         let output_header: Element<'_, Message> = row![
-            widget::text::title4(fl!("output"))
-                .width(Length::Fill)
-                .align_x(Alignment::Start),
+            widget::row::with_capacity(2)
+                .push(widget::text::title4(fl!("output")))
+                .push_maybe(conversion::round_trip_view(self.round_trip))
+                .spacing(space_s)
+                .align_y(Alignment::Center)
+                .width(Length::Fill),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("object-flip-vertical-symbolic"))
+                    .on_press(Message::from(Base64StringEncoderDecoderMessage::Swap)),
+                widget::text(fl!("swap")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::container(
                 widget::checkbox(self.url_safe,)
                     .label(fl!("base64-string-encoder-decoder", "url-safe"),)
                     .on_toggle(|url_safe| {
-                        Message::Base64StringEncoderDecoderMessage(
-                            Base64StringEncoderDecoderMessage::UrlSafeToggled(url_safe),
-                        )
+                        Message::from(Base64StringEncoderDecoderMessage::UrlSafeToggled(url_safe))
                     }),
             )
             .padding(Padding::new(0.0).right(8.0)),
+            self.output.rendering_dropdown(|selection| {
+                Message::from(Base64StringEncoderDecoderMessage::RenderingChanged(
+                    selection,
+                ))
+            }),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(Base64StringEncoderDecoderMessage::SaveFile,)),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::Base64StringEncoderDecoderMessage(
-                        Base64StringEncoderDecoderMessage::CopyText(Id::new(OUTPUT_EDITOR_ID)),
-                    ),
+                    Message::from(Base64StringEncoderDecoderMessage::CopyText(Id::new(
+                        OUTPUT_EDITOR_ID
+                    )),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
@@ -134,7 +213,7 @@ impl UtilityPage for Base64StringEncoderDecoderPage {
         .align_y(Alignment::Center)
         .into();
 
-        let output_editor: Element<'_, Message> = TextEditor::new(&self.output_content)
+        let output_editor: Element<'_, Message> = TextEditor::new(&self.output.content)
             .padding(Padding::new(12.0))
             .height(Length::Fill)
             .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
@@ -142,13 +221,13 @@ impl UtilityPage for Base64StringEncoderDecoderPage {
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::Base64StringEncoderDecoderMessage(
-                    Base64StringEncoderDecoderMessage::OutputEditorAction(action),
-                )
+                Message::from(Base64StringEncoderDecoderMessage::OutputEditorAction(
+                    action,
+                ))
             })
             .into();
 
-        column![
+        let mut page = column![
             header,
             input_header,
             input_editor,
@@ -156,121 +235,329 @@ impl UtilityPage for Base64StringEncoderDecoderPage {
             output_editor
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(progress) = self.conversion.view(Message::from(
+            Base64StringEncoderDecoderMessage::CancelConversion,
+        )) {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::Base64StringEncoderDecoderMessage(data_converter_formatter_message) => {
+        match message.tool_message::<Base64StringEncoderDecoderMessage>() {
+            Some(data_converter_formatter_message) => {
                 match data_converter_formatter_message {
                     Base64StringEncoderDecoderMessage::InputEditorAction(action) => {
+                        let is_edit = action.is_edit();
                         self.input_content.perform(action);
+                        if is_edit {
+                            self.recording.clear();
+                        }
+                        if is_edit && self.live_convert {
+                            return self.debounce.edit(|edit| {
+                                Message::from(Base64StringEncoderDecoderMessage::InputSettled(edit))
+                            });
+                        }
+                    }
+                    Base64StringEncoderDecoderMessage::LiveConvertToggled(live_convert) => {
+                        self.live_convert = live_convert;
+                        if live_convert {
+                            return self.convert_live();
+                        }
+                    }
+                    Base64StringEncoderDecoderMessage::InputSettled(edit) => {
+                        if self.live_convert && self.debounce.is_settled(edit) {
+                            return self.convert_live();
+                        }
                     }
                     Base64StringEncoderDecoderMessage::OutputEditorAction(action) => {
                         if !action.is_edit() {
-                            self.output_content.perform(action);
+                            self.output.content.perform(action);
                         }
                     }
                     Base64StringEncoderDecoderMessage::OperationChanged(selection) => {
                         if selection != self.selected_operation {
                             self.selected_operation = selection;
-                            self.convert_input();
+                            return self.convert_live();
                         }
                     }
                     Base64StringEncoderDecoderMessage::UrlSafeToggled(url_safe) => {
                         self.url_safe = url_safe;
-                        self.convert_input();
+                        return self.convert_live();
+                    }
+                    Base64StringEncoderDecoderMessage::RenderingChanged(selection) => {
+                        self.output.set_rendering(selection);
                     }
                     Base64StringEncoderDecoderMessage::ConvertInput => {
-                        self.convert_input();
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    Base64StringEncoderDecoderMessage::Converted(id, result) => {
+                        if self.conversion.finish(id) {
+                            match result {
+                                Ok((output, round_trip)) => {
+                                    self.round_trip = Some(round_trip);
+                                    self.output.set_buffer(output);
+                                    self.status = Status::Idle;
+                                    if self.recording.finish(true) {
+                                        return history_page::record(
+                                            Page::Base64StringEncoderDecoder,
+                                            self.history_entry(),
+                                        );
+                                    }
+                                }
+                                Err(status) => {
+                                    self.round_trip = None;
+                                    self.recording.finish(false);
+                                    self.status = status;
+                                }
+                            }
+                        }
+                    }
+                    Base64StringEncoderDecoderMessage::Swap => {
+                        // Binary output cannot be edited as text, so it stays where it is.
+                        let Some(output) = self.output.text() else {
+                            self.status = Status::Info(fl!("binary-output"));
+                            return Task::none();
+                        };
+                        self.input_content = text_editor::Content::with_text(&output);
+                        self.selected_operation = 1 - self.selected_operation;
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    Base64StringEncoderDecoderMessage::Clear => {
+                        self.conversion.cancel();
+                        self.recording.clear();
+                        self.input_content = text_editor::Content::default();
+                        self.round_trip = None;
+                        self.output.set_buffer(ByteBuffer::default());
+                        self.status = Status::Idle;
+                    }
+                    Base64StringEncoderDecoderMessage::CancelConversion => {
+                        self.conversion.cancel();
                     }
                     Base64StringEncoderDecoderMessage::CopyText(id) => {
                         let mut to_copy: String = String::new();
                         if id == Id::new(INPUT_EDITOR_ID) {
                             to_copy = self.input_content.text();
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            to_copy = self.output_content.text();
+                            to_copy = self.output.content.text();
                         }
-                        return clipboard::write(to_copy);
+                        // Copying the output keeps its conversion in the history.
+                        let recorded = if id == Id::new(OUTPUT_EDITOR_ID) {
+                            history_page::record(
+                                Page::Base64StringEncoderDecoder,
+                                self.history_entry(),
+                            )
+                        } else {
+                            Task::none()
+                        };
+                        return Task::batch([clipboard::write(to_copy), recorded]);
                     }
                     Base64StringEncoderDecoderMessage::PasteText(id) => {
                         return clipboard::read().map(move |optional_data| match optional_data {
-                            Some(data) => {
-                                cosmic::Action::App(Message::Base64StringEncoderDecoderMessage(
-                                    Base64StringEncoderDecoderMessage::ReplaceText(
-                                        id.clone(),
-                                        data,
-                                    ),
-                                ))
-                            }
-                            None => {
-                                cosmic::Action::App(Message::Base64StringEncoderDecoderMessage(
-                                    Base64StringEncoderDecoderMessage::NoOp,
-                                ))
-                            }
+                            Some(data) => cosmic::Action::App(Message::from(
+                                Base64StringEncoderDecoderMessage::ReplaceText(id.clone(), data),
+                            )),
+                            None => cosmic::Action::App(Message::from(
+                                Base64StringEncoderDecoderMessage::NoOp,
+                            )),
                         });
                     }
                     Base64StringEncoderDecoderMessage::ReplaceText(id, text) => {
                         if id == Id::new(INPUT_EDITOR_ID) {
+                            self.recording.clear();
                             self.input_content.perform(text_editor::Action::SelectAll);
                             self.input_content.perform(text_editor::Action::Edit(
                                 text_editor::Edit::Paste(Arc::new(text)),
                             ));
+                            if self.live_convert {
+                                return self.convert_live();
+                            }
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            self.output_content.perform(text_editor::Action::SelectAll);
-                            self.output_content.perform(text_editor::Action::Edit(
-                                text_editor::Edit::Paste(Arc::new(text)),
-                            ));
+                            self.output.set_buffer(text.into());
                         }
                     }
+                    Base64StringEncoderDecoderMessage::OpenFile => {
+                        return files::open(|result| {
+                            Message::from(Base64StringEncoderDecoderMessage::FileOpened(result))
+                        });
+                    }
+                    Base64StringEncoderDecoderMessage::FileDropped(path) => {
+                        return files::read(path, |result| {
+                            Message::from(Base64StringEncoderDecoderMessage::FileOpened(result))
+                        });
+                    }
+                    Base64StringEncoderDecoderMessage::FileOpened(result) => {
+                        match result.and_then(|file| file.text()) {
+                            Ok(text) => {
+                                self.input_content = text_editor::Content::with_text(&text);
+                                return self.convert_live();
+                            }
+                            Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                        }
+                    }
+                    Base64StringEncoderDecoderMessage::SaveFile => {
+                        return self.output.save_as(
+                            if self.selected_operation == 0 {
+                                "encoded.txt".to_string()
+                            } else {
+                                format!("decoded.{}", self.output.extension())
+                            },
+                            |result| {
+                                Message::from(Base64StringEncoderDecoderMessage::FileSaved(result))
+                            },
+                        );
+                    }
+                    Base64StringEncoderDecoderMessage::FileSaved(result) => {
+                        self.status = Status::saved(result);
+                    }
                     Base64StringEncoderDecoderMessage::NoOp => {}
                 }
             }
-            _ => {
-                println!("Wrong message type");
-            }
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.base64_string_encoder_decoder;
+        self.live_convert = config.live_convert;
+        self.selected_operation = config.operation.min(OPERATIONS.len() - 1);
+        self.url_safe = config.url_safe;
+        if !config.input.is_empty() {
+            self.input_content = text_editor::Content::with_text(&config.input);
+            return self.convert_live();
+        }
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::Base64StringEncoderDecoder(Base64StringEncoderDecoderConfig {
+            operation: self.selected_operation,
+            url_safe: self.url_safe,
+            live_convert: self.live_convert,
+            input: self.input_content.text(),
+        })
+    }
+
+    fn paste_detected(
+        &mut self,
+        content: String,
+        _kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        self.selected_operation = 1;
+        self.url_safe = content.contains(['-', '_']);
+        self.input_content = text_editor::Content::with_text(&content);
+        self.convert_live()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        Some(Message::from(match action {
+            EditAction::Convert => Base64StringEncoderDecoderMessage::ConvertInput,
+            EditAction::Swap => Base64StringEncoderDecoderMessage::Swap,
+            EditAction::CopyOutput => {
+                Base64StringEncoderDecoderMessage::CopyText(Id::new(OUTPUT_EDITOR_ID))
+            }
+            EditAction::PasteInput => {
+                Base64StringEncoderDecoderMessage::PasteText(Id::new(INPUT_EDITOR_ID))
+            }
+            EditAction::Clear => Base64StringEncoderDecoderMessage::Clear,
+        }))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        let url_safe_title = fl!("base64-string-encoder-decoder", "url-safe");
+        let mut commands = Vec::new();
+        for url_safe in [false, true] {
+            for (operation, id) in OPERATIONS.into_iter().enumerate() {
+                let mut title = LANGUAGE_LOADER.get(id);
+                if url_safe {
+                    title = format!("{title} {url_safe_title}");
+                }
+                commands.push(Command::new(
+                    title,
+                    [
+                        Base64StringEncoderDecoderMessage::OperationChanged(operation),
+                        Base64StringEncoderDecoderMessage::UrlSafeToggled(url_safe),
+                        Base64StringEncoderDecoderMessage::ConvertInput,
+                    ]
+                    .map(Message::from),
+                ));
+            }
+        }
+        commands
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        self.recording
+            .is_converted()
+            .then(|| (self.input_content.text(), self.output.content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.input_content = text_editor::Content::with_text(&input);
+        self.convert_input()
+    }
+
+    fn prefill(&mut self, operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        if let Some(position) = OPERATIONS.iter().position(|other| *other == operation) {
+            self.selected_operation = position;
+        }
+        self.restore_input(input)
+    }
 }
 
 impl Base64StringEncoderDecoderPage {
-    fn convert_input(&mut self) {
-        let engine = if self.url_safe {
-            base64::engine::general_purpose::URL_SAFE
-        } else {
-            base64::engine::general_purpose::STANDARD
-        };
+    /// Converts the input in the background, replacing a conversion that is still running.
+    fn convert_input(&mut self) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
         let input = self.input_content.text();
-        match self.selected_operation {
-            0 => {
-                self.output_content.perform(text_editor::Action::SelectAll);
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
-                        Arc::new(engine.encode(input.trim().as_bytes())),
-                    )));
-            }
-            _ => {
-                let decode_result = engine.decode(input.trim());
-                if let Ok(bytes) = decode_result {
-                    self.output_content.perform(text_editor::Action::SelectAll);
-                    self.output_content
-                        .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                    self.output_content.perform(text_editor::Action::Edit(
-                        text_editor::Edit::Paste(Arc::new(
-                            String::from_utf8_lossy(bytes.as_slice()).into_owned(),
-                        )),
-                    ));
-                } else if let Err(err) = decode_result {
-                    println!("Base64 Decoding Error: {}", err);
+        let operation = self.selected_operation;
+        let url_safe = self.url_safe;
+        self.conversion.start(
+            move || match operation {
+                0 => {
+                    let output = encode(&input, url_safe);
+                    // Encoding trims the input, so the trimmed input is what has to come back.
+                    let round_trip = is_round_trip(&output, input.trim().as_bytes(), url_safe);
+                    Ok((output.into(), round_trip))
                 }
-            }
+                _ => decode(&input, url_safe)
+                    .map(|output| {
+                        let round_trip = is_round_trip(&input, output.as_bytes(), url_safe);
+                        (output, round_trip)
+                    })
+                    .map_err(|err| Status::from(&err)),
+            },
+            |id, result| {
+                Message::from(Base64StringEncoderDecoderMessage::Converted(
+                    id,
+                    result.flatten(),
+                ))
+            },
+        )
+    }
+
+    /// Converts the input after a change, unless it is too large to convert on every change.
+    fn convert_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.input_content.text()) {
+            return self.convert_input();
         }
+        self.conversion.cancel();
+        self.recording.clear();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
     }
 }
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Output editor contents backed by raw bytes, shown in a selectable rendering.
+
+use {
+    crate::{Message, i18n::LANGUAGE_LOADER, utility_pages::files},
+    cosmic::{
+        self, Element, Task,
+        widget::{self, text_editor},
+    },
+    dev_toolbox_core::bytes::{ByteBuffer, Rendering},
+    std::sync::Arc,
+};
+
+const RENDERING_IDS: [&str; 3] = ["text", "hex-dump", "escaped"];
+
+/// The bytes of an output and the text editor content rendering them.
+#[derive(Default)]
+pub(crate) struct ByteOutput {
+    buffer: ByteBuffer,
+    rendering: Rendering,
+    pub(crate) content: text_editor::Content,
+}
+
+impl ByteOutput {
+    /// Replaces the output, switching to a hex dump when the bytes are not text.
+    pub(crate) fn set_buffer(&mut self, buffer: ByteBuffer) {
+        self.rendering = buffer.preferred_rendering();
+        self.buffer = buffer;
+        self.render();
+    }
+
+    pub(crate) fn set_rendering(&mut self, selection: usize) {
+        self.rendering = Rendering::ALL[selection];
+        self.render();
+    }
+
+    /// The bytes as text, `None` when they are binary.
+    pub(crate) fn text(&self) -> Option<String> {
+        self.buffer.as_text().ok().map(str::to_string)
+    }
+
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        self.buffer.as_bytes().to_vec()
+    }
+
+    fn render(&mut self) {
+        self.content.perform(text_editor::Action::SelectAll);
+        self.content
+            .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
+        self.content
+            .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                Arc::new(self.buffer.render(self.rendering)),
+            )));
+    }
+
+    pub(crate) fn rendering_dropdown(
+        &self,
+        on_select: impl Fn(usize) -> Message + Send + Sync + 'static,
+    ) -> Element<'_, Message> {
+        widget::dropdown(
+            RENDERING_IDS
+                .map(|id| LANGUAGE_LOADER.get_attr("rendering", id))
+                .to_vec(),
+            Rendering::ALL
+                .iter()
+                .position(|rendering| *rendering == self.rendering),
+            on_select,
+        )
+        .into()
+    }
+
+    /// Asks for a destination and writes the raw bytes to it.
+    pub(crate) fn save_as(
+        &self,
+        file_name: String,
+        on_saved: impl FnOnce(Result<(), String>) -> Message + Send + 'static,
+    ) -> Task<cosmic::Action<Message>> {
+        files::save(file_name, self.bytes(), on_saved)
+    }
+
+    /// The extension to save the bytes with when the tool does not suggest one.
+    pub(crate) fn extension(&self) -> &'static str {
+        match self.buffer.preferred_rendering() {
+            Rendering::Text => "txt",
+            _ => "bin",
+        }
+    }
+}
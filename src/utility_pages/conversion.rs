@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Conversions run on a blocking thread, so that large inputs do not freeze the window.
+
+use {
+    crate::{Message, fl, utility_pages::status::Status},
+    cosmic::{
+        self, Element, Task,
+        iced::{Alignment, Length, Subscription, task, time},
+        widget,
+    },
+    std::time::{Duration, Instant},
+};
+
+/// Inputs longer than this many bytes are only converted when asked to, not on every change.
+const LIVE_CONVERSION_LIMIT: usize = 1024 * 1024;
+
+/// How long the input has to stay unchanged before it is converted as you type.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
+
+/// How long the progress bar of a running conversion takes to fill before it starts over.
+const PROGRESS_PERIOD: Duration = Duration::from_millis(1500);
+
+/// How often the progress bar of a running conversion moves.
+const PROGRESS_FRAME: Duration = Duration::from_millis(50);
+
+/// Whether an input is small enough to be converted on every change.
+pub(crate) fn is_live(input: &str) -> bool {
+    input.len() <= LIVE_CONVERSION_LIMIT
+}
+
+/// Whether the output converts back to the input, shown next to the output title.
+pub(crate) fn round_trip_view<'a>(round_trip: Option<bool>) -> Option<Element<'a, Message>> {
+    let (icon, description) = if round_trip? {
+        ("emblem-ok-symbolic", fl!("round-trip", "ok"))
+    } else {
+        ("dialog-warning-symbolic", fl!("round-trip", "changed"))
+    };
+    Some(
+        widget::tooltip(
+            widget::row::with_capacity(2)
+                .push(widget::icon::from_name(icon).size(16).icon())
+                .push(widget::text::caption(fl!("round-trip")))
+                .spacing(cosmic::theme::spacing().space_xxxs)
+                .align_y(Alignment::Center),
+            widget::text(description),
+            widget::tooltip::Position::Bottom,
+        )
+        .into(),
+    )
+}
+
+/// Delays converting as you type until the input stops changing.
+#[derive(Default)]
+pub(crate) struct Debounce {
+    /// Identifies the latest edit of the input.
+    edit: u64,
+}
+
+impl Debounce {
+    /// Records an edit, emitting `on_settled` with its identifier after the delay.
+    pub(crate) fn edit(
+        &mut self,
+        on_settled: impl FnOnce(u64) -> Message + Send + 'static,
+    ) -> Task<cosmic::Action<Message>> {
+        self.edit += 1;
+        let edit = self.edit;
+        Task::perform(tokio::time::sleep(DEBOUNCE_DELAY), move |()| {
+            cosmic::Action::App(on_settled(edit))
+        })
+    }
+
+    /// Whether the input was left unchanged since the given edit.
+    pub(crate) fn is_settled(&self, edit: u64) -> bool {
+        edit == self.edit
+    }
+}
+
+/// The background conversion of a page.
+#[derive(Default)]
+pub(crate) struct Conversion {
+    /// Identifies the latest conversion, so that results of the ones it replaced are dropped.
+    id: u64,
+    /// The handle of the running conversion and when it started.
+    running: Option<(task::Handle, Instant)>,
+}
+
+impl Conversion {
+    /// Runs `convert` on a blocking thread, cancelling the conversion still running.
+    ///
+    /// The page passes the identifier given to `on_done` back to [`Conversion::finish`]. A
+    /// conversion that panics is handed to `on_done` as an error.
+    pub(crate) fn start<T: Send + 'static>(
+        &mut self,
+        convert: impl FnOnce() -> T + Send + 'static,
+        on_done: impl FnOnce(u64, Result<T, Status>) -> Message + Send + 'static,
+    ) -> Task<cosmic::Action<Message>> {
+        self.cancel();
+        self.id += 1;
+        let id = self.id;
+        let (task, handle) = Task::perform(
+            async move { tokio::task::spawn_blocking(convert).await },
+            move |result| {
+                let result = result.map_err(|err| Status::error(fl!("conversion-failed"), err));
+                cosmic::Action::App(on_done(id, result))
+            },
+        )
+        .abortable();
+        self.running = Some((handle, Instant::now()));
+        task
+    }
+
+    /// Marks the conversion as done, returning whether its result is the latest one.
+    pub(crate) fn finish(&mut self, id: u64) -> bool {
+        if id != self.id || self.running.is_none() {
+            return false;
+        }
+        self.running = None;
+        true
+    }
+
+    /// Stops waiting for the running conversion, its result is dropped.
+    ///
+    /// A blocking thread cannot be interrupted, so the conversion itself runs to its end in the
+    /// background.
+    pub(crate) fn cancel(&mut self) {
+        if let Some((handle, _)) = self.running.take() {
+            handle.abort();
+        }
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Redraws the page while a conversion is running, so that its progress bar moves.
+    pub(crate) fn subscription(&self) -> Subscription<Message> {
+        if self.is_running() {
+            time::every(PROGRESS_FRAME).map(|_| Message::Redraw)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// A progress row with a cancel button, shown while a conversion is running.
+    pub(crate) fn view(&self, on_cancel: Message) -> Option<Element<'_, Message>> {
+        let (_, started) = self.running.as_ref()?;
+        // The duration of a conversion is unknown, so the bar fills over and over until it ends.
+        let progress = started.elapsed().as_secs_f32() % PROGRESS_PERIOD.as_secs_f32()
+            / PROGRESS_PERIOD.as_secs_f32();
+        let space_s = cosmic::theme::spacing().space_s;
+
+        Some(
+            widget::row::with_capacity(3)
+                .push(widget::text::body(fl!("converting")))
+                .push(widget::progress_bar(0.0..=1.0, progress).height(Length::Fixed(4.0)))
+                .push(widget::button::standard(fl!("cancel")).on_press(on_cancel))
+                .spacing(space_s)
+                .align_y(Alignment::Center)
+                .into(),
+        )
+    }
+}
@@ -1,28 +1,52 @@
 use {
-    crate::{Message, app::AppModel, class::text_editor_class, fl, utility_pages::UtilityPage},
+    crate::{
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, DataConverterFormatterConfig, PageConfig},
+        context_drawer_pages::history_page::{self, Recording},
+        fl,
+        utility_pages::{
+            EditAction, UtilityPage,
+            conversion::{self, Conversion, Debounce},
+            diff_view, editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
+    },
     cosmic::{
         self, Application, Element, Task, iced,
         iced::{
-            Alignment, Length, Padding, clipboard,
-            keyboard::{Key, key},
+            Alignment, Length, Padding, Subscription, clipboard,
             widget::{column, row},
         },
         widget::{
             self, Id,
-            text_editor::{self, Binding, TextEditor},
+            text_editor::{self, TextEditor},
         },
     },
-    quick_xml,
-    serde::Serialize,
-    serde_json, serde_saphyr,
-    std::sync::Arc,
-    toml,
+    dev_toolbox_core::{
+        data_format::{DataFormat, INDENTS, convert, is_round_trip},
+        detect::ContentKind,
+        diff::{self, DiffLine, DiffOptions},
+        service::Tool,
+    },
+    std::{path::PathBuf, sync::Arc},
 };
 
 const INPUT_EDITOR_ID: &str = "input-editor";
 const OUTPUT_EDITOR_ID: &str = "output-editor";
-const DATA_FORMATS: [&str; 4] = ["JSON", "YAML", "XML", "TOML"];
-const INDENTS: [&str; 3] = ["2", "4", "8"];
+
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::DataConverterFormatter,
+    id: "data-converter-formatter",
+    icon: ToolIcon::Named("x-office-document-symbolic"),
+    new_page: || Box::new(DataConverterFormatterPage::default()),
+    service: Some(Tool::Convert),
+    takes: |kind| matches!(kind, ContentKind::Document(_)),
+};
 
 #[derive(Debug, Clone)]
 pub enum DataConverterFormatterMessage {
@@ -31,13 +55,33 @@ pub enum DataConverterFormatterMessage {
     InputFormatChanged(usize),
     OutputFormatChanged(usize),
     IndentChanged(usize),
+    LiveConvertToggled(bool),
+    ShowDiffToggled(bool),
+    InputSettled(u64),
     ConvertInput,
+    /// The output, whether it converts back to the input, and how it differs from the input when
+    /// the diff is shown.
+    Converted(u64, Result<(String, bool, Option<Vec<DiffLine>>), Status>),
+    CancelConversion,
+    Swap,
+    Clear,
     CopyText(Id),
     PasteText(Id),
     ReplaceText(Id, String),
+    OpenFile,
+    FileDropped(PathBuf),
+    FileOpened(Result<OpenedFile, String>),
+    SaveFile,
+    FileSaved(Result<(), String>),
     NoOp,
 }
 
+impl From<DataConverterFormatterMessage> for Message {
+    fn from(message: DataConverterFormatterMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct DataConverterFormatterPage {
     input_content: text_editor::Content,
@@ -45,6 +89,16 @@ pub(crate) struct DataConverterFormatterPage {
     input_format: usize,
     output_format: usize,
     selected_indent: usize,
+    live_convert: bool,
+    /// Whether the changes from the input to the output are shown in place of the output.
+    show_diff: bool,
+    diff: Vec<DiffLine>,
+    debounce: Debounce,
+    conversion: Conversion,
+    recording: Recording,
+    /// Whether the latest output converts back to the input.
+    round_trip: Option<bool>,
+    status: Status,
 }
 
 impl UtilityPage for DataConverterFormatterPage {
@@ -60,23 +114,33 @@ impl UtilityPage for DataConverterFormatterPage {
             widget::text::title4(fl!("input"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
-            widget::button::text(fl!("convert")).on_press(Message::DataConverterFormatterMessage(
-                DataConverterFormatterMessage::ConvertInput
-            )),
+            widget::checkbox(self.live_convert)
+                .label(fl!("live-convert"))
+                .on_toggle(|live_convert| {
+                    Message::from(DataConverterFormatterMessage::LiveConvertToggled(
+                        live_convert,
+                    ))
+                }),
+            widget::button::text(fl!("convert"))
+                .on_press(Message::from(DataConverterFormatterMessage::ConvertInput)),
             widget::dropdown(
-                DATA_FORMATS.to_vec(),
+                DataFormat::ALL.map(DataFormat::name).to_vec(),
                 Some(self.input_format),
                 |selection| {
-                    Message::DataConverterFormatterMessage(
-                        DataConverterFormatterMessage::InputFormatChanged(selection),
-                    )
+                    Message::from(DataConverterFormatterMessage::InputFormatChanged(selection))
                 },
             ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::from(DataConverterFormatterMessage::OpenFile)),
+                widget::text(fl!("open-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::DataConverterFormatterMessage(
-                        DataConverterFormatterMessage::PasteText(Id::new(INPUT_EDITOR_ID)),
-                    )
+                    Message::from(DataConverterFormatterMessage::PasteText(Id::new(
+                        INPUT_EDITOR_ID
+                    )),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -92,27 +156,39 @@ impl UtilityPage for DataConverterFormatterPage {
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::DataConverterFormatterMessage(
-                    DataConverterFormatterMessage::InputEditorAction(action),
-                )
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
+                Message::from(DataConverterFormatterMessage::InputEditorAction(action))
             })
+            .key_binding(editor_key_binding)
             .into();
+        let input_editor = files::drop_target(
+            input_editor,
+            |path| Message::from(DataConverterFormatterMessage::FileDropped(path)),
+            Message::from(DataConverterFormatterMessage::NoOp),
+        );
 
         let output_header: Element<'_, Message> = widget::row([])
             .push(
-                widget::text::title4(fl!("output"))
-                    .width(Length::Fill)
-                    .align_x(Alignment::Start),
+                widget::row::with_capacity(2)
+                    .push(widget::text::title4(fl!("output")))
+                    .push_maybe(conversion::round_trip_view(self.round_trip))
+                    .spacing(space_s)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill),
             )
-            .push_maybe(if self.output_format != 3 {
+            .push(
+                widget::checkbox(self.show_diff)
+                    .label(fl!("data-converter-formatter", "show-diff"))
+                    .on_toggle(|show_diff| {
+                        Message::from(DataConverterFormatterMessage::ShowDiffToggled(show_diff))
+                    }),
+            )
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("object-flip-vertical-symbolic"))
+                    .on_press(Message::from(DataConverterFormatterMessage::Swap)),
+                widget::text(fl!("swap")),
+                widget::tooltip::Position::Bottom,
+            ))
+            .push_maybe(if DataFormat::ALL[self.output_format].supports_indent() {
                 Some(widget::dropdown(
                     INDENTS
                         .map(|indent| {
@@ -121,49 +197,55 @@ impl UtilityPage for DataConverterFormatterPage {
                         .to_vec(),
                     Some(self.selected_indent),
                     |selection| {
-                        Message::DataConverterFormatterMessage(
-                            DataConverterFormatterMessage::IndentChanged(selection),
-                        )
+                        Message::from(DataConverterFormatterMessage::IndentChanged(selection))
                     },
                 ))
             } else {
                 None
             })
             .push(widget::dropdown(
-                DATA_FORMATS.to_vec(),
+                DataFormat::ALL.map(DataFormat::name).to_vec(),
                 Some(self.output_format),
                 |selection| {
-                    Message::DataConverterFormatterMessage(
-                        DataConverterFormatterMessage::OutputFormatChanged(selection),
-                    )
+                    Message::from(DataConverterFormatterMessage::OutputFormatChanged(
+                        selection,
+                    ))
                 },
             ))
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(DataConverterFormatterMessage::SaveFile)),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ))
             .push(widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::DataConverterFormatterMessage(
-                        DataConverterFormatterMessage::CopyText(Id::new(OUTPUT_EDITOR_ID)),
-                    ),
+                    Message::from(DataConverterFormatterMessage::CopyText(Id::new(
+                        OUTPUT_EDITOR_ID,
+                    ))),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
             ))
             .into();
 
-        let output_editor: Element<'_, Message> = TextEditor::new(&self.output_content)
-            .padding(Padding::new(12.0))
-            .height(Length::Fill)
-            .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
-                text_editor_class,
-            )))
-            .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::DataConverterFormatterMessage(
-                    DataConverterFormatterMessage::OutputEditorAction(action),
-                )
-            })
-            .into();
+        let output_editor: Element<'_, Message> = if self.show_diff {
+            diff_view::lines_view(&self.diff)
+        } else {
+            TextEditor::new(&self.output_content)
+                .padding(Padding::new(12.0))
+                .height(Length::Fill)
+                .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+                    text_editor_class,
+                )))
+                .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+                .on_action(|action| {
+                    Message::from(DataConverterFormatterMessage::OutputEditorAction(action))
+                })
+                .into()
+        };
 
-        column![
+        let mut page = column![
             header,
             input_header,
             input_editor,
@@ -171,19 +253,53 @@ impl UtilityPage for DataConverterFormatterPage {
             output_editor
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(progress) = self.conversion.view(Message::from(
+            DataConverterFormatterMessage::CancelConversion,
+        )) {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::DataConverterFormatterMessage(data_converter_formatter_message) => {
+        match message.tool_message::<DataConverterFormatterMessage>() {
+            Some(data_converter_formatter_message) => {
                 match data_converter_formatter_message {
                     DataConverterFormatterMessage::InputEditorAction(action) => {
+                        let is_edit = action.is_edit();
                         self.input_content.perform(action);
+                        if is_edit {
+                            self.recording.clear();
+                        }
+                        if is_edit && self.live_convert {
+                            return self.debounce.edit(|edit| {
+                                Message::from(DataConverterFormatterMessage::InputSettled(edit))
+                            });
+                        }
+                    }
+                    DataConverterFormatterMessage::LiveConvertToggled(live_convert) => {
+                        self.live_convert = live_convert;
+                        if live_convert {
+                            return self.convert_live();
+                        }
+                    }
+                    DataConverterFormatterMessage::ShowDiffToggled(show_diff) => {
+                        self.show_diff = show_diff;
+                        if show_diff {
+                            return self.convert_live();
+                        }
+                    }
+                    DataConverterFormatterMessage::InputSettled(edit) => {
+                        if self.live_convert && self.debounce.is_settled(edit) {
+                            return self.convert_live();
+                        }
                     }
                     DataConverterFormatterMessage::OutputEditorAction(action) => {
                         if !action.is_edit() {
@@ -191,18 +307,68 @@ impl UtilityPage for DataConverterFormatterPage {
                         }
                     }
                     DataConverterFormatterMessage::InputFormatChanged(selection) => {
-                        self.input_format = selection
+                        self.input_format = selection;
+                        if self.live_convert {
+                            return self.convert_live();
+                        }
                     }
                     DataConverterFormatterMessage::OutputFormatChanged(selection) => {
                         self.output_format = selection;
-                        self.convert_input();
+                        return self.convert_live();
                     }
                     DataConverterFormatterMessage::IndentChanged(selection) => {
                         self.selected_indent = selection;
-                        self.convert_input();
+                        return self.convert_live();
                     }
                     DataConverterFormatterMessage::ConvertInput => {
-                        self.convert_input();
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    DataConverterFormatterMessage::Converted(id, result) => {
+                        if self.conversion.finish(id) {
+                            match result {
+                                Ok((output, round_trip, diff)) => {
+                                    self.round_trip = Some(round_trip);
+                                    self.diff = diff.unwrap_or_default();
+                                    self.output_content.perform(text_editor::Action::SelectAll);
+                                    self.output_content.perform(text_editor::Action::Edit(
+                                        text_editor::Edit::Paste(Arc::new(output)),
+                                    ));
+                                    self.status = Status::Idle;
+                                    if self.recording.finish(true) {
+                                        return history_page::record(
+                                            Page::DataConverterFormatter,
+                                            self.history_entry(),
+                                        );
+                                    }
+                                }
+                                Err(status) => {
+                                    self.round_trip = None;
+                                    self.recording.finish(false);
+                                    self.diff.clear();
+                                    self.status = status;
+                                }
+                            }
+                        }
+                    }
+                    DataConverterFormatterMessage::Swap => {
+                        self.input_content =
+                            text_editor::Content::with_text(&self.output_content.text());
+                        std::mem::swap(&mut self.input_format, &mut self.output_format);
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    DataConverterFormatterMessage::Clear => {
+                        self.conversion.cancel();
+                        self.recording.clear();
+                        self.input_content = text_editor::Content::default();
+                        self.round_trip = None;
+                        self.diff.clear();
+                        self.output_content = text_editor::Content::default();
+                        self.status = Status::Idle;
+                    }
+                    DataConverterFormatterMessage::CancelConversion => {
+                        self.conversion.cancel();
                     }
                     DataConverterFormatterMessage::CopyText(id) => {
                         let mut to_copy: String = String::new();
@@ -211,26 +377,34 @@ impl UtilityPage for DataConverterFormatterPage {
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
                             to_copy = self.output_content.text();
                         }
-                        return clipboard::write(to_copy);
+                        // Copying the output keeps its conversion in the history.
+                        let recorded = if id == Id::new(OUTPUT_EDITOR_ID) {
+                            history_page::record(Page::DataConverterFormatter, self.history_entry())
+                        } else {
+                            Task::none()
+                        };
+                        return Task::batch([clipboard::write(to_copy), recorded]);
                     }
                     DataConverterFormatterMessage::PasteText(id) => {
                         return clipboard::read().map(move |optional_data| match optional_data {
-                            Some(data) => {
-                                cosmic::Action::App(Message::DataConverterFormatterMessage(
-                                    DataConverterFormatterMessage::ReplaceText(id.clone(), data),
-                                ))
-                            }
-                            None => cosmic::Action::App(Message::DataConverterFormatterMessage(
+                            Some(data) => cosmic::Action::App(Message::from(
+                                DataConverterFormatterMessage::ReplaceText(id.clone(), data),
+                            )),
+                            None => cosmic::Action::App(Message::from(
                                 DataConverterFormatterMessage::NoOp,
                             )),
                         });
                     }
                     DataConverterFormatterMessage::ReplaceText(id, text) => {
                         if id == Id::new(INPUT_EDITOR_ID) {
+                            self.recording.clear();
                             self.input_content.perform(text_editor::Action::SelectAll);
                             self.input_content.perform(text_editor::Action::Edit(
                                 text_editor::Edit::Paste(Arc::new(text)),
                             ));
+                            if self.live_convert {
+                                return self.convert_live();
+                            }
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
                             self.output_content.perform(text_editor::Action::SelectAll);
                             self.output_content.perform(text_editor::Action::Edit(
@@ -238,68 +412,198 @@ impl UtilityPage for DataConverterFormatterPage {
                             ));
                         }
                     }
+                    DataConverterFormatterMessage::OpenFile => {
+                        return files::open(|result| {
+                            Message::from(DataConverterFormatterMessage::FileOpened(result))
+                        });
+                    }
+                    DataConverterFormatterMessage::FileDropped(path) => {
+                        return files::read(path, |result| {
+                            Message::from(DataConverterFormatterMessage::FileOpened(result))
+                        });
+                    }
+                    DataConverterFormatterMessage::FileOpened(result) => {
+                        match result
+                            .and_then(|file| Ok((DataFormat::from_path(&file.path), file.text()?)))
+                        {
+                            Ok((format, text)) => {
+                                // Files with a known extension select their input format.
+                                if let Some(position) = format.and_then(|format| {
+                                    DataFormat::ALL.iter().position(|other| *other == format)
+                                }) {
+                                    self.input_format = position;
+                                }
+                                self.input_content = text_editor::Content::with_text(&text);
+                                return self.convert_live();
+                            }
+                            Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                        }
+                    }
+                    DataConverterFormatterMessage::SaveFile => {
+                        return files::save(
+                            format!("output.{}", DataFormat::ALL[self.output_format].extension()),
+                            self.output_content.text().into_bytes(),
+                            |result| {
+                                Message::from(DataConverterFormatterMessage::FileSaved(result))
+                            },
+                        );
+                    }
+                    DataConverterFormatterMessage::FileSaved(result) => {
+                        self.status = Status::saved(result);
+                    }
                     DataConverterFormatterMessage::NoOp => {}
                 }
             }
-            _ => {
-                println!("Wrong message type");
-            }
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.data_converter_formatter;
+        self.live_convert = config.live_convert;
+        self.show_diff = config.show_diff;
+        self.input_format = config.input_format.min(DataFormat::ALL.len() - 1);
+        self.output_format = config.output_format.min(DataFormat::ALL.len() - 1);
+        self.selected_indent = config.indent.min(INDENTS.len() - 1);
+        if !config.input.is_empty() {
+            self.input_content = text_editor::Content::with_text(&config.input);
+            return self.convert_live();
+        }
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::DataConverterFormatter(DataConverterFormatterConfig {
+            input_format: self.input_format,
+            output_format: self.output_format,
+            indent: self.selected_indent,
+            live_convert: self.live_convert,
+            show_diff: self.show_diff,
+            input: self.input_content.text(),
+        })
+    }
+
+    fn paste_detected(
+        &mut self,
+        content: String,
+        kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        if let ContentKind::Document(format) = kind
+            && let Some(position) = DataFormat::ALL.iter().position(|other| *other == format)
+        {
+            self.input_format = position;
+        }
+        self.input_content = text_editor::Content::with_text(&content);
+        self.convert_live()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        Some(Message::from(match action {
+            EditAction::Convert => DataConverterFormatterMessage::ConvertInput,
+            EditAction::Swap => DataConverterFormatterMessage::Swap,
+            EditAction::CopyOutput => {
+                DataConverterFormatterMessage::CopyText(Id::new(OUTPUT_EDITOR_ID))
+            }
+            EditAction::PasteInput => {
+                DataConverterFormatterMessage::PasteText(Id::new(INPUT_EDITOR_ID))
+            }
+            EditAction::Clear => DataConverterFormatterMessage::Clear,
+        }))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        let mut commands = Vec::new();
+        for (input, input_format) in DataFormat::ALL.into_iter().enumerate() {
+            for (output, output_format) in DataFormat::ALL.into_iter().enumerate() {
+                let title = if input == output {
+                    fl!("command-palette", "format", format = input_format.name())
+                } else {
+                    fl!(
+                        "command-palette",
+                        "convert",
+                        from = input_format.name(),
+                        to = output_format.name()
+                    )
+                };
+                commands.push(Command::new(
+                    title,
+                    [
+                        DataConverterFormatterMessage::InputFormatChanged(input),
+                        DataConverterFormatterMessage::OutputFormatChanged(output),
+                        DataConverterFormatterMessage::ConvertInput,
+                    ]
+                    .map(Message::from),
+                ));
+            }
+        }
+        commands
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        self.recording
+            .is_converted()
+            .then(|| (self.input_content.text(), self.output_content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.input_content = text_editor::Content::with_text(&input);
+        self.convert_input()
+    }
+
+    /// The operations are the formats to convert to, named by their file extension.
+    fn prefill(&mut self, operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        if let Some(position) = DataFormat::ALL
+            .iter()
+            .position(|format| format.extension() == operation)
+        {
+            self.output_format = position;
+        }
+        self.restore_input(input)
+    }
 }
 
 impl DataConverterFormatterPage {
-    fn convert_input(&mut self) {
+    /// Converts the input in the background, replacing a conversion that is still running.
+    fn convert_input(&mut self) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
         let input = self.input_content.text();
-        let input_value: Option<serde_json::Value> = match self.input_format {
-            0 => serde_json::from_str(input.as_str()).ok(),
-            1 => serde_saphyr::from_str(input.as_str()).ok(),
-            2 => quick_xml::de::from_str(input.as_str()).ok(),
-            _ => toml::from_str(input.as_str()).ok(),
-        };
-        if let Some(value) = input_value {
-            self.output_content.perform(text_editor::Action::SelectAll);
-            let mut output_string: String;
-            let indent_count = INDENTS[self.selected_indent].parse::<usize>().unwrap();
-            match self.output_format {
-                0 => {
-                    let mut buf = vec![];
-                    let indent = " ".repeat(indent_count);
-                    let formatter =
-                        serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
-                    let mut json_serializer =
-                        serde_json::Serializer::with_formatter(&mut buf, formatter);
-                    value.serialize(&mut json_serializer).unwrap();
-                    output_string = String::from_utf8(buf).unwrap();
-                }
-                1 => {
-                    output_string = String::new();
-                    let mut yaml_serializer = serde_saphyr::ser::YamlSerializer::with_indent(
-                        &mut output_string,
-                        indent_count,
-                    );
-                    value.serialize(&mut yaml_serializer).unwrap();
-                }
-                2 => {
-                    output_string = String::new();
-                    let mut xml_serializer =
-                        quick_xml::se::Serializer::with_root(&mut output_string, Some("root"))
-                            .unwrap();
-                    xml_serializer.indent(' ', indent_count);
-                    xml_serializer.empty_element_handling(
-                        quick_xml::se::EmptyElementHandling::SelfClosedWithSpace,
-                    );
-                    if let Some(err) = value.serialize(xml_serializer).err() {
-                        println!("Error while converting XML: {}", err);
-                    }
-                }
-                _ => output_string = toml::to_string_pretty(&value).unwrap(),
-            }
-            self.output_content
-                .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
-                    Arc::new(output_string),
-                )));
+        let from = DataFormat::ALL[self.input_format];
+        let to = DataFormat::ALL[self.output_format];
+        let indent = INDENTS[self.selected_indent];
+        let show_diff = self.show_diff;
+        self.conversion.start(
+            move || {
+                convert(&input, from, to, indent)
+                    .map(|output| {
+                        let round_trip = is_round_trip(&input, from, &output, to);
+                        let diff = show_diff
+                            .then(|| diff::diff_lines(&input, &output, DiffOptions::default()));
+                        (output, round_trip, diff)
+                    })
+                    .map_err(|err| Status::from(&err))
+            },
+            |id, result| {
+                Message::from(DataConverterFormatterMessage::Converted(
+                    id,
+                    result.flatten(),
+                ))
+            },
+        )
+    }
+
+    /// Converts the input after a change, unless it is too large to convert on every change.
+    fn convert_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.input_content.text()) {
+            return self.convert_input();
         }
+        self.conversion.cancel();
+        self.recording.clear();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
     }
 }
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Line diffs and structural changes, as shown by the Text Diff page and the formatter.
+
+use {
+    crate::{Message, class::diff_line_style, fl},
+    cosmic::{
+        self, Element,
+        iced::{Alignment, Length},
+        widget,
+    },
+    dev_toolbox_core::diff::{DiffLine, LineKind, ValueChange},
+    serde_json::Value,
+};
+
+/// Unchanged lines shown around each change, longer runs of unchanged lines are collapsed.
+const CONTEXT_LINES: usize = 3;
+
+/// Width of the line number columns.
+const NUMBER_WIDTH: f32 = 48.0;
+
+/// The number of removed and added lines, or that the texts do not differ.
+pub(crate) fn lines_summary(lines: &[DiffLine]) -> String {
+    let count = |kind| lines.iter().filter(|line| line.kind == kind).count();
+    let (removed, added) = (count(LineKind::Removed), count(LineKind::Added));
+    if removed == 0 && added == 0 {
+        fl!("text-diff", "no-differences")
+    } else {
+        fl!(
+            "text-diff",
+            "lines-changed",
+            removed = removed,
+            added = added
+        )
+    }
+}
+
+/// The number of changed values, or that the documents are equivalent.
+pub(crate) fn changes_summary(changes: &[ValueChange]) -> String {
+    if changes.is_empty() {
+        fl!("text-diff", "equivalent")
+    } else {
+        fl!("text-diff", "values-changed", count = changes.len())
+    }
+}
+
+/// The lines with their numbers on both sides, collapsing unchanged lines far from a change.
+pub(crate) fn lines_view(lines: &[DiffLine]) -> Element<'_, Message> {
+    let is_shown = |index: usize| {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(lines.len());
+        lines[start..end]
+            .iter()
+            .any(|line| line.kind != LineKind::Unchanged)
+    };
+
+    let mut column = widget::column::with_capacity(lines.len()).width(Length::Fill);
+    let mut collapsed = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if !is_shown(index) {
+            collapsed += 1;
+            continue;
+        }
+        if collapsed > 0 {
+            column = column.push(collapsed_view(collapsed));
+            collapsed = 0;
+        }
+        column = column.push(line_view(line));
+    }
+    if collapsed > 0 {
+        column = column.push(collapsed_view(collapsed));
+    }
+
+    widget::scrollable(column).height(Length::Fill).into()
+}
+
+/// The changed values by path, removed values highlighted like removed lines and added values
+/// like added lines.
+pub(crate) fn changes_view(changes: &[ValueChange]) -> Element<'_, Message> {
+    let space_xxs = cosmic::theme::spacing().space_xxs;
+
+    let mut column = widget::column::with_capacity(changes.len())
+        .spacing(space_xxs)
+        .width(Length::Fill);
+    for change in changes {
+        let mut row = widget::row::with_capacity(4)
+            .push(widget::text::monotext(change.path()))
+            .spacing(space_xxs)
+            .align_y(Alignment::Center);
+        row = match change {
+            ValueChange::Added { value, .. } => row.push(value_view(value, LineKind::Added)),
+            ValueChange::Removed { value, .. } => row.push(value_view(value, LineKind::Removed)),
+            ValueChange::Changed { old, new, .. } => row
+                .push(value_view(old, LineKind::Removed))
+                .push(widget::text::monotext("→"))
+                .push(value_view(new, LineKind::Added)),
+        };
+        column = column.push(row);
+    }
+
+    widget::scrollable(column).height(Length::Fill).into()
+}
+
+fn line_view(line: &DiffLine) -> Element<'_, Message> {
+    let space_xxs = cosmic::theme::spacing().space_xxs;
+    let number = |number: Option<usize>| {
+        widget::text::monotext(number.map(|number| number.to_string()).unwrap_or_default())
+            .width(Length::Fixed(NUMBER_WIDTH))
+    };
+
+    let mut text = widget::row::with_capacity(line.segments.len());
+    for segment in &line.segments {
+        let segment_text = widget::text::monotext(&segment.text);
+        text = if segment.changed {
+            let kind = line.kind;
+            text.push(
+                widget::container(segment_text).class(cosmic::theme::Container::Custom(Box::new(
+                    move |theme| diff_line_style(theme, kind, true),
+                ))),
+            )
+        } else {
+            text.push(segment_text)
+        };
+    }
+
+    let kind = line.kind;
+    widget::container(
+        widget::row::with_capacity(4)
+            .push(number(line.old_number))
+            .push(number(line.new_number))
+            .push(widget::text::monotext(line.kind.marker().to_string()))
+            .push(text)
+            .spacing(space_xxs),
+    )
+    .width(Length::Fill)
+    .class(cosmic::theme::Container::Custom(Box::new(move |theme| {
+        diff_line_style(theme, kind, false)
+    })))
+    .into()
+}
+
+fn collapsed_view<'a>(count: usize) -> Element<'a, Message> {
+    widget::text::caption(fl!("text-diff", "unchanged-lines", count = count))
+        .width(Length::Fill)
+        .align_x(Alignment::Center)
+        .into()
+}
+
+fn value_view<'a>(value: &Value, kind: LineKind) -> Element<'a, Message> {
+    widget::container(widget::text::monotext(value.to_string()))
+        .padding([0, 4])
+        .class(cosmic::theme::Container::Custom(Box::new(move |theme| {
+            diff_line_style(theme, kind, true)
+        })))
+        .into()
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Opening editor inputs from files and saving outputs to them.
+
+use {
+    crate::{Message, fl},
+    cosmic::{
+        self, Element, Task,
+        dialog::file_chooser,
+        iced::clipboard::{dnd::DndAction, mime::AllowedMimeTypes},
+        widget::dnd_destination::DndDestination,
+    },
+    dev_toolbox_core::{base64, bytes::ByteBuffer},
+    std::{borrow::Cow, path::PathBuf},
+};
+
+const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+
+/// The path and contents of a file opened into an editor.
+#[derive(Clone, Debug)]
+pub(crate) struct OpenedFile {
+    pub(crate) path: PathBuf,
+    pub(crate) contents: ByteBuffer,
+}
+
+impl OpenedFile {
+    /// The contents as text, for the editors that only take text.
+    pub(crate) fn text(&self) -> Result<String, String> {
+        self.contents
+            .as_text()
+            .map(str::to_string)
+            .map_err(|_| fl!("not-text"))
+    }
+
+    /// The contents as text for the key editors, which read binary DER keys as Base64.
+    pub(crate) fn key_text(&self) -> String {
+        self.contents.as_text().map_or_else(
+            |_| base64::encode_bytes(self.contents.as_bytes(), false),
+            str::to_string,
+        )
+    }
+}
+
+/// Asks for a file and reads it.
+///
+/// Nothing is emitted when the dialog is cancelled.
+pub(crate) fn open(
+    on_opened: impl FnOnce(Result<OpenedFile, String>) -> Message + Send + 'static,
+) -> Task<cosmic::Action<Message>> {
+    Task::future(async move {
+        let dialog = file_chooser::open::Dialog::new().title(fl!("open-file"));
+        let result = match dialog.open_file().await {
+            Ok(response) => match response.url().to_file_path() {
+                Ok(path) => read_file(path).await,
+                Err(()) => Err(fl!("not-a-local-file")),
+            },
+            Err(file_chooser::Error::Cancelled) => return None,
+            Err(err) => Err(err.to_string()),
+        };
+        Some(on_opened(result))
+    })
+    .and_then(|message| Task::done(cosmic::Action::App(message)))
+}
+
+/// Reads a file that was dropped onto an editor.
+pub(crate) fn read(
+    path: PathBuf,
+    on_opened: impl FnOnce(Result<OpenedFile, String>) -> Message + Send + 'static,
+) -> Task<cosmic::Action<Message>> {
+    cosmic::task::future(async move { cosmic::Action::App(on_opened(read_file(path).await)) })
+}
+
+/// Asks for a destination, suggesting the given file name, and writes the bytes to it.
+///
+/// Nothing is emitted when the dialog is cancelled.
+pub(crate) fn save(
+    file_name: String,
+    bytes: Vec<u8>,
+    on_saved: impl FnOnce(Result<(), String>) -> Message + Send + 'static,
+) -> Task<cosmic::Action<Message>> {
+    Task::future(async move {
+        let dialog = file_chooser::save::Dialog::new()
+            .title(fl!("save-as-file"))
+            .file_name(file_name);
+        let result = match dialog.save_file().await {
+            Ok(response) => match response.url()?.to_file_path() {
+                Ok(path) => tokio::fs::write(&path, bytes)
+                    .await
+                    .map_err(|err| format!("{}: {err}", path.display())),
+                Err(()) => Err(fl!("not-a-local-file")),
+            },
+            Err(file_chooser::Error::Cancelled) => return None,
+            Err(err) => Err(err.to_string()),
+        };
+        Some(on_saved(result))
+    })
+    .and_then(|message| Task::done(cosmic::Action::App(message)))
+}
+
+async fn read_file(path: PathBuf) -> Result<OpenedFile, String> {
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => Ok(OpenedFile {
+            path,
+            contents: bytes.into(),
+        }),
+        Err(err) => Err(format!("{}: {err}", path.display())),
+    }
+}
+
+/// The first local file of a drag and drop.
+#[derive(Clone, Debug)]
+pub(crate) struct DroppedFile(PathBuf);
+
+impl AllowedMimeTypes for DroppedFile {
+    fn allowed() -> Cow<'static, [String]> {
+        Cow::Owned(vec![URI_LIST_MIME_TYPE.to_string()])
+    }
+}
+
+impl TryFrom<(Vec<u8>, String)> for DroppedFile {
+    type Error = ();
+
+    fn try_from((data, _mime_type): (Vec<u8>, String)) -> Result<Self, Self::Error> {
+        String::from_utf8_lossy(&data)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .find_map(|line| url::Url::parse(line).ok()?.to_file_path().ok())
+            .map(DroppedFile)
+            .ok_or(())
+    }
+}
+
+/// Wraps an editor so that files dropped onto it are passed to `on_drop`.
+pub(crate) fn drop_target<'a>(
+    editor: impl Into<Element<'a, Message>>,
+    on_drop: impl Fn(PathBuf) -> Message + 'static,
+    no_op: Message,
+) -> Element<'a, Message> {
+    DndDestination::for_data::<DroppedFile>(editor, move |file, _action: DndAction| match file {
+        Some(DroppedFile(path)) => on_drop(path),
+        None => no_op.clone(),
+    })
+    .into()
+}
@@ -1,49 +1,100 @@
 use {
     crate::{
-        Message, app::AppModel, class::text_editor_class, fl, i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, GZipCompressorDecompressorConfig, PageConfig},
+        context_drawer_pages::history_page::{self, Recording},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            byte_output::ByteOutput,
+            conversion::{self, Conversion, Debounce},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
-    base64::Engine,
     cosmic::{
         self, Application, Element, Task, iced,
         iced::{
-            Alignment, Length, Padding, clipboard,
-            keyboard::{Key, key},
+            Alignment, Length, Padding, Subscription, clipboard,
             widget::{column, row},
         },
         widget::{
             self, Id,
-            text_editor::{self, Binding, TextEditor},
+            text_editor::{self, TextEditor},
         },
     },
-    flate2::{Compression, read::GzDecoder, write::GzEncoder},
-    std::{
-        io::{Read, Write},
-        sync::Arc,
+    dev_toolbox_core::{
+        base64::{decode_bytes, encode_bytes},
+        bytes::ByteBuffer,
+        detect::ContentKind,
+        gzip::{MAGIC, compress, decompress, is_round_trip},
+        service::Tool,
     },
+    std::{path::PathBuf, sync::Arc},
 };
 
 const INPUT_EDITOR_ID: &str = "input-editor";
 const OUTPUT_EDITOR_ID: &str = "output-editor";
 const OPERATIONS: [&str; 2] = ["compress", "decompress"];
 
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::GZipCompressorDecompressor,
+    id: "gzip-compressor-decompressor",
+    icon: ToolIcon::Named("media-zip-symbolic"),
+    new_page: || Box::new(GZipCompressorDecompressorPage::default()),
+    service: Some(Tool::Gzip),
+    takes: |kind| kind == ContentKind::Gzip,
+};
+
 #[derive(Debug, Clone)]
 pub enum GZipCompressorDecompressorMessage {
     InputEditorAction(text_editor::Action),
     OutputEditorAction(text_editor::Action),
     OperationChanged(usize),
+    RenderingChanged(usize),
+    LiveConvertToggled(bool),
+    InputSettled(u64),
     ConvertInput,
+    /// The output and whether it converts back to the input.
+    Converted(u64, Result<(ByteBuffer, bool), Status>),
+    CancelConversion,
+    Swap,
+    Clear,
     CopyText(Id),
     PasteText(Id),
     ReplaceText(Id, String),
+    OpenFile,
+    FileDropped(PathBuf),
+    FileOpened(Result<OpenedFile, String>),
+    SaveFile,
+    FileSaved(Result<(), String>),
     NoOp,
 }
 
+impl From<GZipCompressorDecompressorMessage> for Message {
+    fn from(message: GZipCompressorDecompressorMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct GZipCompressorDecompressorPage {
     input_content: text_editor::Content,
-    output_content: text_editor::Content,
+    output: ByteOutput,
     selected_operation: usize,
+    live_convert: bool,
+    debounce: Debounce,
+    conversion: Conversion,
+    recording: Recording,
+    /// Whether the latest output converts back to the input.
+    round_trip: Option<bool>,
+    status: Status,
 }
 
 impl UtilityPage for GZipCompressorDecompressorPage {
@@ -59,27 +110,38 @@ impl UtilityPage for GZipCompressorDecompressorPage {
             widget::text::title4(fl!("input"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
-            widget::button::text(fl!("convert")).on_press(
-                Message::GZipCompressorDecompressorMessage(
-                    GZipCompressorDecompressorMessage::ConvertInput
-                )
-            ),
+            widget::checkbox(self.live_convert)
+                .label(fl!("live-convert"))
+                .on_toggle(|live_convert| {
+                    Message::from(GZipCompressorDecompressorMessage::LiveConvertToggled(
+                        live_convert,
+                    ))
+                }),
+            widget::button::text(fl!("convert")).on_press(Message::from(
+                GZipCompressorDecompressorMessage::ConvertInput
+            )),
             widget::dropdown(
                 OPERATIONS
                     .map(|operation| LANGUAGE_LOADER.get(operation))
                     .to_vec(),
                 Some(self.selected_operation),
                 |selection| {
-                    Message::GZipCompressorDecompressorMessage(
-                        GZipCompressorDecompressorMessage::OperationChanged(selection),
-                    )
+                    Message::from(GZipCompressorDecompressorMessage::OperationChanged(
+                        selection,
+                    ))
                 },
             ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::from(GZipCompressorDecompressorMessage::OpenFile)),
+                widget::text(fl!("open-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::GZipCompressorDecompressorMessage(
-                        GZipCompressorDecompressorMessage::PasteText(Id::new(INPUT_EDITOR_ID)),
-                    )
+                    Message::from(GZipCompressorDecompressorMessage::PasteText(Id::new(
+                        INPUT_EDITOR_ID
+                    )),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -95,29 +157,45 @@ impl UtilityPage for GZipCompressorDecompressorPage {
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::GZipCompressorDecompressorMessage(
-                    GZipCompressorDecompressorMessage::InputEditorAction(action),
-                )
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
+                Message::from(GZipCompressorDecompressorMessage::InputEditorAction(action))
             })
+            .key_binding(editor_key_binding)
             .into();
+        let input_editor = files::drop_target(
+            input_editor,
+            |path| Message::from(GZipCompressorDecompressorMessage::FileDropped(path)),
+            Message::from(GZipCompressorDecompressorMessage::NoOp),
+        );
 
         let output_header: Element<'_, Message> = row![
-            widget::text::title4(fl!("output"))
-                .width(Length::Fill)
-                .align_x(Alignment::Start),
+            widget::row::with_capacity(2)
+                .push(widget::text::title4(fl!("output")))
+                .push_maybe(conversion::round_trip_view(self.round_trip))
+                .spacing(space_s)
+                .align_y(Alignment::Center)
+                .width(Length::Fill),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("object-flip-vertical-symbolic"))
+                    .on_press(Message::from(GZipCompressorDecompressorMessage::Swap)),
+                widget::text(fl!("swap")),
+                widget::tooltip::Position::Bottom,
+            ),
+            self.output.rendering_dropdown(|selection| {
+                Message::from(GZipCompressorDecompressorMessage::RenderingChanged(
+                    selection,
+                ))
+            }),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(GZipCompressorDecompressorMessage::SaveFile,)),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::GZipCompressorDecompressorMessage(
-                        GZipCompressorDecompressorMessage::CopyText(Id::new(OUTPUT_EDITOR_ID)),
-                    ),
+                    Message::from(GZipCompressorDecompressorMessage::CopyText(Id::new(
+                        OUTPUT_EDITOR_ID
+                    )),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
@@ -126,7 +204,7 @@ impl UtilityPage for GZipCompressorDecompressorPage {
         .align_y(Alignment::Center)
         .into();
 
-        let output_editor: Element<'_, Message> = TextEditor::new(&self.output_content)
+        let output_editor: Element<'_, Message> = TextEditor::new(&self.output.content)
             .padding(Padding::new(12.0))
             .height(Length::Fill)
             .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
@@ -134,13 +212,13 @@ impl UtilityPage for GZipCompressorDecompressorPage {
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::GZipCompressorDecompressorMessage(
-                    GZipCompressorDecompressorMessage::OutputEditorAction(action),
-                )
+                Message::from(GZipCompressorDecompressorMessage::OutputEditorAction(
+                    action,
+                ))
             })
             .into();
 
-        column![
+        let mut page = column![
             header,
             input_header,
             input_editor,
@@ -148,121 +226,338 @@ impl UtilityPage for GZipCompressorDecompressorPage {
             output_editor
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(progress) = self.conversion.view(Message::from(
+            GZipCompressorDecompressorMessage::CancelConversion,
+        )) {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::GZipCompressorDecompressorMessage(data_converter_formatter_message) => {
+        match message.tool_message::<GZipCompressorDecompressorMessage>() {
+            Some(data_converter_formatter_message) => {
                 match data_converter_formatter_message {
                     GZipCompressorDecompressorMessage::InputEditorAction(action) => {
+                        let is_edit = action.is_edit();
                         self.input_content.perform(action);
+                        if is_edit {
+                            self.recording.clear();
+                        }
+                        if is_edit && self.live_convert {
+                            return self.debounce.edit(|edit| {
+                                Message::from(GZipCompressorDecompressorMessage::InputSettled(edit))
+                            });
+                        }
+                    }
+                    GZipCompressorDecompressorMessage::LiveConvertToggled(live_convert) => {
+                        self.live_convert = live_convert;
+                        if live_convert {
+                            return self.convert_live();
+                        }
+                    }
+                    GZipCompressorDecompressorMessage::InputSettled(edit) => {
+                        if self.live_convert && self.debounce.is_settled(edit) {
+                            return self.convert_live();
+                        }
                     }
                     GZipCompressorDecompressorMessage::OutputEditorAction(action) => {
                         if !action.is_edit() {
-                            self.output_content.perform(action);
+                            self.output.content.perform(action);
                         }
                     }
                     GZipCompressorDecompressorMessage::OperationChanged(selection) => {
                         if selection != self.selected_operation {
                             self.selected_operation = selection;
-                            self.convert_input();
+                            return self.convert_live();
                         }
                     }
+                    GZipCompressorDecompressorMessage::RenderingChanged(selection) => {
+                        self.output.set_rendering(selection);
+                    }
                     GZipCompressorDecompressorMessage::ConvertInput => {
-                        self.convert_input();
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    GZipCompressorDecompressorMessage::Converted(id, result) => {
+                        if self.conversion.finish(id) {
+                            match result {
+                                Ok((output, round_trip)) => {
+                                    self.round_trip = Some(round_trip);
+                                    self.output.set_buffer(output);
+                                    self.status = Status::Idle;
+                                    if self.recording.finish(true) {
+                                        return history_page::record(
+                                            Page::GZipCompressorDecompressor,
+                                            self.history_entry(),
+                                        );
+                                    }
+                                }
+                                Err(status) => {
+                                    self.round_trip = None;
+                                    self.recording.finish(false);
+                                    self.status = status;
+                                }
+                            }
+                        }
+                    }
+                    GZipCompressorDecompressorMessage::Swap => {
+                        // Binary output cannot be edited as text, so it stays where it is.
+                        let Some(output) = self.output.text() else {
+                            self.status = Status::Info(fl!("binary-output"));
+                            return Task::none();
+                        };
+                        self.input_content = text_editor::Content::with_text(&output);
+                        self.selected_operation = 1 - self.selected_operation;
+                        self.recording.request();
+                        return self.convert_input();
+                    }
+                    GZipCompressorDecompressorMessage::Clear => {
+                        self.conversion.cancel();
+                        self.recording.clear();
+                        self.input_content = text_editor::Content::default();
+                        self.round_trip = None;
+                        self.output.set_buffer(ByteBuffer::default());
+                        self.status = Status::Idle;
+                    }
+                    GZipCompressorDecompressorMessage::CancelConversion => {
+                        self.conversion.cancel();
                     }
                     GZipCompressorDecompressorMessage::CopyText(id) => {
                         let mut to_copy: String = String::new();
                         if id == Id::new(INPUT_EDITOR_ID) {
                             to_copy = self.input_content.text();
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            to_copy = self.output_content.text();
+                            to_copy = self.output.content.text();
                         }
-                        return clipboard::write(to_copy);
+                        // Copying the output keeps its conversion in the history.
+                        let recorded = if id == Id::new(OUTPUT_EDITOR_ID) {
+                            history_page::record(
+                                Page::GZipCompressorDecompressor,
+                                self.history_entry(),
+                            )
+                        } else {
+                            Task::none()
+                        };
+                        return Task::batch([clipboard::write(to_copy), recorded]);
                     }
                     GZipCompressorDecompressorMessage::PasteText(id) => {
                         return clipboard::read().map(move |optional_data| match optional_data {
-                            Some(data) => {
-                                cosmic::Action::App(Message::GZipCompressorDecompressorMessage(
-                                    GZipCompressorDecompressorMessage::ReplaceText(
-                                        id.clone(),
-                                        data,
-                                    ),
-                                ))
-                            }
-                            None => {
-                                cosmic::Action::App(Message::GZipCompressorDecompressorMessage(
-                                    GZipCompressorDecompressorMessage::NoOp,
-                                ))
-                            }
+                            Some(data) => cosmic::Action::App(Message::from(
+                                GZipCompressorDecompressorMessage::ReplaceText(id.clone(), data),
+                            )),
+                            None => cosmic::Action::App(Message::from(
+                                GZipCompressorDecompressorMessage::NoOp,
+                            )),
                         });
                     }
                     GZipCompressorDecompressorMessage::ReplaceText(id, text) => {
                         if id == Id::new(INPUT_EDITOR_ID) {
+                            self.recording.clear();
                             self.input_content.perform(text_editor::Action::SelectAll);
                             self.input_content.perform(text_editor::Action::Edit(
                                 text_editor::Edit::Paste(Arc::new(text)),
                             ));
+                            if self.live_convert {
+                                return self.convert_live();
+                            }
                         } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            self.output_content.perform(text_editor::Action::SelectAll);
-                            self.output_content.perform(text_editor::Action::Edit(
-                                text_editor::Edit::Paste(Arc::new(text)),
-                            ));
+                            self.output.set_buffer(text.into());
                         }
                     }
+                    GZipCompressorDecompressorMessage::OpenFile => {
+                        return files::open(|result| {
+                            Message::from(GZipCompressorDecompressorMessage::FileOpened(result))
+                        });
+                    }
+                    GZipCompressorDecompressorMessage::FileDropped(path) => {
+                        return files::read(path, |result| {
+                            Message::from(GZipCompressorDecompressorMessage::FileOpened(result))
+                        });
+                    }
+                    GZipCompressorDecompressorMessage::FileOpened(result) => {
+                        let input = result.and_then(|file| {
+                            if file.contents.as_bytes().starts_with(&MAGIC) {
+                                Ok((Some(1), encode_bytes(file.contents.as_bytes(), false)))
+                            } else {
+                                Ok((None, file.text()?))
+                            }
+                        });
+                        match input {
+                            Ok((operation, text)) => {
+                                // Raw GZip files are decompressed from the Base64 text they encode to.
+                                if let Some(operation) = operation {
+                                    self.selected_operation = operation;
+                                }
+                                self.input_content = text_editor::Content::with_text(&text);
+                                return self.convert_live();
+                            }
+                            Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                        }
+                    }
+                    GZipCompressorDecompressorMessage::SaveFile => {
+                        return match self.selected_operation {
+                            // Compressed data is saved as a GZip file rather than its Base64 text.
+                            0 => match decode_bytes(&self.output.bytes(), false) {
+                                Ok(compressed_data) => files::save(
+                                    "compressed.gz".to_string(),
+                                    compressed_data.into_bytes(),
+                                    |result| {
+                                        Message::from(GZipCompressorDecompressorMessage::FileSaved(
+                                            result,
+                                        ))
+                                    },
+                                ),
+                                Err(_) => Task::none(),
+                            },
+                            _ => self.output.save_as(
+                                format!("decompressed.{}", self.output.extension()),
+                                |result| {
+                                    Message::from(GZipCompressorDecompressorMessage::FileSaved(
+                                        result,
+                                    ))
+                                },
+                            ),
+                        };
+                    }
+                    GZipCompressorDecompressorMessage::FileSaved(result) => {
+                        self.status = Status::saved(result);
+                    }
                     GZipCompressorDecompressorMessage::NoOp => {}
                 }
             }
-            _ => {
-                println!("Wrong message type");
-            }
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.gzip_compressor_decompressor;
+        self.live_convert = config.live_convert;
+        self.selected_operation = config.operation.min(OPERATIONS.len() - 1);
+        if !config.input.is_empty() {
+            self.input_content = text_editor::Content::with_text(&config.input);
+            return self.convert_live();
+        }
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::GZipCompressorDecompressor(GZipCompressorDecompressorConfig {
+            operation: self.selected_operation,
+            live_convert: self.live_convert,
+            input: self.input_content.text(),
+        })
+    }
+
+    fn paste_detected(
+        &mut self,
+        content: String,
+        _kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        self.selected_operation = 1;
+        self.input_content = text_editor::Content::with_text(&content);
+        self.convert_live()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        Some(Message::from(match action {
+            EditAction::Convert => GZipCompressorDecompressorMessage::ConvertInput,
+            EditAction::Swap => GZipCompressorDecompressorMessage::Swap,
+            EditAction::CopyOutput => {
+                GZipCompressorDecompressorMessage::CopyText(Id::new(OUTPUT_EDITOR_ID))
+            }
+            EditAction::PasteInput => {
+                GZipCompressorDecompressorMessage::PasteText(Id::new(INPUT_EDITOR_ID))
+            }
+            EditAction::Clear => GZipCompressorDecompressorMessage::Clear,
+        }))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        OPERATIONS
+            .into_iter()
+            .enumerate()
+            .map(|(operation, id)| {
+                Command::new(
+                    LANGUAGE_LOADER.get(id),
+                    [
+                        GZipCompressorDecompressorMessage::OperationChanged(operation),
+                        GZipCompressorDecompressorMessage::ConvertInput,
+                    ]
+                    .map(Message::from),
+                )
+            })
+            .collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        self.recording
+            .is_converted()
+            .then(|| (self.input_content.text(), self.output.content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.input_content = text_editor::Content::with_text(&input);
+        self.convert_input()
+    }
+
+    fn prefill(&mut self, operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        if let Some(position) = OPERATIONS.iter().position(|other| *other == operation) {
+            self.selected_operation = position;
+        }
+        self.restore_input(input)
+    }
 }
 
 impl GZipCompressorDecompressorPage {
-    fn convert_input(&mut self) {
-        let engine = base64::engine::general_purpose::STANDARD;
+    /// Converts the input in the background, replacing a conversion that is still running.
+    fn convert_input(&mut self) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
         let input = self.input_content.text();
-        match self.selected_operation {
-            0 => {
-                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-                encoder.write_all(input.as_bytes()).unwrap();
-                let compressed_data = encoder.finish().unwrap();
-                self.output_content.perform(text_editor::Action::SelectAll);
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
-                        Arc::new(engine.encode(compressed_data)),
-                    )));
-            }
-            _ => {
-                let compressed_data = engine.decode(input.trim());
-                if let Ok(bytes) = compressed_data {
-                    let mut decoder = GzDecoder::new(&bytes[..]);
-                    let mut decompressed_string = String::new();
-                    match decoder.read_to_string(&mut decompressed_string) {
-                        Ok(_) => {
-                            self.output_content.perform(text_editor::Action::SelectAll);
-                            self.output_content
-                                .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                            self.output_content.perform(text_editor::Action::Edit(
-                                text_editor::Edit::Paste(Arc::new(decompressed_string)),
-                            ));
-                        }
-                        Err(err) => println!("GZip Decompression Error: {}", err),
-                    };
-                } else if let Err(err) = compressed_data {
-                    println!("Base64 Decoding Error: {}", err);
+        let operation = self.selected_operation;
+        self.conversion.start(
+            move || match operation {
+                0 => {
+                    let output = compress(&input);
+                    let round_trip = is_round_trip(&output, input.as_bytes());
+                    Ok((output.into(), round_trip))
                 }
-            }
+                _ => decompress(&input)
+                    .map(|output| {
+                        let round_trip = is_round_trip(&input, output.as_bytes());
+                        (output, round_trip)
+                    })
+                    .map_err(|err| Status::from(&err)),
+            },
+            |id, result| {
+                Message::from(GZipCompressorDecompressorMessage::Converted(
+                    id,
+                    result.flatten(),
+                ))
+            },
+        )
+    }
+
+    /// Converts the input after a change, unless it is too large to convert on every change.
+    fn convert_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.input_content.text()) {
+            return self.convert_input();
         }
+        self.conversion.cancel();
+        self.recording.clear();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
     }
 }
@@ -1,99 +1,235 @@
 use {
     crate::{
         Message,
-        app::AppModel,
+        app::{AppModel, Page},
         class::{text_editor_class, text_input_style},
+        command_palette::Command,
+        config::{Config, JwtDebuggerConfig, PageConfig},
+        context_drawer_pages::history_page::{self, Recording},
         fl,
         i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        utility_pages::{
+            EditAction, UtilityPage,
+            conversion::{Conversion, Debounce},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
     cosmic::{
         self, Application, Element, Task, iced,
         iced::{
-            Alignment, Length, Padding, clipboard,
-            keyboard::{Key, key},
+            Alignment, Length, Padding, Subscription, clipboard, time,
             widget::{column, row},
         },
         widget::{
             self, Id,
-            text_editor::{self, Binding, TextEditor},
+            text_editor::{self, TextEditor},
+            text_input::TextInput,
         },
     },
-    jsonwebtoken::{self, Algorithm},
-    std::sync::Arc,
+    dev_toolbox_core::{
+        claims::{self, Claim, ClaimCheck, ClaimRules, Finding, Verdict},
+        detect::ContentKind,
+        jwe::{self, KeyManagement},
+        jwk,
+        jwt::{self, Algorithm, JwtError, JwtKeys, KeyEncoding},
+        key::{self, Key, KeyPair, KeyType},
+        service::Tool,
+        unix_time::{self, DurationUnit, TimestampUnit},
+    },
+    std::{path::PathBuf, sync::Arc, time::Duration},
 };
 
 const TOKEN_EDITOR_ID: &str = "token-editor";
 const HEADER_EDITOR_ID: &str = "header-editor";
 const CLAIMS_EDITOR_ID: &str = "claims-editor";
+const ENCRYPTION_HEADER_EDITOR_ID: &str = "encryption-header-editor";
+/// The header filled in when encryption is turned on, nesting the signed token.
+const DEFAULT_ENCRYPTION_HEADER: &str = r#"{
+  "alg": "dir",
+  "enc": "A256GCM",
+  "cty": "JWT"
+}"#;
 const KEY_ENCODING: [&str; 2] = ["utf-8", "base64"];
+
+/// Algorithms offered by the command palette for signing.
+const SIGNING_ALGORITHMS: [Algorithm; 12] = [
+    Algorithm::HS256,
+    Algorithm::HS384,
+    Algorithm::HS512,
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
 const SYMMETRIC_KEY_TEXT_ID: &str = "symmetric-key-text";
 const PUBLIC_KEY_EDITOR_ID: &str = "public-key-editor";
 const PRIVATE_KEY_EDITOR_ID: &str = "private-key-editor";
 
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::JwtDebugger,
+    id: "jwt-debugger",
+    icon: ToolIcon::Svg(include_bytes!("../../resources/icons/jwt-symbolic.svg")),
+    new_page: || Box::new(JwtDebuggerPage::default()),
+    service: Some(Tool::Jwt),
+    takes: |kind| kind == ContentKind::Jwt,
+};
+
 #[derive(Debug, Clone)]
 pub enum JwtDebuggerMessage {
     TokenEditorAction(text_editor::Action),
     HeaderEditorAction(text_editor::Action),
     ClaimsEditorAction(text_editor::Action),
+    EncryptionHeaderEditorAction(text_editor::Action),
     PublicKeyEditorAction(text_editor::Action),
     PrivateKeyEditorAction(text_editor::Action),
     KeyEncodingChanged(usize),
     SymmetricKeyChanged(String),
+    ValidateClaimsToggled(bool),
+    EncryptToggled(bool),
+    RememberTokenToggled(bool),
+    ExpectedAudienceChanged(String),
+    ExpectedIssuerChanged(String),
+    ExpectedSubjectChanged(String),
+    LeewayChanged(String),
+    RequiredClaimToggled(Claim, bool),
+    SignWith(Algorithm),
+    RsaKeySizeChanged(usize),
+    EditorsSettled(u64),
+    TokenDecoded(u64, Result<Box<DecodedToken>, Status>),
+    TokenEncoded(u64, Result<Box<EncodedToken>, Status>),
+    CancelConversion,
+    GenerateKey,
+    KeyGenerated(u64, Result<KeyPair, Status>),
+    CancelGeneration,
+    CopyAsJwk,
+    Clear,
     CopyText(Id),
     PasteText(Id),
     ReplaceText(Id, String),
+    OpenFile(Id),
+    FileDropped(Id, PathBuf),
+    FileOpened(Id, Result<OpenedFile, String>),
+    SaveFile(Id),
+    FileSaved(Result<(), String>),
     NoOp,
 }
 
+impl From<JwtDebuggerMessage> for Message {
+    fn from(message: JwtDebuggerMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 enum Operation {
     Encode,
     Decode,
 }
 
+/// What decoding a token found, with the texts of the editors it fills in.
+#[derive(Debug, Clone, Default)]
+pub struct DecodedToken {
+    encrypted: bool,
+    /// The encryption header and its `alg`, when the token is encrypted and its header is valid.
+    encryption_header: Option<String>,
+    key_management: Option<KeyManagement>,
+    header: Option<String>,
+    algorithm: Option<Algorithm>,
+    claims_text: Option<String>,
+    claims: Option<serde_json::Value>,
+    status: Status,
+}
+
+/// The token encoded from the editors, with what was read from its headers.
+#[derive(Debug, Clone, Default)]
+pub struct EncodedToken {
+    algorithm: Option<Algorithm>,
+    key_management: Option<KeyManagement>,
+    /// The token, `None` when it could not be encoded.
+    token: Option<String>,
+    status: Status,
+}
+
+/// The texts of the editors and the keys a token is encoded from.
+struct Encoding {
+    encrypt: bool,
+    encryption_header: String,
+    header: String,
+    claims: String,
+    keys: JwtKeys,
+}
+
 pub(crate) struct JwtDebuggerPage {
     token_content: text_editor::Content,
     header_content: text_editor::Content,
     claims_content: text_editor::Content,
+    encryption_header_content: text_editor::Content,
     public_key_content: text_editor::Content,
     private_key_content: text_editor::Content,
     algorithm: Algorithm,
     key_encoding: usize,
     symmetric_key: String,
-    status: String,
+    /// Whether tokens are encrypted, as the last decoded token was when it had five parts.
+    encrypt: bool,
+    /// The `alg` of the encryption header, when it is valid.
+    key_management: Option<KeyManagement>,
+    validate_claims: bool,
+    claim_rules: ClaimRules,
+    /// Whether the token is saved with the options, which it is not by default as it is a
+    /// credential.
+    remember_token: bool,
+    /// The text of the clock skew input, which may not be a number while it is edited.
+    leeway: String,
+    /// The claims of the token last decoded or encoded.
+    claims: Option<serde_json::Value>,
+    /// The size of the keys generated for the RSA algorithms, an index into [`KeyType::RSA`].
+    rsa_key_size: usize,
+    debounce: Debounce,
+    /// Decodes or encodes the token off the UI thread, as keys can be slow to read and use.
+    conversion: Conversion,
+    generation: Conversion,
+    recording: Recording,
+    status: Status,
     last_operation: Operation,
 }
 
-#[derive(PartialEq)]
-enum AlgorithmType {
-    Symmetric,
-    Asymmetric,
-}
-
 impl Default for JwtDebuggerPage {
     fn default() -> Self {
         Self {
             token_content: text_editor::Content::default(),
             header_content: text_editor::Content::default(),
             claims_content: text_editor::Content::default(),
+            encryption_header_content: text_editor::Content::default(),
             public_key_content: text_editor::Content::default(),
             private_key_content: text_editor::Content::default(),
             algorithm: Algorithm::default(),
             key_encoding: usize::default(),
             symmetric_key: String::default(),
-            status: "ok".to_string(),
+            encrypt: false,
+            key_management: None,
+            validate_claims: false,
+            claim_rules: ClaimRules::default(),
+            remember_token: false,
+            leeway: ClaimRules::default().leeway.to_string(),
+            claims: None,
+            rsa_key_size: 0,
+            debounce: Debounce::default(),
+            conversion: Conversion::default(),
+            generation: Conversion::default(),
+            recording: Recording::default(),
+            status: Status::Success(fl!("jwt-debugger", "ok")),
             last_operation: Operation::Decode,
         }
     }
 }
 
-fn algorithm_type(algorithm: Algorithm) -> AlgorithmType {
-    match algorithm {
-        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => AlgorithmType::Symmetric,
-        _ => AlgorithmType::Asymmetric,
-    }
-}
-
 fn replace_text_in_field(content: &mut text_editor::Content, text: String) {
     content.perform(text_editor::Action::SelectAll);
     content.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
@@ -101,6 +237,53 @@ fn replace_text_in_field(content: &mut text_editor::Content, text: String) {
     )));
 }
 
+/// The file name suggested when saving the contents of an editor.
+fn file_name(id: &Id) -> &'static str {
+    if *id == Id::new(TOKEN_EDITOR_ID) {
+        "token.jwt"
+    } else if *id == Id::new(HEADER_EDITOR_ID) {
+        "header.json"
+    } else if *id == Id::new(CLAIMS_EDITOR_ID) {
+        "claims.json"
+    } else if *id == Id::new(ENCRYPTION_HEADER_EDITOR_ID) {
+        "encryption-header.json"
+    } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+        "public-key.pem"
+    } else {
+        "private-key.pem"
+    }
+}
+
+fn open_file_button<'a>(editor_id: &'static str) -> Element<'a, Message> {
+    widget::tooltip(
+        widget::button::icon(widget::icon::from_name("document-open-symbolic")).on_press(
+            Message::from(JwtDebuggerMessage::OpenFile(Id::new(editor_id))),
+        ),
+        widget::text(fl!("open-file")),
+        widget::tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+fn save_file_button<'a>(editor_id: &'static str) -> Element<'a, Message> {
+    widget::tooltip(
+        widget::button::icon(widget::icon::from_name("document-save-as-symbolic")).on_press(
+            Message::from(JwtDebuggerMessage::SaveFile(Id::new(editor_id))),
+        ),
+        widget::text(fl!("save-as-file")),
+        widget::tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+fn drop_target<'a>(editor: Element<'a, Message>, editor_id: &'static str) -> Element<'a, Message> {
+    files::drop_target(
+        editor,
+        move |path| Message::from(JwtDebuggerMessage::FileDropped(Id::new(editor_id), path)),
+        Message::from(JwtDebuggerMessage::NoOp),
+    )
+}
+
 impl UtilityPage for JwtDebuggerPage {
     fn get_utility_page(&self) -> Element<'_, Message> {
         let space_s = cosmic::theme::spacing().space_s;
@@ -114,27 +297,28 @@ impl UtilityPage for JwtDebuggerPage {
             widget::text::title4(fl!("options"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
+            widget::button::text(fl!("jwt-debugger", "generate-key"))
+                .on_press(Message::from(JwtDebuggerMessage::GenerateKey)),
         ]
+        .align_y(Alignment::Center)
         .into();
 
         let token_header: Element<'_, Message> = row![
             widget::text::title4(fl!("jwt-debugger", "token"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
+            save_file_button(TOKEN_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::CopyText(Id::new(
-                        TOKEN_EDITOR_ID
-                    )),),
+                    Message::from(JwtDebuggerMessage::CopyText(Id::new(TOKEN_EDITOR_ID)),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
             ),
+            open_file_button(TOKEN_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(Id::new(
-                        TOKEN_EDITOR_ID
-                    )),)
+                    Message::from(JwtDebuggerMessage::PasteText(Id::new(TOKEN_EDITOR_ID)),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -150,37 +334,27 @@ impl UtilityPage for JwtDebuggerPage {
                 text_editor_class,
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::JwtDebuggerMessage(JwtDebuggerMessage::TokenEditorAction(action))
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
-            })
+            .on_action(|action| Message::from(JwtDebuggerMessage::TokenEditorAction(action)))
+            .key_binding(editor_key_binding)
             .into();
+        let token_editor = drop_target(token_editor, TOKEN_EDITOR_ID);
 
         let header_header: Element<'_, Message> = row![
             widget::text::title4(fl!("jwt-debugger", "header"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
+            save_file_button(HEADER_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::CopyText(Id::new(
-                        HEADER_EDITOR_ID
-                    )),),
+                    Message::from(JwtDebuggerMessage::CopyText(Id::new(HEADER_EDITOR_ID)),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
             ),
+            open_file_button(HEADER_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(Id::new(
-                        HEADER_EDITOR_ID
-                    )),)
+                    Message::from(JwtDebuggerMessage::PasteText(Id::new(HEADER_EDITOR_ID)),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -196,37 +370,27 @@ impl UtilityPage for JwtDebuggerPage {
                 text_editor_class,
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::JwtDebuggerMessage(JwtDebuggerMessage::HeaderEditorAction(action))
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
-            })
+            .on_action(|action| Message::from(JwtDebuggerMessage::HeaderEditorAction(action)))
+            .key_binding(editor_key_binding)
             .into();
+        let header_editor = drop_target(header_editor, HEADER_EDITOR_ID);
 
         let claims_header: Element<'_, Message> = row![
             widget::text::title4(fl!("jwt-debugger", "claims"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
+            save_file_button(CLAIMS_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::CopyText(Id::new(
-                        CLAIMS_EDITOR_ID
-                    )),),
+                    Message::from(JwtDebuggerMessage::CopyText(Id::new(CLAIMS_EDITOR_ID)),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
             ),
+            open_file_button(CLAIMS_EDITOR_ID),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(Id::new(
-                        CLAIMS_EDITOR_ID
-                    )),)
+                    Message::from(JwtDebuggerMessage::PasteText(Id::new(CLAIMS_EDITOR_ID)),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -242,32 +406,86 @@ impl UtilityPage for JwtDebuggerPage {
                 text_editor_class,
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::JwtDebuggerMessage(JwtDebuggerMessage::ClaimsEditorAction(action))
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
-            })
+            .on_action(|action| Message::from(JwtDebuggerMessage::ClaimsEditorAction(action)))
+            .key_binding(editor_key_binding)
             .into();
+        let claims_editor = drop_target(claims_editor, CLAIMS_EDITOR_ID);
 
-        let status_row: Element<'_, Message> = row![
-            widget::text::heading(fl!("status")),
-            widget::space().width(8),
-            widget::text::body(LANGUAGE_LOADER.get_attr("jwt-debugger", self.status.as_str())),
+        let encryption_header_header: Element<'_, Message> = row![
+            widget::text::title4(fl!("jwt-debugger", "encryption-header"))
+                .width(Length::Fill)
+                .align_x(Alignment::Start),
+            save_file_button(ENCRYPTION_HEADER_EDITOR_ID),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
+                    Message::from(JwtDebuggerMessage::CopyText(Id::new(
+                        ENCRYPTION_HEADER_EDITOR_ID
+                    )),),
+                ),
+                widget::text(fl!("copy")),
+                widget::tooltip::Position::Bottom,
+            ),
+            open_file_button(ENCRYPTION_HEADER_EDITOR_ID),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
+                    Message::from(JwtDebuggerMessage::PasteText(Id::new(
+                        ENCRYPTION_HEADER_EDITOR_ID
+                    )),)
+                ),
+                widget::text(fl!("paste")),
+                widget::tooltip::Position::Bottom,
+            ),
         ]
+        .align_y(Alignment::Center)
         .into();
 
+        let encryption_header_editor: Element<'_, Message> =
+            TextEditor::new(&self.encryption_header_content)
+                .padding(Padding::new(12.0))
+                .height(Length::Fill)
+                .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+                    text_editor_class,
+                )))
+                .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+                .on_action(|action| {
+                    Message::from(JwtDebuggerMessage::EncryptionHeaderEditorAction(action))
+                })
+                .key_binding(editor_key_binding)
+                .into();
+        let encryption_header_editor =
+            drop_target(encryption_header_editor, ENCRYPTION_HEADER_EDITOR_ID);
+
         let mut column = widget::column::with_capacity(10)
             .spacing(space_s)
             .height(Length::Fill);
-        column = column.push(header).push(options_header);
+        column = column.push(header).push(options_header).push(
+            widget::checkbox(self.validate_claims)
+                .label(fl!("jwt-debugger", "validate-claims"))
+                .on_toggle(|validate_claims| {
+                    Message::from(JwtDebuggerMessage::ValidateClaimsToggled(validate_claims))
+                }),
+        );
+        column = column.push(
+            widget::checkbox(self.encrypt)
+                .label(fl!("jwt-debugger", "encrypt"))
+                .on_toggle(|encrypt| Message::from(JwtDebuggerMessage::EncryptToggled(encrypt))),
+        );
+        column = column.push(
+            widget::checkbox(self.remember_token)
+                .label(fl!("jwt-debugger", "remember-token"))
+                .on_toggle(|remember_token| {
+                    Message::from(JwtDebuggerMessage::RememberTokenToggled(remember_token))
+                }),
+        );
+
+        // A nested token may need the symmetric key to decrypt it and the PEM keys to verify it.
+        let key_management = self.key_management.filter(|_| self.encrypt);
+        let uses_symmetric_key = jwt::is_symmetric(self.algorithm)
+            || key_management.is_some_and(KeyManagement::is_symmetric);
+        let uses_pem_keys = !jwt::is_symmetric(self.algorithm)
+            || key_management.is_some_and(|key_management| !key_management.is_symmetric());
 
-        if algorithm_type(self.algorithm) == AlgorithmType::Symmetric {
+        if uses_symmetric_key {
             let key_encoding_option: Element<'_, Message> = widget::settings::item(
                 fl!("jwt-debugger", "key-encoding"),
                 widget::dropdown(
@@ -275,11 +493,7 @@ impl UtilityPage for JwtDebuggerPage {
                         .map(|key_encoding| LANGUAGE_LOADER.get_attr("jwt-debugger", key_encoding))
                         .to_vec(),
                     Some(self.key_encoding),
-                    |selection| {
-                        Message::JwtDebuggerMessage(JwtDebuggerMessage::KeyEncodingChanged(
-                            selection,
-                        ))
-                    },
+                    |selection| Message::from(JwtDebuggerMessage::KeyEncodingChanged(selection)),
                 ),
             )
             .into();
@@ -290,43 +504,60 @@ impl UtilityPage for JwtDebuggerPage {
                 .trailing_icon(
                     widget::tooltip(
                         widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
-                            .on_press(Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(
-                                Id::new(SYMMETRIC_KEY_TEXT_ID),
-                            ))),
+                            .on_press(Message::from(JwtDebuggerMessage::PasteText(Id::new(
+                                SYMMETRIC_KEY_TEXT_ID,
+                            )))),
                         widget::text(fl!("paste")),
                         widget::tooltip::Position::Bottom,
                     )
                     .into(),
                 )
-                .on_input(|input| {
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::SymmetricKeyChanged(input))
-                });
+                .on_input(|input| Message::from(JwtDebuggerMessage::SymmetricKeyChanged(input)));
             column = column.push(key_encoding_option).push(symmetric_key_text);
-        } else {
-            let public_key_header: Element<'_, Message> =
-                row![
-                    widget::text::heading(fl!("jwt-debugger", "public-key"))
-                        .width(Length::Fill)
-                        .align_x(Alignment::Start),
-                    widget::tooltip(
-                        widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
-                            .on_press(Message::JwtDebuggerMessage(JwtDebuggerMessage::CopyText(
-                                Id::new(PUBLIC_KEY_EDITOR_ID)
-                            ),),),
-                        widget::text(fl!("copy")),
-                        widget::tooltip::Position::Bottom,
-                    ),
-                    widget::tooltip(
-                        widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
-                            .on_press(Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(
-                                Id::new(PUBLIC_KEY_EDITOR_ID)
-                            ),)),
-                        widget::text(fl!("paste")),
-                        widget::tooltip::Position::Bottom,
+        }
+        if uses_pem_keys {
+            if KeyType::for_algorithm(self.algorithm, KeyType::Rsa2048).is_some_and(KeyType::is_rsa)
+            {
+                let key_size_option: Element<'_, Message> = widget::settings::item(
+                    fl!("jwt-debugger", "key-size"),
+                    widget::dropdown(
+                        KeyType::RSA.map(KeyType::name).to_vec(),
+                        Some(self.rsa_key_size),
+                        |selection| Message::from(JwtDebuggerMessage::RsaKeySizeChanged(selection)),
                     ),
-                ]
-                .align_y(Alignment::Center)
+                )
                 .into();
+                column = column.push(key_size_option);
+            }
+            let public_key_header: Element<'_, Message> = row![
+                widget::text::heading(fl!("jwt-debugger", "public-key"))
+                    .width(Length::Fill)
+                    .align_x(Alignment::Start),
+                widget::tooltip(
+                    widget::button::icon(widget::icon::from_name("document-export-symbolic"))
+                        .on_press(Message::from(JwtDebuggerMessage::CopyAsJwk)),
+                    widget::text(fl!("jwt-debugger", "copy-jwk")),
+                    widget::tooltip::Position::Bottom,
+                ),
+                save_file_button(PUBLIC_KEY_EDITOR_ID),
+                widget::tooltip(
+                    widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
+                        Message::from(JwtDebuggerMessage::CopyText(Id::new(PUBLIC_KEY_EDITOR_ID)),),
+                    ),
+                    widget::text(fl!("copy")),
+                    widget::tooltip::Position::Bottom,
+                ),
+                open_file_button(PUBLIC_KEY_EDITOR_ID),
+                widget::tooltip(
+                    widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
+                        Message::from(JwtDebuggerMessage::PasteText(Id::new(PUBLIC_KEY_EDITOR_ID)),)
+                    ),
+                    widget::text(fl!("paste")),
+                    widget::tooltip::Position::Bottom,
+                ),
+            ]
+            .align_y(Alignment::Center)
+            .into();
 
             let public_key_editor: Element<'_, Message> = TextEditor::new(&self.public_key_content)
                 .padding(Padding::new(12.0))
@@ -336,42 +567,37 @@ impl UtilityPage for JwtDebuggerPage {
                 )))
                 .wrapping(iced::core::text::Wrapping::WordOrGlyph)
                 .on_action(|action| {
-                    Message::JwtDebuggerMessage(JwtDebuggerMessage::PublicKeyEditorAction(action))
-                })
-                .key_binding(|key_press| {
-                    if key_press.key == Key::Named(key::Named::Tab)
-                        && matches!(key_press.status, text_editor::Status::Focused { .. })
-                    {
-                        return Some(Binding::Insert('\t'));
-                    }
-                    return Binding::from_key_press(key_press);
+                    Message::from(JwtDebuggerMessage::PublicKeyEditorAction(action))
                 })
+                .key_binding(editor_key_binding)
                 .into();
+            let public_key_editor = drop_target(public_key_editor, PUBLIC_KEY_EDITOR_ID);
 
-            let private_key_header: Element<'_, Message> =
-                row![
+            let private_key_header: Element<'_, Message> = row![
                     widget::text::heading(fl!("jwt-debugger", "private-key"))
                         .width(Length::Fill)
                         .align_x(Alignment::Start),
+                    save_file_button(PRIVATE_KEY_EDITOR_ID),
                     widget::tooltip(
                         widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
-                            .on_press(Message::JwtDebuggerMessage(JwtDebuggerMessage::CopyText(
+                            .on_press(Message::from(JwtDebuggerMessage::CopyText(
                                 Id::new(PRIVATE_KEY_EDITOR_ID)
                             ),),),
                         widget::text(fl!("copy")),
                         widget::tooltip::Position::Bottom,
                     ),
+                    open_file_button(PRIVATE_KEY_EDITOR_ID),
                     widget::tooltip(
                         widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
-                            .on_press(Message::JwtDebuggerMessage(JwtDebuggerMessage::PasteText(
+                            .on_press(Message::from(JwtDebuggerMessage::PasteText(
                                 Id::new(PRIVATE_KEY_EDITOR_ID)
                             ),)),
                         widget::text(fl!("paste")),
                         widget::tooltip::Position::Bottom,
                     ),
                 ]
-                .align_y(Alignment::Center)
-                .into();
+            .align_y(Alignment::Center)
+            .into();
 
             let private_key_editor: Element<'_, Message> =
                 TextEditor::new(&self.private_key_content)
@@ -382,19 +608,11 @@ impl UtilityPage for JwtDebuggerPage {
                     )))
                     .wrapping(iced::core::text::Wrapping::WordOrGlyph)
                     .on_action(|action| {
-                        Message::JwtDebuggerMessage(JwtDebuggerMessage::PrivateKeyEditorAction(
-                            action,
-                        ))
-                    })
-                    .key_binding(|key_press| {
-                        if key_press.key == Key::Named(key::Named::Tab)
-                            && matches!(key_press.status, text_editor::Status::Focused { .. })
-                        {
-                            return Some(Binding::Insert('\t'));
-                        }
-                        return Binding::from_key_press(key_press);
+                        Message::from(JwtDebuggerMessage::PrivateKeyEditorAction(action))
                     })
+                    .key_binding(editor_key_binding)
                     .into();
+            let private_key_editor = drop_target(private_key_editor, PRIVATE_KEY_EDITOR_ID);
 
             column = column.push(
                 row![
@@ -406,22 +624,35 @@ impl UtilityPage for JwtDebuggerPage {
             );
         }
 
-        column = column
-            .push(token_header)
-            .push(token_editor)
-            .push(
-                row![
-                    column![header_header, header_editor].spacing(space_s),
-                    column![claims_header, claims_editor].spacing(space_s)
-                ]
-                .height(Length::FillPortion(3))
-                .spacing(space_s),
-            )
-            .push(
-                column![status_row]
-                    .width(Length::Fill)
-                    .align_x(Alignment::Center),
-            );
+        let mut editors = widget::row::with_capacity(3)
+            .height(Length::FillPortion(3))
+            .spacing(space_s);
+        if self.encrypt {
+            editors = editors
+                .push(column![encryption_header_header, encryption_header_editor].spacing(space_s));
+        }
+        editors = editors
+            .push(column![header_header, header_editor].spacing(space_s))
+            .push(column![claims_header, claims_editor].spacing(space_s));
+        column = column.push(token_header).push(token_editor).push(editors);
+        if self.validate_claims {
+            column = column.push(self.validation_view());
+        }
+        if let Some(progress) = self
+            .conversion
+            .view(Message::from(JwtDebuggerMessage::CancelConversion))
+        {
+            column = column.push(progress);
+        }
+        if let Some(progress) = self
+            .generation
+            .view(Message::from(JwtDebuggerMessage::CancelGeneration))
+        {
+            column = column.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            column = column.push(status_banner);
+        }
         column.into()
     }
 
@@ -429,51 +660,192 @@ impl UtilityPage for JwtDebuggerPage {
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::JwtDebuggerMessage(data_converter_formatter_message) => {
+        match message.tool_message::<JwtDebuggerMessage>() {
+            Some(data_converter_formatter_message) => {
                 match data_converter_formatter_message {
                     JwtDebuggerMessage::TokenEditorAction(action) => {
                         let is_edit = matches!(action, text_editor::Action::Edit(_));
                         self.token_content.perform(action);
                         if is_edit {
-                            self.decode_token();
+                            return self.edit(Operation::Decode);
                         }
                     }
                     JwtDebuggerMessage::HeaderEditorAction(action) => {
                         let is_edit = matches!(action, text_editor::Action::Edit(_));
                         self.header_content.perform(action);
                         if is_edit {
-                            self.encode_token();
+                            return self.edit(Operation::Encode);
                         }
                     }
                     JwtDebuggerMessage::ClaimsEditorAction(action) => {
                         let is_edit = matches!(action, text_editor::Action::Edit(_));
                         self.claims_content.perform(action);
                         if is_edit {
-                            self.encode_token();
+                            return self.edit(Operation::Encode);
+                        }
+                    }
+                    JwtDebuggerMessage::EncryptionHeaderEditorAction(action) => {
+                        let is_edit = matches!(action, text_editor::Action::Edit(_));
+                        self.encryption_header_content.perform(action);
+                        if is_edit {
+                            return self.edit(Operation::Encode);
                         }
                     }
                     JwtDebuggerMessage::PublicKeyEditorAction(action) => {
                         let is_edit = matches!(action, text_editor::Action::Edit(_));
                         self.public_key_content.perform(action);
                         if is_edit {
-                            self.decode_token();
+                            return self.edit(Operation::Decode);
                         }
                     }
                     JwtDebuggerMessage::PrivateKeyEditorAction(action) => {
                         let is_edit = matches!(action, text_editor::Action::Edit(_));
                         self.private_key_content.perform(action);
                         if is_edit {
-                            self.encode_token();
+                            return self.edit(Operation::Encode);
                         }
                     }
                     JwtDebuggerMessage::KeyEncodingChanged(selection) => {
                         self.key_encoding = selection;
-                        self.perform_last_operation();
+                        return self.perform_last_operation();
                     }
                     JwtDebuggerMessage::SymmetricKeyChanged(input) => {
                         self.symmetric_key = input;
                     }
+                    JwtDebuggerMessage::ValidateClaimsToggled(validate_claims) => {
+                        self.validate_claims = validate_claims;
+                    }
+                    JwtDebuggerMessage::RememberTokenToggled(remember_token) => {
+                        self.remember_token = remember_token;
+                    }
+                    JwtDebuggerMessage::EncryptToggled(encrypt) => {
+                        self.encrypt = encrypt;
+                        if encrypt && self.encryption_header_content.text().trim().is_empty() {
+                            replace_text_in_field(
+                                &mut self.encryption_header_content,
+                                DEFAULT_ENCRYPTION_HEADER.to_string(),
+                            );
+                        }
+                        return self.encode_token();
+                    }
+                    JwtDebuggerMessage::ExpectedAudienceChanged(audience) => {
+                        self.claim_rules.audience = audience;
+                    }
+                    JwtDebuggerMessage::ExpectedIssuerChanged(issuer) => {
+                        self.claim_rules.issuer = issuer;
+                    }
+                    JwtDebuggerMessage::ExpectedSubjectChanged(subject) => {
+                        self.claim_rules.subject = subject;
+                    }
+                    JwtDebuggerMessage::LeewayChanged(input) => {
+                        // The last valid number stays in effect while the input is not one.
+                        if let Ok(leeway) = input.trim().parse() {
+                            self.claim_rules.leeway = leeway;
+                        }
+                        self.leeway = input;
+                    }
+                    JwtDebuggerMessage::RequiredClaimToggled(claim, required) => {
+                        self.claim_rules.required.retain(|other| *other != claim);
+                        if required {
+                            self.claim_rules.required.push(claim);
+                        }
+                    }
+                    JwtDebuggerMessage::Clear => {
+                        self.conversion.cancel();
+                        self.recording.clear();
+                        self.token_content = text_editor::Content::default();
+                        self.header_content = text_editor::Content::default();
+                        self.claims_content = text_editor::Content::default();
+                        self.claims = None;
+                        self.status = Status::Idle;
+                    }
+                    JwtDebuggerMessage::SignWith(algorithm) => {
+                        // Keep the other header fields, starting over when the header is invalid.
+                        let mut header =
+                            jwt::parse_header(&self.header_content.text()).unwrap_or_default();
+                        header.alg = algorithm;
+                        replace_text_in_field(
+                            &mut self.header_content,
+                            serde_json::to_string_pretty(&header).unwrap(),
+                        );
+                        return self.encode_token();
+                    }
+                    JwtDebuggerMessage::RsaKeySizeChanged(selection) => {
+                        self.rsa_key_size = selection;
+                    }
+                    JwtDebuggerMessage::EditorsSettled(edit) => {
+                        if self.debounce.is_settled(edit) {
+                            return self.perform_last_operation();
+                        }
+                    }
+                    JwtDebuggerMessage::TokenDecoded(id, result) => {
+                        if self.conversion.finish(id) {
+                            match result {
+                                Ok(decoded) => self.show_decoded(*decoded),
+                                Err(status) => {
+                                    self.recording.finish(false);
+                                    self.status = status;
+                                }
+                            }
+                        }
+                    }
+                    JwtDebuggerMessage::TokenEncoded(id, result) => {
+                        if self.conversion.finish(id) {
+                            match result {
+                                Ok(encoded) => self.show_encoded(*encoded),
+                                Err(status) => {
+                                    self.recording.finish(false);
+                                    self.status = status;
+                                }
+                            }
+                        }
+                    }
+                    JwtDebuggerMessage::CancelConversion => {
+                        self.conversion.cancel();
+                    }
+                    JwtDebuggerMessage::GenerateKey => {
+                        return self.generate_key();
+                    }
+                    JwtDebuggerMessage::KeyGenerated(id, result) => {
+                        if self.generation.finish(id) {
+                            match result {
+                                Ok(key_pair) => {
+                                    replace_text_in_field(
+                                        &mut self.private_key_content,
+                                        key_pair.private_key,
+                                    );
+                                    replace_text_in_field(
+                                        &mut self.public_key_content,
+                                        key_pair.public_key,
+                                    );
+                                    return self.encode_token();
+                                }
+                                Err(status) => self.status = status,
+                            }
+                        }
+                    }
+                    JwtDebuggerMessage::CancelGeneration => {
+                        self.generation.cancel();
+                    }
+                    JwtDebuggerMessage::CopyAsJwk => {
+                        // The public half of the private key serves when there is no public key.
+                        let result = Key::parse(&self.public_key_content.text())
+                            .or_else(|_| Key::parse(&self.private_key_content.text()))
+                            .map(|key| key.public_jwk(Some(self.algorithm)));
+                        match result {
+                            Ok(jwk) => {
+                                self.status = Status::Success(fl!(
+                                    "jwt-debugger",
+                                    "jwk-copied",
+                                    kid = jwk::key_id(&jwk)
+                                ));
+                                return clipboard::write(
+                                    serde_json::to_string_pretty(&jwk).unwrap(),
+                                );
+                            }
+                            Err(err) => self.status = err.into(),
+                        }
+                    }
                     JwtDebuggerMessage::CopyText(id) => {
                         let mut to_copy: String = String::new();
                         if id == Id::new(TOKEN_EDITOR_ID) {
@@ -482,259 +854,628 @@ impl UtilityPage for JwtDebuggerPage {
                             to_copy = self.header_content.text();
                         } else if id == Id::new(CLAIMS_EDITOR_ID) {
                             to_copy = self.claims_content.text();
+                        } else if id == Id::new(ENCRYPTION_HEADER_EDITOR_ID) {
+                            to_copy = self.encryption_header_content.text();
                         } else if id == Id::new(PUBLIC_KEY_EDITOR_ID) {
                             to_copy = self.public_key_content.text();
                         } else if id == Id::new(PRIVATE_KEY_EDITOR_ID) {
                             to_copy = self.private_key_content.text();
                         }
+                        // Copying the token or its claims keeps them in the history.
+                        if id == Id::new(TOKEN_EDITOR_ID) || id == Id::new(CLAIMS_EDITOR_ID) {
+                            return Task::batch([
+                                clipboard::write(to_copy),
+                                history_page::record(Page::JwtDebugger, self.history_entry()),
+                            ]);
+                        }
                         return clipboard::write(to_copy);
                     }
                     JwtDebuggerMessage::PasteText(id) => {
                         return clipboard::read().map(move |optional_data| match optional_data {
-                            Some(data) => cosmic::Action::App(Message::JwtDebuggerMessage(
+                            Some(data) => cosmic::Action::App(Message::from(
                                 JwtDebuggerMessage::ReplaceText(id.clone(), data),
                             )),
-                            None => cosmic::Action::App(Message::JwtDebuggerMessage(
-                                JwtDebuggerMessage::NoOp,
-                            )),
+                            None => cosmic::Action::App(Message::from(JwtDebuggerMessage::NoOp)),
                         });
                     }
                     JwtDebuggerMessage::ReplaceText(id, text) => {
-                        if id == Id::new(TOKEN_EDITOR_ID) {
-                            replace_text_in_field(&mut self.token_content, text);
-                            self.decode_token();
-                        } else if id == Id::new(HEADER_EDITOR_ID) {
-                            replace_text_in_field(&mut self.header_content, text);
-                            self.encode_token();
-                        } else if id == Id::new(CLAIMS_EDITOR_ID) {
-                            replace_text_in_field(&mut self.claims_content, text);
-                            self.encode_token();
-                        } else if id == Id::new(PUBLIC_KEY_EDITOR_ID) {
-                            replace_text_in_field(&mut self.public_key_content, text);
-                            self.decode_token();
-                        } else if id == Id::new(PRIVATE_KEY_EDITOR_ID) {
-                            replace_text_in_field(&mut self.private_key_content, text);
-                            self.encode_token();
-                        } else if id == Id::new(SYMMETRIC_KEY_TEXT_ID) {
-                            self.symmetric_key = text;
-                            self.perform_last_operation();
+                        return self.replace_text(&id, text);
+                    }
+                    JwtDebuggerMessage::OpenFile(id) => {
+                        return files::open(move |result| {
+                            Message::from(JwtDebuggerMessage::FileOpened(id, result))
+                        });
+                    }
+                    JwtDebuggerMessage::FileDropped(id, path) => {
+                        return files::read(path, move |result| {
+                            Message::from(JwtDebuggerMessage::FileOpened(id, result))
+                        });
+                    }
+                    JwtDebuggerMessage::FileOpened(id, result) => {
+                        let text = if id == Id::new(PUBLIC_KEY_EDITOR_ID)
+                            || id == Id::new(PRIVATE_KEY_EDITOR_ID)
+                        {
+                            result.map(|file| file.key_text())
+                        } else {
+                            result.and_then(|file| file.text())
+                        };
+                        match text {
+                            // Token files usually end with a newline, which is not part of the token.
+                            Ok(text) if id == Id::new(TOKEN_EDITOR_ID) => {
+                                return self.replace_text(&id, text.trim().to_string());
+                            }
+                            Ok(text) => return self.replace_text(&id, text),
+                            Err(err) => self.status = Status::error(fl!("open-failed"), err),
                         }
                     }
+                    JwtDebuggerMessage::SaveFile(id) => {
+                        if let Some(content) = self.editor_content(&id) {
+                            return files::save(
+                                file_name(&id).to_string(),
+                                content.text().into_bytes(),
+                                |result| Message::from(JwtDebuggerMessage::FileSaved(result)),
+                            );
+                        }
+                    }
+                    JwtDebuggerMessage::FileSaved(result) => {
+                        self.status = Status::saved(result);
+                    }
                     JwtDebuggerMessage::NoOp => {}
                 }
             }
-            _ => {
-                println!("Wrong message type");
-            }
+            None => unreachable!("a page only receives the messages its tool handles"),
+        }
+        Task::none()
+    }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.jwt_debugger;
+        self.algorithm = config.algorithm;
+        self.key_encoding = config.key_encoding.min(KEY_ENCODING.len() - 1);
+        self.rsa_key_size = config.rsa_key_size.min(KeyType::RSA.len() - 1);
+        self.validate_claims = config.validate_claims;
+        self.claim_rules = config.claim_rules.clone();
+        self.leeway = config.claim_rules.leeway.to_string();
+        self.remember_token = config.remember_token;
+        if !config.token.is_empty() {
+            self.token_content = text_editor::Content::with_text(&config.token);
+            return self.decode_token();
         }
         Task::none()
     }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::JwtDebugger(JwtDebuggerConfig {
+            algorithm: self.algorithm,
+            key_encoding: self.key_encoding,
+            rsa_key_size: self.rsa_key_size,
+            validate_claims: self.validate_claims,
+            claim_rules: self.claim_rules.clone(),
+            remember_token: self.remember_token,
+            token: self.token_content.text(),
+        })
+    }
+
+    fn paste_detected(
+        &mut self,
+        content: String,
+        _kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        self.token_content = text_editor::Content::with_text(content.trim());
+        self.decode_token()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        let message = match action {
+            EditAction::CopyOutput => JwtDebuggerMessage::CopyText(Id::new(CLAIMS_EDITOR_ID)),
+            EditAction::PasteInput => JwtDebuggerMessage::PasteText(Id::new(TOKEN_EDITOR_ID)),
+            EditAction::Clear => JwtDebuggerMessage::Clear,
+            EditAction::Convert | EditAction::Swap => return None,
+        };
+        Some(Message::from(message))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        SIGNING_ALGORITHMS
+            .into_iter()
+            .map(|algorithm| {
+                Command::new(
+                    fl!(
+                        "command-palette",
+                        "sign",
+                        algorithm = format!("{algorithm:?}")
+                    ),
+                    [Message::from(JwtDebuggerMessage::SignWith(algorithm))],
+                )
+            })
+            .chain([
+                Command::new(
+                    fl!("jwt-debugger", "generate-key"),
+                    [Message::from(JwtDebuggerMessage::GenerateKey)],
+                ),
+                Command::new(
+                    fl!("jwt-debugger", "copy-jwk"),
+                    [Message::from(JwtDebuggerMessage::CopyAsJwk)],
+                ),
+            ])
+            .collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // Keeps the times of the validation panel relative to now current.
+        let clock = if self.validate_claims && self.claims.is_some() {
+            time::every(Duration::from_secs(1)).map(|_| Message::Redraw)
+        } else {
+            Subscription::none()
+        };
+        Subscription::batch([
+            clock,
+            self.conversion.subscription(),
+            self.generation.subscription(),
+        ])
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        self.recording
+            .is_converted()
+            .then(|| (self.token_content.text(), self.claims_content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.token_content = text_editor::Content::with_text(&input);
+        self.decode_token()
+    }
 }
 
 impl JwtDebuggerPage {
-    fn decode_token(&mut self) {
+    fn editor_content(&self, id: &Id) -> Option<&text_editor::Content> {
+        if *id == Id::new(TOKEN_EDITOR_ID) {
+            Some(&self.token_content)
+        } else if *id == Id::new(HEADER_EDITOR_ID) {
+            Some(&self.header_content)
+        } else if *id == Id::new(CLAIMS_EDITOR_ID) {
+            Some(&self.claims_content)
+        } else if *id == Id::new(ENCRYPTION_HEADER_EDITOR_ID) {
+            Some(&self.encryption_header_content)
+        } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+            Some(&self.public_key_content)
+        } else if *id == Id::new(PRIVATE_KEY_EDITOR_ID) {
+            Some(&self.private_key_content)
+        } else {
+            None
+        }
+    }
+
+    fn replace_text(&mut self, id: &Id, text: String) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
+        if *id == Id::new(TOKEN_EDITOR_ID) {
+            replace_text_in_field(&mut self.token_content, text);
+            self.decode_token()
+        } else if *id == Id::new(HEADER_EDITOR_ID) {
+            replace_text_in_field(&mut self.header_content, text);
+            self.encode_token()
+        } else if *id == Id::new(CLAIMS_EDITOR_ID) {
+            replace_text_in_field(&mut self.claims_content, text);
+            self.encode_token()
+        } else if *id == Id::new(ENCRYPTION_HEADER_EDITOR_ID) {
+            replace_text_in_field(&mut self.encryption_header_content, text);
+            self.encode_token()
+        } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+            replace_text_in_field(&mut self.public_key_content, text);
+            self.decode_token()
+        } else if *id == Id::new(PRIVATE_KEY_EDITOR_ID) {
+            replace_text_in_field(&mut self.private_key_content, text);
+            self.encode_token()
+        } else if *id == Id::new(SYMMETRIC_KEY_TEXT_ID) {
+            self.symmetric_key = text;
+            self.perform_last_operation()
+        } else {
+            Task::none()
+        }
+    }
+
+    fn keys(&self) -> JwtKeys {
+        JwtKeys {
+            symmetric_key: self.symmetric_key.clone(),
+            key_encoding: KeyEncoding::ALL[self.key_encoding],
+            public_key: self.public_key_content.text(),
+            private_key: self.private_key_content.text(),
+        }
+    }
+
+    /// Decodes or encodes once the editors stop changing, dropping the result of the running
+    /// conversion so that it does not overwrite the edit.
+    fn edit(&mut self, operation: Operation) -> Task<cosmic::Action<Message>> {
+        self.conversion.cancel();
+        self.recording.clear();
+        self.last_operation = operation;
+        self.debounce
+            .edit(|edit| Message::from(JwtDebuggerMessage::EditorsSettled(edit)))
+    }
+
+    fn decode_token(&mut self) -> Task<cosmic::Action<Message>> {
         self.last_operation = Operation::Decode;
-        let input = self.token_content.text();
-        match jsonwebtoken::decode_header(input.as_bytes()) {
-            Ok(header) => {
-                replace_text_in_field(
-                    &mut self.header_content,
-                    serde_json::to_string_pretty(&header).unwrap(),
-                );
-                self.algorithm = header.alg;
-
-                match jsonwebtoken::dangerous::insecure_decode::<serde_json::Value>(
-                    input.as_bytes(),
-                ) {
-                    Ok(token_data) => {
-                        replace_text_in_field(
-                            &mut self.claims_content,
-                            serde_json::to_string_pretty(&token_data.claims).unwrap(),
-                        );
-                        let decoding_key;
-                        match algorithm_type(self.algorithm) {
-                            AlgorithmType::Symmetric => {
-                                if self.key_encoding == 0 {
-                                    decoding_key = jsonwebtoken::DecodingKey::from_secret(
-                                        self.symmetric_key.as_bytes(),
-                                    );
-                                } else {
-                                    if let Ok(key) = jsonwebtoken::DecodingKey::from_base64_secret(
-                                        &self.symmetric_key.as_str(),
-                                    ) {
-                                        decoding_key = key;
-                                    } else {
-                                        self.status = "invalid-key".to_string();
-                                        return;
-                                    }
-                                };
-                            }
-                            AlgorithmType::Asymmetric => {
-                                let decoding_key_result = match self.algorithm {
-                                    Algorithm::RS256
-                                    | Algorithm::RS384
-                                    | Algorithm::RS512
-                                    | Algorithm::PS256
-                                    | Algorithm::PS384
-                                    | Algorithm::PS512 => jsonwebtoken::DecodingKey::from_rsa_pem(
-                                        self.public_key_content.text().as_bytes(),
-                                    ),
-                                    Algorithm::ES256 | Algorithm::ES384 => {
-                                        jsonwebtoken::DecodingKey::from_ec_pem(
-                                            self.public_key_content.text().as_bytes(),
-                                        )
-                                    }
-                                    Algorithm::EdDSA => jsonwebtoken::DecodingKey::from_ed_pem(
-                                        self.public_key_content.text().as_bytes(),
-                                    ),
-                                    _ => Err(jsonwebtoken::errors::new_error(
-                                        jsonwebtoken::errors::ErrorKind::InvalidAlgorithm,
-                                    )),
-                                };
-                                if let Ok(key) = decoding_key_result {
-                                    decoding_key = key;
-                                } else {
-                                    self.status = "invalid-key".to_string();
-                                    return;
-                                }
-                            }
-                        };
+        let token = self.token_content.text();
+        let keys = self.keys();
+        self.conversion.start(
+            move || Box::new(decode(&token, &keys)),
+            |id, result| Message::from(JwtDebuggerMessage::TokenDecoded(id, result)),
+        )
+    }
 
-                        let mut validation = jsonwebtoken::Validation::new(self.algorithm);
-                        validation.validate_aud = false;
-                        validation.validate_exp = false;
-                        validation.set_required_spec_claims::<&str>(&[]);
-                        match jsonwebtoken::decode::<serde_json::Value>(
-                            input.as_bytes(),
-                            &decoding_key,
-                            &validation,
-                        ) {
-                            Ok(_) => self.status = "ok".to_string(),
-                            Err(_) => self.status = "invalid-signature".to_string(),
-                        };
-                    }
-                    Err(_) => {
-                        self.status = "invalid-token".to_string();
-                    }
-                };
-            }
-            Err(_) => {
-                self.status = "invalid-token".to_string();
-            }
-        };
+    fn show_decoded(&mut self, decoded: DecodedToken) {
+        self.encrypt = decoded.encrypted;
+        if let Some(encryption_header) = decoded.encryption_header {
+            replace_text_in_field(&mut self.encryption_header_content, encryption_header);
+            self.key_management = decoded.key_management;
+        }
+        if let Some(header) = decoded.header {
+            replace_text_in_field(&mut self.header_content, header);
+        }
+        if let Some(algorithm) = decoded.algorithm {
+            self.algorithm = algorithm;
+        }
+        if let Some(claims_text) = decoded.claims_text {
+            replace_text_in_field(&mut self.claims_content, claims_text);
+        }
+        self.claims = decoded.claims;
+        self.recording
+            .finish(matches!(decoded.status, Status::Success(_)));
+        self.status = decoded.status;
     }
 
-    fn encode_token(&mut self) {
+    fn encode_token(&mut self) -> Task<cosmic::Action<Message>> {
         self.last_operation = Operation::Encode;
-        let header_input = self.header_content.text();
-        match serde_json::from_str::<serde_json::Value>(header_input.as_str()) {
-            Ok(header_value) => {
-                if header_value.is_object() {
-                    let header_object = header_value.as_object().unwrap();
-                    if header_object.contains_key("alg") {
-                        match serde_json::from_value::<jsonwebtoken::Header>(header_value) {
-                            Ok(header) => {
-                                self.algorithm = header.alg;
-                                let claims_input = self.claims_content.text();
-                                match serde_json::from_str::<serde_json::Value>(
-                                    claims_input.as_str(),
-                                ) {
-                                    Ok(claims_value) => {
-                                        if claims_value.is_object() {
-                                            let encoding_key;
-                                            match algorithm_type(self.algorithm) {
-                                                AlgorithmType::Symmetric => {
-                                                    if self.key_encoding == 0 {
-                                                        encoding_key =
-                                                            jsonwebtoken::EncodingKey::from_secret(
-                                                                self.symmetric_key.as_bytes(),
-                                                            );
-                                                    } else {
-                                                        if let Ok(key) =
-                                                    jsonwebtoken::EncodingKey::from_base64_secret(
-                                                        &self.symmetric_key.as_str(),
-                                                    )
-                                                {
-                                                    encoding_key = key;
-                                                } else {
-                                                    self.status = "invalid-key".to_string();
-                                                    return;
-                                                }
-                                                    }
-                                                }
-                                                AlgorithmType::Asymmetric => {
-                                                    let encoding_key_result = match self.algorithm {
-                                                        Algorithm::RS256
-                                                        | Algorithm::RS384
-                                                        | Algorithm::RS512
-                                                        | Algorithm::PS256
-                                                        | Algorithm::PS384
-                                                        | Algorithm::PS512 => jsonwebtoken::EncodingKey::from_rsa_pem(
-                                                            self.private_key_content.text().as_bytes(),
-                                                        ),
-                                                        Algorithm::ES256 | Algorithm::ES384 => {
-                                                            jsonwebtoken::EncodingKey::from_ec_pem(
-                                                                self.private_key_content.text().as_bytes(),
-                                                            )
-                                                        }
-                                                        Algorithm::EdDSA => jsonwebtoken::EncodingKey::from_ed_pem(
-                                                            self.private_key_content.text().as_bytes(),
-                                                        ),
-                                                        _ => Err(jsonwebtoken::errors::new_error(
-                                                            jsonwebtoken::errors::ErrorKind::InvalidAlgorithm,
-                                                        )),
-                                                    };
-                                                    if let Ok(key) = encoding_key_result {
-                                                        encoding_key = key;
-                                                    } else {
-                                                        self.status = "invalid-key".to_string();
-                                                        return;
-                                                    }
-                                                }
-                                            }
-                                            match jsonwebtoken::encode(
-                                                &header,
-                                                &claims_value,
-                                                &encoding_key,
-                                            ) {
-                                                Ok(token) => {
-                                                    replace_text_in_field(
-                                                        &mut self.token_content,
-                                                        token,
-                                                    );
-                                                    self.status = "ok".to_string();
-                                                }
-                                                Err(err) => {
-                                                    println!("{:?}", err);
-                                                    self.status = "encoding-failed".to_string();
-                                                }
-                                            }
-                                        } else {
-                                            self.status = "invalid-claims".to_string();
-                                        }
-                                    }
-                                    Err(_) => self.status = "invalid-claims".to_string(),
-                                }
-                            }
-                            Err(_) => {
-                                self.status = "invalid-header".to_string();
-                            }
-                        }
-                    } else {
-                        self.status = "invalid-header".to_string();
-                    }
-                } else {
-                    self.status = "invalid-header".to_string();
-                }
+        let encoding = Encoding {
+            encrypt: self.encrypt,
+            encryption_header: self.encryption_header_content.text(),
+            header: self.header_content.text(),
+            claims: self.claims_content.text(),
+            keys: self.keys(),
+        };
+        self.conversion.start(
+            move || Box::new(encoding.encode()),
+            |id, result| Message::from(JwtDebuggerMessage::TokenEncoded(id, result)),
+        )
+    }
+
+    fn show_encoded(&mut self, encoded: EncodedToken) {
+        if let Some(algorithm) = encoded.algorithm {
+            self.algorithm = algorithm;
+        }
+        if let Some(key_management) = encoded.key_management {
+            self.key_management = Some(key_management);
+        }
+        match encoded.token {
+            Some(token) => {
+                replace_text_in_field(&mut self.token_content, token);
+                self.claims = serde_json::from_str(&self.claims_content.text()).ok();
+                self.recording.finish(true);
+                self.status = Status::Success(fl!("jwt-debugger", "ok"));
+            }
+            None => {
+                self.claims = None;
+                self.recording.finish(false);
+                self.status = encoded.status;
             }
-            Err(_) => self.status = "invalid-header".to_string(),
         }
     }
 
-    fn perform_last_operation(&mut self) {
+    /// The values expected of the claims and the checks of the current claims against them.
+    fn validation_view(&self) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+        let space_xxs = cosmic::theme::spacing().space_xxs;
+
+        let inputs = row![
+            expected_input(
+                fl!("jwt-debugger", "expected-audience"),
+                &self.claim_rules.audience,
+                JwtDebuggerMessage::ExpectedAudienceChanged,
+            ),
+            expected_input(
+                fl!("jwt-debugger", "expected-issuer"),
+                &self.claim_rules.issuer,
+                JwtDebuggerMessage::ExpectedIssuerChanged,
+            ),
+            expected_input(
+                fl!("jwt-debugger", "expected-subject"),
+                &self.claim_rules.subject,
+                JwtDebuggerMessage::ExpectedSubjectChanged,
+            ),
+            expected_input(
+                fl!("jwt-debugger", "clock-skew"),
+                &self.leeway,
+                JwtDebuggerMessage::LeewayChanged,
+            )
+            .width(160),
+        ]
+        .spacing(space_s);
+
+        let mut column = widget::column::with_capacity(Claim::ALL.len() + 2)
+            .push(widget::text::title4(fl!("jwt-debugger", "validation")))
+            .push(inputs)
+            .spacing(space_xxs);
+        let Some(claims) = &self.claims else {
+            return column.into();
+        };
+
+        // Checked on every redraw, which the page's subscription triggers every second, so that
+        // the relative times stay current.
+        let checks = claims::validate(claims, &self.claim_rules, chrono::Utc::now().timestamp());
+        let offset_seconds = chrono::Local::now().offset().local_minus_utc();
+        for check in &checks {
+            let icon_name = match check.verdict {
+                Verdict::Pass => "emblem-ok-symbolic",
+                Verdict::Fail => "dialog-error-symbolic",
+                Verdict::Skipped => "dialog-information-symbolic",
+            };
+            let claim = check.claim;
+            column = column.push(
+                row![
+                    widget::icon::from_name(icon_name).size(16).icon(),
+                    widget::text::heading(format!(
+                        "{} ({})",
+                        LANGUAGE_LOADER.get_attr("jwt-debugger", claim.name()),
+                        claim.name()
+                    ))
+                    .width(Length::Fixed(200.0)),
+                    widget::text::body(describe(check, &self.claim_rules, offset_seconds))
+                        .width(Length::Fill),
+                    widget::checkbox(check.required)
+                        .label(fl!("jwt-debugger", "required"))
+                        .on_toggle(move |required| {
+                            Message::from(JwtDebuggerMessage::RequiredClaimToggled(claim, required))
+                        }),
+                ]
+                .spacing(space_s)
+                .align_y(Alignment::Center),
+            );
+        }
+        column
+            .push(widget::text::heading(if claims::is_valid(&checks) {
+                fl!("jwt-debugger", "claims-valid")
+            } else {
+                fl!("jwt-debugger", "claims-invalid")
+            }))
+            .into()
+    }
+
+    /// Fills in a random secret for the HMAC algorithms, or starts generating a key pair for the
+    /// others.
+    fn generate_key(&mut self) -> Task<cosmic::Action<Message>> {
+        let algorithm = self.algorithm;
+        let Some(key_type) = KeyType::for_algorithm(algorithm, KeyType::RSA[self.rsa_key_size])
+        else {
+            self.key_encoding = KeyEncoding::ALL
+                .iter()
+                .position(|key_encoding| *key_encoding == KeyEncoding::Base64)
+                .unwrap();
+            self.symmetric_key = key::generate_secret(algorithm);
+            return self.perform_last_operation();
+        };
+        self.generation.start(
+            move || key::generate_pair(key_type).map_err(Status::from),
+            |id, result| Message::from(JwtDebuggerMessage::KeyGenerated(id, result.flatten())),
+        )
+    }
+
+    fn perform_last_operation(&mut self) -> Task<cosmic::Action<Message>> {
         match self.last_operation {
             Operation::Decode => self.decode_token(),
             Operation::Encode => self.encode_token(),
+        }
+    }
+}
+
+/// Decodes a token, decrypting it first when it is encrypted.
+fn decode(token: &str, keys: &JwtKeys) -> DecodedToken {
+    if !jwe::is_jwe(token) {
+        return decode_signed(token, keys, DecodedToken::default());
+    }
+
+    let decoded = DecodedToken {
+        encrypted: true,
+        ..DecodedToken::default()
+    };
+    let header = match jwe::decode_header(token) {
+        Ok(header) => header,
+        Err(err) => {
+            return DecodedToken {
+                status: err.into(),
+                ..decoded
+            };
+        }
+    };
+    let decoded = DecodedToken {
+        encryption_header: Some(serde_json::to_string_pretty(&header).unwrap()),
+        key_management: jwe::algorithms(&header).ok().map(|(alg, _)| alg),
+        ..decoded
+    };
+    let payload = match jwe::decrypt(token, keys) {
+        Ok(payload) => String::from_utf8_lossy(&payload).into_owned(),
+        Err(err) => {
+            return DecodedToken {
+                status: err.into(),
+                ..decoded
+            };
+        }
+    };
+    if jwe::is_nested(&header) {
+        return decode_signed(&payload, keys, decoded);
+    }
+
+    // The payload has no header of its own and may not be JSON.
+    let claims = serde_json::from_str::<serde_json::Value>(&payload).ok();
+    DecodedToken {
+        header: Some(String::new()),
+        claims_text: Some(claims.as_ref().map_or(payload, |claims| {
+            serde_json::to_string_pretty(claims).unwrap()
+        })),
+        claims,
+        status: Status::Success(fl!("jwt-debugger", "ok")),
+        ..decoded
+    }
+}
+
+/// Decodes a signed token, looking for the key its signature verifies with.
+fn decode_signed(token: &str, keys: &JwtKeys, decoded: DecodedToken) -> DecodedToken {
+    let header = match jwt::decode_header(token) {
+        Ok(header) => header,
+        Err(err) => {
+            return DecodedToken {
+                status: err.into(),
+                ..decoded
+            };
+        }
+    };
+    let decoded = DecodedToken {
+        header: Some(serde_json::to_string_pretty(&header).unwrap()),
+        algorithm: Some(header.alg),
+        ..decoded
+    };
+    let claims = match jwt::decode_claims(token) {
+        Ok(claims) => claims,
+        Err(err) => {
+            return DecodedToken {
+                status: err.into(),
+                ..decoded
+            };
+        }
+    };
+    let status = match jwt::find_verifying_key(token, header.alg, keys) {
+        Ok(Some(jwk)) => Status::Success(fl!(
+            "jwt-debugger",
+            "verified-with-key",
+            kid = jwk::key_id(&jwk)
+        )),
+        Ok(None) => Status::Success(fl!("jwt-debugger", "ok")),
+        Err(err) => err.into(),
+    };
+    DecodedToken {
+        claims_text: Some(serde_json::to_string_pretty(&claims).unwrap()),
+        claims: Some(claims),
+        status,
+        ..decoded
+    }
+}
+
+impl Encoding {
+    fn encode(&self) -> EncodedToken {
+        let mut encoded = EncodedToken::default();
+        let token = if self.encrypt {
+            self.encrypt_token(&mut encoded)
+        } else {
+            self.sign_token(&mut encoded)
+        };
+        match token {
+            Ok(token) => encoded.token = Some(token),
+            Err(err) => encoded.status = err.into(),
+        }
+        encoded
+    }
+
+    fn sign_token(&self, encoded: &mut EncodedToken) -> Result<String, JwtError> {
+        let header = jwt::parse_header(&self.header)?;
+        encoded.algorithm = Some(header.alg);
+        jwt::encode_token(&header, &self.claims, &self.keys)
+    }
+
+    /// Encrypts the claims, or the token signed with them when the encryption header nests it.
+    fn encrypt_token(&self, encoded: &mut EncodedToken) -> Result<String, JwtError> {
+        let (header, key_management, _) = jwe::parse_header(&self.encryption_header)?;
+        encoded.key_management = Some(key_management);
+        let payload = if jwe::is_nested(&header) {
+            self.sign_token(encoded)?
+        } else {
+            self.claims.clone()
         };
+        jwe::encrypt(&self.encryption_header, payload.as_bytes(), &self.keys)
+    }
+}
+
+/// An input of a value the claims are checked against.
+fn expected_input<'a>(
+    label: String,
+    value: &'a str,
+    on_input: fn(String) -> JwtDebuggerMessage,
+) -> TextInput<'a, Message> {
+    widget::text_input::text_input("", value)
+        .style(text_input_style())
+        .helper_text(label)
+        .on_input(move |input| Message::from(on_input(input)))
+}
+
+/// A length of time by its two largest units, such as "3h 12m".
+fn format_duration(seconds: u64) -> String {
+    unix_time::duration_parts(seconds)
+        .into_iter()
+        .map(|(count, unit)| match unit {
+            DurationUnit::Days => fl!("jwt-debugger", "days", count = count),
+            DurationUnit::Hours => fl!("jwt-debugger", "hours", count = count),
+            DurationUnit::Minutes => fl!("jwt-debugger", "minutes", count = count),
+            DurationUnit::Seconds => fl!("jwt-debugger", "seconds", count = count),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// What was found for a claim, with times relative to now and in the timezone `offset_seconds`
+/// east of UTC.
+fn describe(check: &ClaimCheck, rules: &ClaimRules, offset_seconds: i32) -> String {
+    match &check.finding {
+        Finding::Missing => fl!("jwt-debugger", "claim-missing"),
+        Finding::Malformed => fl!("jwt-debugger", "claim-malformed"),
+        Finding::Time {
+            timestamp,
+            from_now,
+        } => {
+            let duration = format_duration(from_now.unsigned_abs());
+            let date =
+                unix_time::format_timestamp(*timestamp, TimestampUnit::Seconds, offset_seconds)
+                    .map(|formatted| formatted.iso_8601)
+                    .unwrap_or_default();
+            match (check.claim, *from_now > 0) {
+                (Claim::Exp, true) => fl!(
+                    "jwt-debugger",
+                    "expires-in",
+                    duration = duration,
+                    date = date
+                ),
+                (Claim::Exp, false) => {
+                    fl!("jwt-debugger", "expired", duration = duration, date = date)
+                }
+                (Claim::Nbf, true) => fl!(
+                    "jwt-debugger",
+                    "not-yet-valid",
+                    duration = duration,
+                    date = date
+                ),
+                (Claim::Nbf, false) => fl!(
+                    "jwt-debugger",
+                    "valid-since",
+                    duration = duration,
+                    date = date
+                ),
+                (_, true) => fl!(
+                    "jwt-debugger",
+                    "issued-in-future",
+                    duration = duration,
+                    date = date
+                ),
+                (_, false) => fl!(
+                    "jwt-debugger",
+                    "issued-ago",
+                    duration = duration,
+                    date = date
+                ),
+            }
+        }
+        Finding::Values(values) if check.verdict == Verdict::Fail => fl!(
+            "jwt-debugger",
+            "unexpected-value",
+            values = values.join(", "),
+            expected = rules.expected(check.claim)
+        ),
+        Finding::Values(values) => values.join(", "),
     }
 }
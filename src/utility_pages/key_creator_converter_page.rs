@@ -0,0 +1,762 @@
+use {
+    crate::{
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, KeyCreatorConverterConfig, PageConfig},
+        fl,
+        utility_pages::{
+            EditAction, UtilityPage,
+            conversion::{Conversion, Debounce},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
+    },
+    cosmic::{
+        self, Application, Element, Task, iced,
+        iced::{
+            Alignment, Length, Padding, Subscription, clipboard,
+            widget::{column, row},
+        },
+        widget::{
+            self, Id,
+            text_editor::{self, TextEditor},
+        },
+    },
+    dev_toolbox_core::{
+        base64,
+        key::{self, Fingerprints, Key, KeyFormat, KeyPair, KeyType},
+    },
+    std::{path::PathBuf, sync::Arc},
+};
+
+const PRIVATE_KEY_EDITOR_ID: &str = "private-key-editor";
+const PUBLIC_KEY_EDITOR_ID: &str = "public-key-editor";
+const OUTPUT_EDITOR_ID: &str = "output-editor";
+
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::KeyCreatorConverter,
+    id: "key-creator-converter",
+    icon: ToolIcon::Named("dialog-password-symbolic"),
+    new_page: || Box::new(KeyCreatorConverterPage::default()),
+    service: None,
+    takes: |_| false,
+};
+
+#[derive(Debug, Clone)]
+pub enum KeyCreatorConverterMessage {
+    PrivateKeyEditorAction(text_editor::Action),
+    PublicKeyEditorAction(text_editor::Action),
+    OutputEditorAction(text_editor::Action),
+    KeysSettled(u64),
+    Converted(u64, Box<ReadKey>),
+    CancelConversion,
+    KeyTypeChanged(usize),
+    OutputFormatChanged(KeyFormat),
+    Generate,
+    Generated(u64, Result<KeyPair, Status>),
+    CancelGeneration,
+    DerivePublicKey,
+    CopyText(Id),
+    PasteText(Id),
+    ReplaceText(Id, String),
+    OpenFile(Id),
+    FileDropped(Id, PathBuf),
+    FileOpened(Id, Result<OpenedFile, String>),
+    SaveFile(Id),
+    FileSaved(Result<(), String>),
+    NoOp,
+}
+
+impl From<KeyCreatorConverterMessage> for Message {
+    fn from(message: KeyCreatorConverterMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
+/// The key read from the editors, written in the output format.
+#[derive(Debug, Clone)]
+pub struct ReadKey {
+    key: Option<Key>,
+    fingerprints: Option<Fingerprints>,
+    output: String,
+    status: Status,
+}
+
+pub(crate) struct KeyCreatorConverterPage {
+    private_key_content: text_editor::Content,
+    public_key_content: text_editor::Content,
+    output_content: text_editor::Content,
+    selected_key_type: usize,
+    output_format: KeyFormat,
+    /// The key being converted: the private key when it can be read, else the public key.
+    key: Option<Key>,
+    fingerprints: Option<Fingerprints>,
+    debounce: Debounce,
+    conversion: Conversion,
+    /// The larger RSA keys take a few seconds to generate.
+    generation: Conversion,
+    status: Status,
+}
+
+impl Default for KeyCreatorConverterPage {
+    fn default() -> Self {
+        Self {
+            private_key_content: text_editor::Content::default(),
+            public_key_content: text_editor::Content::default(),
+            output_content: text_editor::Content::default(),
+            selected_key_type: 0,
+            output_format: KeyFormat::default(),
+            key: None,
+            fingerprints: None,
+            debounce: Debounce::default(),
+            conversion: Conversion::default(),
+            generation: Conversion::default(),
+            status: Status::default(),
+        }
+    }
+}
+
+fn replace_text_in_field(content: &mut text_editor::Content, text: String) {
+    content.perform(text_editor::Action::SelectAll);
+    content.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+        Arc::new(text),
+    )));
+}
+
+/// The label of a format in the output dropdown, telling the private and public formats apart.
+fn format_label(format: KeyFormat) -> String {
+    if format.is_private() {
+        fl!(
+            "key-creator-converter",
+            "private-format",
+            format = format.name()
+        )
+    } else {
+        fl!(
+            "key-creator-converter",
+            "public-format",
+            format = format.name()
+        )
+    }
+}
+
+/// The name a key in `format` is saved as, and whether it is Base64 DER saved as binary.
+fn output_file(format: KeyFormat) -> (&'static str, bool) {
+    match format {
+        KeyFormat::PrivatePkcs8Pem | KeyFormat::PrivatePkcs1Pem | KeyFormat::PrivateSec1Pem => {
+            ("private-key.pem", false)
+        }
+        KeyFormat::PrivatePkcs8Der | KeyFormat::PrivatePkcs1Der | KeyFormat::PrivateSec1Der => {
+            ("private-key.der", true)
+        }
+        KeyFormat::PrivateOpenSsh => ("id_key", false),
+        KeyFormat::PrivateJwk => ("private-key.json", false),
+        KeyFormat::PublicSpkiPem | KeyFormat::PublicPkcs1Pem => ("public-key.pem", false),
+        KeyFormat::PublicSpkiDer | KeyFormat::PublicPkcs1Der => ("public-key.der", true),
+        KeyFormat::PublicOpenSsh => ("id_key.pub", false),
+        KeyFormat::PublicJwk => ("public-key.json", false),
+    }
+}
+
+/// The formats offered for the output, every format until a key has been read.
+fn output_formats(key: Option<&Key>) -> Vec<KeyFormat> {
+    match key {
+        Some(key) => key.formats(),
+        None => KeyFormat::ALL.to_vec(),
+    }
+}
+
+/// The selected output format, or the first format of the key when it cannot be written in
+/// the selected one, such as a private format for a public key.
+fn effective_output_format(key: Option<&Key>, selected: KeyFormat) -> KeyFormat {
+    let formats = output_formats(key);
+    if formats.contains(&selected) {
+        selected
+    } else {
+        formats.first().copied().unwrap_or_default()
+    }
+}
+
+/// Reads the keys of both editors, checks that they form a pair and writes the key in the
+/// output format.
+fn read_keys(private_key: &str, public_key: &str, output_format: KeyFormat) -> ReadKey {
+    let read = |text: &str| (!text.trim().is_empty()).then(|| Key::parse(text));
+    let mut key = None;
+    let mut status = match (read(private_key), read(public_key)) {
+        (None, None) => Status::Idle,
+        (Some(Err(err)), _) | (_, Some(Err(err))) => err.into(),
+        (Some(Ok(private_key)), Some(Ok(public_key))) => {
+            let matches = private_key.matches(&public_key);
+            key = Some(private_key);
+            if matches {
+                Status::Success(fl!("key-creator-converter", "keys-match"))
+            } else {
+                Status::Error {
+                    title: fl!("key-creator-converter", "keys-mismatch"),
+                    details: None,
+                }
+            }
+        }
+        (Some(Ok(read_key)), None) | (None, Some(Ok(read_key))) => {
+            let status = Status::Success(fl!(
+                "key-creator-converter",
+                "read-key",
+                key = read_key.description(),
+                format = format_label(read_key.format())
+            ));
+            key = Some(read_key);
+            status
+        }
+    };
+
+    let fingerprints = key.as_ref().and_then(|key| key.fingerprints().ok());
+    let output = match &key {
+        Some(key) => key.encode(effective_output_format(Some(key), output_format)),
+        None => Ok(String::new()),
+    };
+    let output = output.unwrap_or_else(|err| {
+        status = err.into();
+        String::new()
+    });
+    ReadKey {
+        key,
+        fingerprints,
+        output,
+        status,
+    }
+}
+
+fn icon_button<'a>(
+    icon: &'static str,
+    tooltip: String,
+    message: KeyCreatorConverterMessage,
+) -> Element<'a, Message> {
+    widget::tooltip(
+        widget::button::icon(widget::icon::from_name(icon)).on_press(Message::from(message)),
+        widget::text(tooltip),
+        widget::tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+fn key_header<'a>(title: String, editor_id: &'static str) -> Element<'a, Message> {
+    row![
+        widget::text::title4(title)
+            .width(Length::Fill)
+            .align_x(Alignment::Start),
+        icon_button(
+            "document-save-as-symbolic",
+            fl!("save-as-file"),
+            KeyCreatorConverterMessage::SaveFile(Id::new(editor_id)),
+        ),
+        icon_button(
+            "edit-copy-symbolic",
+            fl!("copy"),
+            KeyCreatorConverterMessage::CopyText(Id::new(editor_id)),
+        ),
+        icon_button(
+            "document-open-symbolic",
+            fl!("open-file"),
+            KeyCreatorConverterMessage::OpenFile(Id::new(editor_id)),
+        ),
+        icon_button(
+            "edit-paste-symbolic",
+            fl!("paste"),
+            KeyCreatorConverterMessage::PasteText(Id::new(editor_id)),
+        ),
+    ]
+    .align_y(Alignment::Center)
+    .into()
+}
+
+fn key_editor<'a>(
+    content: &'a text_editor::Content,
+    editor_id: &'static str,
+    on_action: fn(text_editor::Action) -> KeyCreatorConverterMessage,
+) -> Element<'a, Message> {
+    let editor = TextEditor::new(content)
+        .padding(Padding::new(12.0))
+        .height(Length::Fill)
+        .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+            text_editor_class,
+        )))
+        .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+        .on_action(move |action| Message::from(on_action(action)))
+        .key_binding(editor_key_binding);
+    files::drop_target(
+        editor.into(),
+        move |path| {
+            Message::from(KeyCreatorConverterMessage::FileDropped(
+                Id::new(editor_id),
+                path,
+            ))
+        },
+        Message::from(KeyCreatorConverterMessage::NoOp),
+    )
+}
+
+impl UtilityPage for KeyCreatorConverterPage {
+    fn get_utility_page(&self) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let header = widget::row::with_capacity(2)
+            .push(widget::text::title2(fl!("key-creator-converter")))
+            .align_y(Alignment::End)
+            .spacing(space_s);
+
+        let options_header: Element<'_, Message> = widget::text::title4(fl!("options"))
+            .width(Length::Fill)
+            .align_x(Alignment::Start)
+            .into();
+
+        let key_type_option: Element<'_, Message> = widget::settings::item(
+            fl!("key-creator-converter", "key-type"),
+            row![
+                widget::dropdown(
+                    KeyType::ALL.map(KeyType::name).to_vec(),
+                    Some(self.selected_key_type),
+                    |selection| {
+                        Message::from(KeyCreatorConverterMessage::KeyTypeChanged(selection))
+                    },
+                ),
+                widget::button::text(fl!("key-creator-converter", "generate"))
+                    .on_press(Message::from(KeyCreatorConverterMessage::Generate)),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(space_s),
+        )
+        .into();
+
+        let private_key_editor = key_editor(
+            &self.private_key_content,
+            PRIVATE_KEY_EDITOR_ID,
+            KeyCreatorConverterMessage::PrivateKeyEditorAction,
+        );
+        let public_key_editor = key_editor(
+            &self.public_key_content,
+            PUBLIC_KEY_EDITOR_ID,
+            KeyCreatorConverterMessage::PublicKeyEditorAction,
+        );
+
+        let derive_button: Element<'_, Message> =
+            widget::button::text(fl!("key-creator-converter", "derive-public-key"))
+                .on_press_maybe(
+                    self.key
+                        .as_ref()
+                        .filter(|key| key.is_private())
+                        .map(|_| Message::from(KeyCreatorConverterMessage::DerivePublicKey)),
+                )
+                .into();
+
+        let formats = self.output_formats();
+        let selected_format = formats
+            .iter()
+            .position(|format| *format == self.effective_output_format());
+        let output_header: Element<'_, Message> = row![
+            widget::text::title4(fl!("output"))
+                .width(Length::Fill)
+                .align_x(Alignment::Start),
+            widget::dropdown(
+                formats
+                    .iter()
+                    .copied()
+                    .map(format_label)
+                    .collect::<Vec<_>>(),
+                selected_format,
+                move |selection| {
+                    Message::from(KeyCreatorConverterMessage::OutputFormatChanged(
+                        formats[selection],
+                    ))
+                },
+            ),
+            icon_button(
+                "document-save-as-symbolic",
+                fl!("save-as-file"),
+                KeyCreatorConverterMessage::SaveFile(Id::new(OUTPUT_EDITOR_ID)),
+            ),
+            icon_button(
+                "edit-copy-symbolic",
+                fl!("copy"),
+                KeyCreatorConverterMessage::CopyText(Id::new(OUTPUT_EDITOR_ID)),
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(space_s)
+        .into();
+
+        let output_editor: Element<'_, Message> = TextEditor::new(&self.output_content)
+            .padding(Padding::new(12.0))
+            .height(Length::Fill)
+            .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+                text_editor_class,
+            )))
+            .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+            .on_action(|action| {
+                Message::from(KeyCreatorConverterMessage::OutputEditorAction(action))
+            })
+            .into();
+
+        let mut page = column![
+            header,
+            options_header,
+            key_type_option,
+            row![
+                column![
+                    key_header(
+                        fl!("key-creator-converter", "private-key"),
+                        PRIVATE_KEY_EDITOR_ID
+                    ),
+                    private_key_editor,
+                    derive_button,
+                ]
+                .spacing(space_s),
+                column![
+                    key_header(
+                        fl!("key-creator-converter", "public-key"),
+                        PUBLIC_KEY_EDITOR_ID
+                    ),
+                    public_key_editor
+                ]
+                .spacing(space_s),
+            ]
+            .height(Length::Fill)
+            .spacing(space_s),
+            output_header,
+            output_editor,
+        ]
+        .spacing(space_s)
+        .height(Length::Fill);
+        if let (Some(key), Some(fingerprints)) = (&self.key, &self.fingerprints) {
+            let details = [
+                (fl!("key-creator-converter", "key"), key.description()),
+                (
+                    fl!("key-creator-converter", "format"),
+                    format_label(key.format()),
+                ),
+                (
+                    fl!("key-creator-converter", "sha256-fingerprint"),
+                    fingerprints.sha256.clone(),
+                ),
+                (
+                    fl!("key-creator-converter", "openssh-fingerprint"),
+                    fingerprints.openssh.clone(),
+                ),
+                (
+                    fl!("key-creator-converter", "jwk-thumbprint"),
+                    fingerprints.jwk_thumbprint.clone(),
+                ),
+            ];
+            page = page.push(widget::text::title4(fl!(
+                "key-creator-converter",
+                "details"
+            )));
+            for (label, value) in details {
+                page = page.push(widget::settings::item(label, widget::text::body(value)));
+            }
+        }
+        if let Some(progress) = self
+            .generation
+            .view(Message::from(KeyCreatorConverterMessage::CancelGeneration))
+        {
+            page = page.push(progress);
+        }
+        if let Some(progress) = self
+            .conversion
+            .view(Message::from(KeyCreatorConverterMessage::CancelConversion))
+        {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
+    }
+
+    fn handle_message(
+        &mut self,
+        message: Message,
+    ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
+        match message.tool_message::<KeyCreatorConverterMessage>() {
+            Some(key_creator_converter_message) => match key_creator_converter_message {
+                KeyCreatorConverterMessage::PrivateKeyEditorAction(action) => {
+                    let is_edit = action.is_edit();
+                    self.private_key_content.perform(action);
+                    if is_edit {
+                        return self.debounce.edit(|edit| {
+                            Message::from(KeyCreatorConverterMessage::KeysSettled(edit))
+                        });
+                    }
+                }
+                KeyCreatorConverterMessage::PublicKeyEditorAction(action) => {
+                    let is_edit = action.is_edit();
+                    self.public_key_content.perform(action);
+                    if is_edit {
+                        return self.debounce.edit(|edit| {
+                            Message::from(KeyCreatorConverterMessage::KeysSettled(edit))
+                        });
+                    }
+                }
+                KeyCreatorConverterMessage::KeysSettled(edit) => {
+                    if self.debounce.is_settled(edit) {
+                        return self.convert();
+                    }
+                }
+                KeyCreatorConverterMessage::Converted(id, read_key) => {
+                    if self.conversion.finish(id) {
+                        let ReadKey {
+                            key,
+                            fingerprints,
+                            output,
+                            status,
+                        } = *read_key;
+                        self.key = key;
+                        self.fingerprints = fingerprints;
+                        replace_text_in_field(&mut self.output_content, output);
+                        self.status = status;
+                    }
+                }
+                KeyCreatorConverterMessage::CancelConversion => {
+                    self.conversion.cancel();
+                }
+                KeyCreatorConverterMessage::OutputEditorAction(action) => {
+                    if !action.is_edit() {
+                        self.output_content.perform(action);
+                    }
+                }
+                KeyCreatorConverterMessage::KeyTypeChanged(selection) => {
+                    self.selected_key_type = selection;
+                }
+                KeyCreatorConverterMessage::OutputFormatChanged(format) => {
+                    self.output_format = format;
+                    return self.convert();
+                }
+                KeyCreatorConverterMessage::Generate => {
+                    return self.generate_key();
+                }
+                KeyCreatorConverterMessage::Generated(id, result) => {
+                    if self.generation.finish(id) {
+                        match result {
+                            Ok(key_pair) => {
+                                replace_text_in_field(
+                                    &mut self.private_key_content,
+                                    key_pair.private_key,
+                                );
+                                replace_text_in_field(
+                                    &mut self.public_key_content,
+                                    key_pair.public_key,
+                                );
+                                return self.convert();
+                            }
+                            Err(status) => self.status = status,
+                        }
+                    }
+                }
+                KeyCreatorConverterMessage::CancelGeneration => {
+                    self.generation.cancel();
+                }
+                KeyCreatorConverterMessage::DerivePublicKey => {
+                    if let Some(key) = &self.key {
+                        match key.public_key().to_pem() {
+                            Ok(public_key) => {
+                                replace_text_in_field(&mut self.public_key_content, public_key);
+                                return self.convert();
+                            }
+                            Err(err) => self.status = err.into(),
+                        }
+                    }
+                }
+                KeyCreatorConverterMessage::CopyText(id) => {
+                    return clipboard::write(self.editor_content(&id).text());
+                }
+                KeyCreatorConverterMessage::PasteText(id) => {
+                    return clipboard::read().map(move |optional_data| match optional_data {
+                        Some(data) => cosmic::Action::App(Message::from(
+                            KeyCreatorConverterMessage::ReplaceText(id.clone(), data),
+                        )),
+                        None => {
+                            cosmic::Action::App(Message::from(KeyCreatorConverterMessage::NoOp))
+                        }
+                    });
+                }
+                KeyCreatorConverterMessage::ReplaceText(id, text) => {
+                    return self.replace_text(&id, text);
+                }
+                KeyCreatorConverterMessage::OpenFile(id) => {
+                    return files::open(move |result| {
+                        Message::from(KeyCreatorConverterMessage::FileOpened(id, result))
+                    });
+                }
+                KeyCreatorConverterMessage::FileDropped(id, path) => {
+                    return files::read(path, move |result| {
+                        Message::from(KeyCreatorConverterMessage::FileOpened(id, result))
+                    });
+                }
+                KeyCreatorConverterMessage::FileOpened(id, result) => match result {
+                    Ok(file) => return self.replace_text(&id, file.key_text()),
+                    Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                },
+                KeyCreatorConverterMessage::SaveFile(id) => {
+                    return self.save_file(&id);
+                }
+                KeyCreatorConverterMessage::FileSaved(result) => {
+                    self.status = Status::saved(result);
+                }
+                KeyCreatorConverterMessage::NoOp => {}
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
+        }
+        Task::none()
+    }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.key_creator_converter;
+        self.selected_key_type = config.key_type.min(KeyType::ALL.len() - 1);
+        self.output_format = config.output_format;
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::KeyCreatorConverter(KeyCreatorConverterConfig {
+            key_type: self.selected_key_type,
+            output_format: self.output_format,
+        })
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        let message = match action {
+            EditAction::CopyOutput => {
+                KeyCreatorConverterMessage::CopyText(Id::new(OUTPUT_EDITOR_ID))
+            }
+            EditAction::PasteInput => {
+                KeyCreatorConverterMessage::PasteText(Id::new(PRIVATE_KEY_EDITOR_ID))
+            }
+            EditAction::Clear | EditAction::Convert | EditAction::Swap => return None,
+        };
+        Some(Message::from(message))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        let generate_commands =
+            KeyType::ALL
+                .into_iter()
+                .enumerate()
+                .map(|(selection, key_type)| {
+                    Command::new(
+                        fl!(
+                            "command-palette",
+                            "generate-key",
+                            key_type = key_type.name()
+                        ),
+                        [
+                            Message::from(KeyCreatorConverterMessage::KeyTypeChanged(selection)),
+                            Message::from(KeyCreatorConverterMessage::Generate),
+                        ],
+                    )
+                });
+        let convert_commands = self.output_formats().into_iter().map(|format| {
+            Command::new(
+                fl!(
+                    "command-palette",
+                    "convert-key",
+                    format = format_label(format)
+                ),
+                [Message::from(
+                    KeyCreatorConverterMessage::OutputFormatChanged(format),
+                )],
+            )
+        });
+        generate_commands.chain(convert_commands).collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            self.conversion.subscription(),
+            self.generation.subscription(),
+        ])
+    }
+}
+
+impl KeyCreatorConverterPage {
+    fn editor_content(&self, id: &Id) -> &text_editor::Content {
+        if *id == Id::new(PRIVATE_KEY_EDITOR_ID) {
+            &self.private_key_content
+        } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+            &self.public_key_content
+        } else {
+            &self.output_content
+        }
+    }
+
+    fn replace_text(&mut self, id: &Id, text: String) -> Task<cosmic::Action<Message>> {
+        if *id == Id::new(PRIVATE_KEY_EDITOR_ID) {
+            replace_text_in_field(&mut self.private_key_content, text);
+        } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+            replace_text_in_field(&mut self.public_key_content, text);
+        } else {
+            return Task::none();
+        }
+        self.convert()
+    }
+
+    fn output_formats(&self) -> Vec<KeyFormat> {
+        output_formats(self.key.as_ref())
+    }
+
+    fn effective_output_format(&self) -> KeyFormat {
+        effective_output_format(self.key.as_ref(), self.output_format)
+    }
+
+    /// Reads the keys in the background, replacing a conversion that is still running.
+    fn convert(&mut self) -> Task<cosmic::Action<Message>> {
+        let private_key = self.private_key_content.text();
+        let public_key = self.public_key_content.text();
+        let output_format = self.output_format;
+        self.conversion.start(
+            move || Box::new(read_keys(&private_key, &public_key, output_format)),
+            |id, read_key| {
+                let read_key = read_key.unwrap_or_else(|status| {
+                    Box::new(ReadKey {
+                        key: None,
+                        fingerprints: None,
+                        output: String::new(),
+                        status,
+                    })
+                });
+                Message::from(KeyCreatorConverterMessage::Converted(id, read_key))
+            },
+        )
+    }
+
+    fn save_file(&self, id: &Id) -> Task<cosmic::Action<Message>> {
+        let text = self.editor_content(id).text();
+        let (file_name, contents) = if *id == Id::new(PRIVATE_KEY_EDITOR_ID) {
+            ("private-key.pem", text.into_bytes())
+        } else if *id == Id::new(PUBLIC_KEY_EDITOR_ID) {
+            ("public-key.pem", text.into_bytes())
+        } else {
+            match output_file(self.effective_output_format()) {
+                (file_name, true) => match base64::decode(&text, false) {
+                    Ok(der) => (file_name, der.as_bytes().to_vec()),
+                    Err(_) => (file_name, text.into_bytes()),
+                },
+                (file_name, false) => (file_name, text.into_bytes()),
+            }
+        };
+        files::save(file_name.to_string(), contents, |result| {
+            Message::from(KeyCreatorConverterMessage::FileSaved(result))
+        })
+    }
+
+    /// Generates a key pair of the selected type in the background.
+    fn generate_key(&mut self) -> Task<cosmic::Action<Message>> {
+        let key_type = KeyType::ALL[self.selected_key_type];
+        self.generation.start(
+            move || key::generate_pair(key_type).map_err(Status::from),
+            |id, result| Message::from(KeyCreatorConverterMessage::Generated(id, result.flatten())),
+        )
+    }
+}
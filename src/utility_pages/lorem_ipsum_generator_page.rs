@@ -1,11 +1,17 @@
 use {
     crate::{
         Message,
-        app::AppModel,
+        app::{AppModel, Page},
         class::{text_editor_class, text_input_style},
+        command_palette::Command,
+        config::{Config, LoremIpsumGeneratorConfig, PageConfig},
         fl,
         i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        utility_pages::{
+            EditAction, UtilityPage, files,
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
     cosmic::{
         self, Application, Element, Task, iced,
@@ -18,26 +24,46 @@ use {
             text_editor::{self, TextEditor},
         },
     },
-    lipsum::{lipsum_with_rng, lipsum_words_with_rng},
-    rand::{self, Rng},
+    dev_toolbox_core::{
+        lorem::{LoremUnit, generate},
+        service::Tool,
+    },
     std::sync::Arc,
 };
 
 const OUTPUT_EDITOR_ID: &str = "output-editor";
 const UNITS: [&str; 3] = ["words", "sentences", "paragraphs"];
 
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::LoremIpsumGenerator,
+    id: "lorem-ipsum-generator",
+    icon: ToolIcon::Svg(include_bytes!("../../resources/icons/lorem-ipsum.svg")),
+    new_page: || Box::new(LoremIpsumGeneratorPage::default()),
+    service: Some(Tool::Lorem),
+    takes: |_| false,
+};
+
 #[derive(Debug, Clone)]
 pub enum LoremIpsumGeneratorMessage {
     OutputEditorAction(text_editor::Action),
     UnitChanged(usize),
     AmountChanged(String),
     CopyText(Id),
+    SaveFile,
+    FileSaved(Result<(), String>),
+}
+
+impl From<LoremIpsumGeneratorMessage> for Message {
+    fn from(message: LoremIpsumGeneratorMessage) -> Self {
+        TOOL.message(message)
+    }
 }
 
 pub(crate) struct LoremIpsumGeneratorPage {
     output_content: text_editor::Content,
     selected_unit: usize,
     selected_amount: String,
+    status: Status,
 }
 
 impl Default for LoremIpsumGeneratorPage {
@@ -46,6 +72,7 @@ impl Default for LoremIpsumGeneratorPage {
             output_content: text_editor::Content::default(),
             selected_unit: 0,
             selected_amount: "20".to_string(),
+            status: Status::default(),
         }
     }
 }
@@ -71,9 +98,7 @@ impl UtilityPage for LoremIpsumGeneratorPage {
                     .style(text_input_style())
                     .width(80)
                     .on_input(|input| {
-                        Message::LoremIpsumGeneratorMessage(
-                            LoremIpsumGeneratorMessage::AmountChanged(input),
-                        )
+                        Message::from(LoremIpsumGeneratorMessage::AmountChanged(input))
                     }),
                 widget::dropdown(
                     UNITS
@@ -82,9 +107,7 @@ impl UtilityPage for LoremIpsumGeneratorPage {
                         .to_vec(),
                     Some(self.selected_unit),
                     |selection| {
-                        Message::LoremIpsumGeneratorMessage(
-                            LoremIpsumGeneratorMessage::UnitChanged(selection),
-                        )
+                        Message::from(LoremIpsumGeneratorMessage::UnitChanged(selection))
                     },
                 ),
             ]]
@@ -96,11 +119,17 @@ impl UtilityPage for LoremIpsumGeneratorPage {
             widget::text::title4(fl!("output"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(LoremIpsumGeneratorMessage::SaveFile)),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::LoremIpsumGeneratorMessage(LoremIpsumGeneratorMessage::CopyText(
-                        Id::new(OUTPUT_EDITOR_ID)
-                    ),),
+                    Message::from(LoremIpsumGeneratorMessage::CopyText(Id::new(
+                        OUTPUT_EDITOR_ID
+                    )),),
                 ),
                 widget::text(fl!("copy")),
                 widget::tooltip::Position::Bottom,
@@ -117,13 +146,11 @@ impl UtilityPage for LoremIpsumGeneratorPage {
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
             .on_action(|action| {
-                Message::LoremIpsumGeneratorMessage(LoremIpsumGeneratorMessage::OutputEditorAction(
-                    action,
-                ))
+                Message::from(LoremIpsumGeneratorMessage::OutputEditorAction(action))
             })
             .into();
 
-        column![
+        let mut page = column![
             header,
             options_header,
             amount_option,
@@ -131,123 +158,117 @@ impl UtilityPage for LoremIpsumGeneratorPage {
             output_editor
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::LoremIpsumGeneratorMessage(data_converter_formatter_message) => {
-                match data_converter_formatter_message {
-                    LoremIpsumGeneratorMessage::OutputEditorAction(action) => {
-                        if !action.is_edit() {
-                            self.output_content.perform(action);
-                        }
+        match message.tool_message::<LoremIpsumGeneratorMessage>() {
+            Some(data_converter_formatter_message) => match data_converter_formatter_message {
+                LoremIpsumGeneratorMessage::OutputEditorAction(action) => {
+                    if !action.is_edit() {
+                        self.output_content.perform(action);
                     }
-                    LoremIpsumGeneratorMessage::UnitChanged(selection) => {
-                        if selection != self.selected_unit {
-                            self.selected_unit = selection;
-                            self.generate_lorem();
-                        }
-                    }
-                    LoremIpsumGeneratorMessage::AmountChanged(amount) => {
-                        self.selected_amount = amount;
+                }
+                LoremIpsumGeneratorMessage::UnitChanged(selection) => {
+                    if selection != self.selected_unit {
+                        self.selected_unit = selection;
                         self.generate_lorem();
                     }
-                    LoremIpsumGeneratorMessage::CopyText(_) => {
-                        let to_copy = self.output_content.text();
-                        return clipboard::write(to_copy);
-                    }
                 }
-            }
-            _ => {
-                println!("Wrong message type");
-            }
+                LoremIpsumGeneratorMessage::AmountChanged(amount) => {
+                    self.selected_amount = amount;
+                    self.generate_lorem();
+                }
+                LoremIpsumGeneratorMessage::CopyText(_) => {
+                    let to_copy = self.output_content.text();
+                    return clipboard::write(to_copy);
+                }
+                LoremIpsumGeneratorMessage::SaveFile => {
+                    return files::save(
+                        "lorem-ipsum.txt".to_string(),
+                        self.output_content.text().into_bytes(),
+                        |result| Message::from(LoremIpsumGeneratorMessage::FileSaved(result)),
+                    );
+                }
+                LoremIpsumGeneratorMessage::FileSaved(result) => {
+                    self.status = Status::saved(result);
+                }
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.lorem_ipsum_generator;
+        self.selected_unit = config.unit.min(UNITS.len() - 1);
+        self.selected_amount = config.amount.clone();
+        Task::none()
+    }
+
+    /// The operations are the units to generate, and the input the amount of them.
+    fn prefill(&mut self, operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        if let Some(position) = UNITS.iter().position(|unit| *unit == operation) {
+            self.selected_unit = position;
+        }
+        if !input.trim().is_empty() {
+            self.selected_amount = input.trim().to_string();
+        }
+        self.generate_lorem();
+        Task::none()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        (action == EditAction::CopyOutput).then(|| {
+            Message::from(LoremIpsumGeneratorMessage::CopyText(Id::new(
+                OUTPUT_EDITOR_ID,
+            )))
+        })
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        UNITS
+            .into_iter()
+            .enumerate()
+            .map(|(unit, id)| {
+                Command::new(
+                    LANGUAGE_LOADER.get_attr("lorem-ipsum-generator", id),
+                    [Message::from(LoremIpsumGeneratorMessage::UnitChanged(unit))],
+                )
+            })
+            .collect()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::LoremIpsumGenerator(LoremIpsumGeneratorConfig {
+            unit: self.selected_unit,
+            amount: self.selected_amount.clone(),
+        })
+    }
 }
 
 impl LoremIpsumGeneratorPage {
     fn generate_lorem(&mut self) {
         let amount: usize = match self.selected_amount.parse::<usize>() {
             Ok(amt) => amt,
-            Err(_) => {
+            Err(err) => {
+                self.status = Status::error(fl!("invalid-number"), err);
                 return;
             }
         };
+        self.status = Status::Idle;
         self.output_content.perform(text_editor::Action::SelectAll);
-        let lorem: String = match self.selected_unit {
-            0 => generate_words(amount),
-            1 => generate_sentences(amount),
-            _ => generate_paragraphs(amount),
-        };
+        let lorem = generate(LoremUnit::ALL[self.selected_unit], amount);
         self.output_content
             .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
                 Arc::new(lorem),
             )));
     }
 }
-
-pub fn generate_words(n: usize) -> String {
-    lipsum_with_rng(rand::thread_rng(), n)
-}
-
-pub fn generate_sentences(n: usize) -> String {
-    let mut sentences = Vec::with_capacity(n);
-
-    // An average sentence is ~15-20 words so this should make 2 sentences.
-    while sentences.len() < n {
-        let chunk = if sentences.is_empty() {
-            lipsum_with_rng(rand::thread_rng(), 25)
-        } else {
-            lipsum_words_with_rng(rand::thread_rng(), 25)
-        };
-
-        let parts: Vec<&str> = chunk
-            .split_inclusive(|c| c == '.' || c == '!' || c == '?')
-            .collect();
-
-        for part in parts {
-            if sentences.len() < n {
-                sentences.push(part.trim().to_string());
-            }
-        }
-    }
-
-    sentences.join(" ")
-}
-
-pub fn generate_paragraphs(n: usize) -> String {
-    (0..n)
-        .map(|i| {
-            let s_count = 3 + (rand::thread_rng().r#gen::<usize>() / (usize::max_value() / 3));
-            generate_sentences_custom(s_count, i == 0)
-        })
-        .collect::<Vec<String>>()
-        .join("\n\n")
-}
-
-fn generate_sentences_custom(n: usize, start_with_lorem: bool) -> String {
-    let mut sentences = Vec::with_capacity(n);
-    while sentences.len() < n {
-        let is_first = start_with_lorem && sentences.is_empty();
-        let chunk = if is_first {
-            lipsum_with_rng(rand::thread_rng(), 25)
-        } else {
-            lipsum_words_with_rng(rand::thread_rng(), 25)
-        };
-        let parts: Vec<&str> = chunk
-            .split_inclusive(|c| c == '.' || c == '!' || c == '?')
-            .collect();
-        for part in parts {
-            if sentences.len() < n {
-                sentences.push(part.trim().to_string());
-            }
-        }
-    }
-    sentences.join(" ")
-}
@@ -1,18 +1,138 @@
 pub mod base64_string_encoder_decoder_page;
+pub mod byte_output;
+pub mod conversion;
 pub mod data_converter_formatter_page;
+pub mod diff_view;
+pub mod files;
 pub mod gzip_compressor_decompressor_page;
 pub mod jwt_debugger_page;
+pub mod key_creator_converter_page;
 pub mod lorem_ipsum_generator_page;
+pub mod pipeline_page;
+pub mod registry;
+pub mod status;
+pub mod text_diff_page;
 pub mod unix_time_converter_page;
 pub mod url_encoder_decoder_page;
 
 use {
-    crate::{Message, app::AppModel},
-    cosmic::{Application, Element, app::Task},
+    crate::{
+        Message,
+        app::{self, AppModel},
+        command_palette::Command,
+        config::{Config, PageConfig},
+    },
+    cosmic::{
+        Application, Element,
+        app::Task,
+        cosmic_config,
+        iced::{
+            Subscription,
+            keyboard::{Key, key},
+        },
+        widget::text_editor::{self, Binding, KeyPress},
+    },
+    dev_toolbox_core::detect::ContentKind,
 };
 
+/// The key bindings of the editable text editors: Tab inserts a tab rather than moving the
+/// focus, and the keys bound to actions of the app, such as Ctrl+Tab or Ctrl+Enter, are left to
+/// the app alone.
+pub(crate) fn editor_key_binding(key_press: KeyPress) -> Option<Binding<Message>> {
+    if app::is_key_bind(key_press.modifiers, &key_press.key) {
+        return None;
+    }
+    if key_press.key == Key::Named(key::Named::Tab)
+        && matches!(key_press.status, text_editor::Status::Focused { .. })
+    {
+        return Some(Binding::Insert('\t'));
+    }
+    Binding::from_key_press(key_press)
+}
+
+/// Actions of the Edit menu, applied to the active page.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum EditAction {
+    Convert,
+    /// Moves the output into the input and reverses the operation.
+    Swap,
+    CopyOutput,
+    PasteInput,
+    /// Empties the input and the output.
+    Clear,
+}
+
 pub(crate) trait UtilityPage {
     fn get_utility_page(&self) -> Element<'_, Message>;
 
     fn handle_message(&mut self, message: Message) -> Task<<AppModel as Application>::Message>;
+
+    /// Restores the options, and the input when it was remembered, from a previous run.
+    ///
+    /// The returned task converts the restored input.
+    fn load_config(&mut self, config: &Config) -> Task<<AppModel as Application>::Message>;
+
+    /// The options and the input of the page, as stored in its entry of the config.
+    fn page_config(&self) -> PageConfig;
+
+    /// Writes the page's entry of the config, skipping the input unless inputs are remembered
+    /// and the page keeps it.
+    fn save_config(
+        &self,
+        config: &mut Config,
+        config_handler: &cosmic_config::Config,
+    ) -> Result<bool, cosmic_config::Error> {
+        let mut page_config = self.page_config();
+        if !config.remember_inputs || !page_config.keeps_input() {
+            page_config.clear_input();
+        }
+        config.set_page_config(config_handler, page_config)
+    }
+
+    /// Replaces the input with pasted content of a kind this page handles and converts it.
+    fn paste_detected(
+        &mut self,
+        _content: String,
+        _kind: ContentKind,
+    ) -> Task<<AppModel as Application>::Message> {
+        Task::none()
+    }
+
+    /// The page's message performing an Edit menu action, `None` when the page has no such action.
+    fn edit_message(&self, _action: EditAction) -> Option<Message> {
+        None
+    }
+
+    /// Actions of the page offered by the command palette.
+    fn commands(&self) -> Vec<Command> {
+        Vec::new()
+    }
+
+    /// Events the page listens to while it is open in a tab or a window.
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::none()
+    }
+
+    /// The input and output of the conversion the page shows, kept in the history when the page
+    /// is left. `None` when the output does not convert the current input.
+    fn history_entry(&self) -> Option<(String, String)> {
+        None
+    }
+
+    /// Replaces the input with one from the history and converts it.
+    fn restore_input(&mut self, _input: String) -> Task<<AppModel as Application>::Message> {
+        Task::none()
+    }
+
+    /// Selects an operation by its name in [`Tool::operations`](dev_toolbox_core::service::Tool),
+    /// then replaces the input and converts it.
+    ///
+    /// Operations the page does not have are ignored.
+    fn prefill(
+        &mut self,
+        _operation: &str,
+        input: String,
+    ) -> Task<<AppModel as Application>::Message> {
+        self.restore_input(input)
+    }
 }
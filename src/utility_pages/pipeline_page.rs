@@ -0,0 +1,706 @@
+use {
+    crate::{
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, PageConfig, PipelineConfig},
+        context_drawer_pages::history_page::{self, Recording},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            byte_output::ByteOutput,
+            conversion::{self, Conversion},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
+    },
+    cosmic::{
+        self, Application, Element, Task,
+        dialog::file_chooser::{self, FileFilter},
+        iced::{
+            self, Alignment, Length, Padding, Subscription, clipboard,
+            widget::{column, row},
+        },
+        widget::{
+            self,
+            text_editor::{self, TextEditor},
+        },
+    },
+    dev_toolbox_core::{
+        bytes::ByteBuffer,
+        data_format::{DataFormat, INDENTS},
+        pipeline::{Recipe, Step},
+    },
+    std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+const RECIPE_FILE_NAME: &str = "recipe.toml";
+
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::Pipeline,
+    id: "pipeline",
+    icon: ToolIcon::Named("view-list-symbolic"),
+    new_page: || Box::new(PipelinePage::default()),
+    service: None,
+    takes: |_| false,
+};
+
+#[derive(Debug, Clone)]
+pub enum PipelineMessage {
+    InputEditorAction(text_editor::Action),
+    OutputEditorAction(usize, text_editor::Action),
+    RenderingChanged(usize, usize),
+    AddStep,
+    RemoveStep(usize),
+    MoveStepUp(usize),
+    MoveStepDown(usize),
+    OperationChanged(usize, usize),
+    UrlSafeToggled(usize, bool),
+    FromFormatChanged(usize, usize),
+    ToFormatChanged(usize, usize),
+    IndentChanged(usize, usize),
+    RunPipeline,
+    Converted(u64, Vec<Result<ByteBuffer, Status>>),
+    CancelConversion,
+    Clear,
+    CopyOutput(usize),
+    OpenFile,
+    FileDropped(PathBuf),
+    FileOpened(Result<OpenedFile, String>),
+    SaveFile(usize),
+    FileSaved(Result<(), String>),
+    PasteInput,
+    ReplaceInput(String),
+    OpenRecipe,
+    RecipeOpened(Result<Recipe, String>),
+    SaveRecipe,
+    RecipeSaved(Result<(), String>),
+    NoOp,
+}
+
+impl From<PipelineMessage> for Message {
+    fn from(message: PipelineMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
+/// A step of the pipeline with the output it produced on the last run.
+struct PipelineStep {
+    step: Step,
+    output: ByteOutput,
+    status: Status,
+}
+
+impl PipelineStep {
+    fn new(step: Step) -> Self {
+        Self {
+            step,
+            output: ByteOutput::default(),
+            status: Status::Idle,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PipelinePage {
+    input_content: text_editor::Content,
+    steps: Vec<PipelineStep>,
+    conversion: Conversion,
+    recording: Recording,
+    /// Reports opening and saving recipes, the steps report their own errors.
+    status: Status,
+}
+
+impl UtilityPage for PipelinePage {
+    fn get_utility_page(&self) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let header = row![
+            widget::text::title2(fl!("pipeline")).width(Length::Fill),
+            widget::button::standard(fl!("pipeline", "open-recipe"))
+                .on_press(Message::from(PipelineMessage::OpenRecipe)),
+            widget::button::standard(fl!("pipeline", "save-recipe"))
+                .on_press(Message::from(PipelineMessage::SaveRecipe)),
+        ]
+        .align_y(Alignment::End)
+        .spacing(space_s);
+
+        let input_header: Element<'_, Message> = row![
+            widget::text::title4(fl!("input"))
+                .width(Length::Fill)
+                .align_x(Alignment::Start),
+            widget::button::text(fl!("pipeline", "run"))
+                .on_press(Message::from(PipelineMessage::RunPipeline)),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::from(PipelineMessage::OpenFile)),
+                widget::text(fl!("open-file")),
+                widget::tooltip::Position::Bottom,
+            ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
+                    .on_press(Message::from(PipelineMessage::PasteInput)),
+                widget::text(fl!("paste")),
+                widget::tooltip::Position::Bottom,
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .into();
+
+        let input_editor: Element<'_, Message> = TextEditor::new(&self.input_content)
+            .padding(Padding::new(12.0))
+            .height(Length::Fixed(160.0))
+            .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+                text_editor_class,
+            )))
+            .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+            .on_action(|action| Message::from(PipelineMessage::InputEditorAction(action)))
+            .key_binding(editor_key_binding)
+            .into();
+        let input_editor = files::drop_target(
+            input_editor,
+            |path| Message::from(PipelineMessage::FileDropped(path)),
+            Message::from(PipelineMessage::NoOp),
+        );
+
+        let mut page = column![header, input_header, input_editor].spacing(space_s);
+        if self.steps.is_empty() {
+            page = page.push(widget::text::body(fl!("pipeline", "empty")));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            page = page.push(self.step_view(index, step));
+        }
+        page = page.push(
+            widget::button::standard(fl!("pipeline", "add-step"))
+                .leading_icon(widget::icon::from_name("list-add-symbolic"))
+                .on_press(Message::from(PipelineMessage::AddStep)),
+        );
+        if let Some(progress) = self
+            .conversion
+            .view(Message::from(PipelineMessage::CancelConversion))
+        {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+
+        widget::scrollable(page.padding(Padding::new(0.0).right(space_s)))
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn handle_message(
+        &mut self,
+        message: Message,
+    ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
+        match message.tool_message::<PipelineMessage>() {
+            Some(pipeline_message) => match pipeline_message {
+                PipelineMessage::InputEditorAction(action) => {
+                    if action.is_edit() {
+                        self.recording.clear();
+                    }
+                    self.input_content.perform(action);
+                }
+                PipelineMessage::OutputEditorAction(index, action) => {
+                    if !action.is_edit()
+                        && let Some(step) = self.steps.get_mut(index)
+                    {
+                        step.output.content.perform(action);
+                    }
+                }
+                PipelineMessage::RenderingChanged(index, selection) => {
+                    if let Some(step) = self.steps.get_mut(index) {
+                        step.output.set_rendering(selection);
+                    }
+                }
+                PipelineMessage::AddStep => {
+                    // Decoding the output of the previous step is the most common next step.
+                    self.steps.push(PipelineStep::new(Step::ALL[1]));
+                    return self.run_live();
+                }
+                PipelineMessage::RemoveStep(index) => {
+                    if index < self.steps.len() {
+                        self.steps.remove(index);
+                        return self.run_live();
+                    }
+                }
+                PipelineMessage::MoveStepUp(index) => {
+                    if index > 0 && index < self.steps.len() {
+                        self.steps.swap(index - 1, index);
+                        return self.run_live();
+                    }
+                }
+                PipelineMessage::MoveStepDown(index) => {
+                    if index + 1 < self.steps.len() {
+                        self.steps.swap(index, index + 1);
+                        return self.run_live();
+                    }
+                }
+                PipelineMessage::OperationChanged(index, selection) => {
+                    return self.update_step(index, |step| *step = Step::ALL[selection]);
+                }
+                PipelineMessage::UrlSafeToggled(index, url_safe) => {
+                    return self.update_step(index, |step| {
+                        if let Step::Base64Encode { url_safe: value }
+                        | Step::Base64Decode { url_safe: value } = step
+                        {
+                            *value = url_safe;
+                        }
+                    });
+                }
+                PipelineMessage::FromFormatChanged(index, selection) => {
+                    return self.update_step(index, |step| {
+                        if let Step::ConvertFormat { from, .. } = step {
+                            *from = DataFormat::ALL[selection];
+                        }
+                    });
+                }
+                PipelineMessage::ToFormatChanged(index, selection) => {
+                    return self.update_step(index, |step| {
+                        if let Step::ConvertFormat { to, .. } = step {
+                            *to = DataFormat::ALL[selection];
+                        }
+                    });
+                }
+                PipelineMessage::IndentChanged(index, selection) => {
+                    return self.update_step(index, |step| {
+                        if let Step::ConvertFormat { indent, .. } = step {
+                            *indent = INDENTS[selection];
+                        }
+                    });
+                }
+                PipelineMessage::RunPipeline => {
+                    self.recording.request();
+                    return self.run_pipeline();
+                }
+                PipelineMessage::Converted(id, results) => {
+                    if self.conversion.finish(id) {
+                        if let Status::Info(_) = self.status {
+                            self.status = Status::Idle;
+                        }
+                        let succeeded = results.iter().all(Result::is_ok);
+                        self.show_results(results);
+                        if self.recording.finish(succeeded) {
+                            return history_page::record(Page::Pipeline, self.history_entry());
+                        }
+                    }
+                }
+                PipelineMessage::Clear => {
+                    self.conversion.cancel();
+                    self.recording.clear();
+                    self.input_content = text_editor::Content::default();
+                    self.show_results(Vec::new());
+                    self.status = Status::Idle;
+                }
+                PipelineMessage::CancelConversion => {
+                    self.conversion.cancel();
+                }
+                PipelineMessage::CopyOutput(index) => {
+                    if let Some(step) = self.steps.get(index) {
+                        // Copying an output keeps the run in the history.
+                        return Task::batch([
+                            clipboard::write(step.output.content.text()),
+                            history_page::record(Page::Pipeline, self.history_entry()),
+                        ]);
+                    }
+                }
+                PipelineMessage::OpenFile => {
+                    return files::open(|result| {
+                        Message::from(PipelineMessage::FileOpened(result))
+                    });
+                }
+                PipelineMessage::FileDropped(path) => {
+                    return files::read(path, |result| {
+                        Message::from(PipelineMessage::FileOpened(result))
+                    });
+                }
+                PipelineMessage::FileOpened(result) => match result.and_then(|file| file.text()) {
+                    Ok(text) => {
+                        self.input_content = text_editor::Content::with_text(&text);
+                        return self.run_live();
+                    }
+                    Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                },
+                PipelineMessage::SaveFile(index) => {
+                    if let Some(step) = self.steps.get(index) {
+                        let extension = match step.step {
+                            Step::ConvertFormat { to, .. } => to.extension(),
+                            Step::JwtDecode => DataFormat::Json.extension(),
+                            _ => step.output.extension(),
+                        };
+                        return step
+                            .output
+                            .save_as(format!("output.{extension}"), |result| {
+                                Message::from(PipelineMessage::FileSaved(result))
+                            });
+                    }
+                }
+                PipelineMessage::FileSaved(result) => {
+                    self.status = Status::saved(result);
+                }
+                PipelineMessage::PasteInput => {
+                    return clipboard::read().map(|optional_data| match optional_data {
+                        Some(data) => {
+                            cosmic::Action::App(Message::from(PipelineMessage::ReplaceInput(data)))
+                        }
+                        None => cosmic::Action::App(Message::from(PipelineMessage::NoOp)),
+                    });
+                }
+                PipelineMessage::ReplaceInput(text) => {
+                    self.recording.clear();
+                    self.input_content.perform(text_editor::Action::SelectAll);
+                    self.input_content.perform(text_editor::Action::Edit(
+                        text_editor::Edit::Paste(Arc::new(text)),
+                    ));
+                    return self.run_live();
+                }
+                PipelineMessage::OpenRecipe => {
+                    return cosmic::task::future(async move {
+                        let dialog = file_chooser::open::Dialog::new()
+                            .title(fl!("pipeline", "open-recipe"))
+                            .filter(recipe_filter());
+                        let message = match dialog.open_file().await {
+                            Ok(response) => {
+                                PipelineMessage::RecipeOpened(open_recipe(response.url()).await)
+                            }
+                            Err(file_chooser::Error::Cancelled) => PipelineMessage::NoOp,
+                            Err(err) => PipelineMessage::RecipeOpened(Err(err.to_string())),
+                        };
+                        cosmic::Action::App(Message::from(message))
+                    });
+                }
+                PipelineMessage::RecipeOpened(result) => match result {
+                    Ok(recipe) => {
+                        self.steps = recipe.steps.into_iter().map(PipelineStep::new).collect();
+                        self.status = Status::Idle;
+                        return self.run_live();
+                    }
+                    Err(err) => self.status = Status::error(fl!("pipeline", "open-failed"), err),
+                },
+                PipelineMessage::SaveRecipe => {
+                    let recipe = self.recipe();
+                    return cosmic::task::future(async move {
+                        let dialog = file_chooser::save::Dialog::new()
+                            .title(fl!("pipeline", "save-recipe"))
+                            .file_name(RECIPE_FILE_NAME)
+                            .filter(recipe_filter());
+                        let message = match dialog.save_file().await {
+                            Ok(response) => match response.url() {
+                                Some(url) => {
+                                    PipelineMessage::RecipeSaved(save_recipe(&recipe, url).await)
+                                }
+                                None => PipelineMessage::NoOp,
+                            },
+                            Err(file_chooser::Error::Cancelled) => PipelineMessage::NoOp,
+                            Err(err) => PipelineMessage::RecipeSaved(Err(err.to_string())),
+                        };
+                        cosmic::Action::App(Message::from(message))
+                    });
+                }
+                PipelineMessage::RecipeSaved(result) => {
+                    self.status = match result {
+                        Ok(()) => Status::Success(fl!("pipeline", "saved")),
+                        Err(err) => Status::error(fl!("pipeline", "save-failed"), err),
+                    };
+                }
+                PipelineMessage::NoOp => {}
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
+        }
+        Task::none()
+    }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.pipeline;
+        self.steps = config
+            .steps
+            .iter()
+            .copied()
+            .map(PipelineStep::new)
+            .collect();
+        if !config.input.is_empty() {
+            self.input_content = text_editor::Content::with_text(&config.input);
+            return self.run_live();
+        }
+        Task::none()
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        let step = self.steps.last()?;
+        self.recording
+            .is_converted()
+            .then(|| (self.input_content.text(), step.output.content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.input_content = text_editor::Content::with_text(&input);
+        self.run_pipeline()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        let message = match action {
+            EditAction::Convert => PipelineMessage::RunPipeline,
+            EditAction::CopyOutput => PipelineMessage::CopyOutput(self.steps.len().checked_sub(1)?),
+            EditAction::PasteInput => PipelineMessage::PasteInput,
+            EditAction::Clear => PipelineMessage::Clear,
+            EditAction::Swap => return None,
+        };
+        Some(Message::from(message))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        [
+            (fl!("pipeline", "run"), PipelineMessage::RunPipeline),
+            (fl!("pipeline", "add-step"), PipelineMessage::AddStep),
+            (fl!("pipeline", "open-recipe"), PipelineMessage::OpenRecipe),
+            (fl!("pipeline", "save-recipe"), PipelineMessage::SaveRecipe),
+        ]
+        .into_iter()
+        .map(|(title, message)| Command::new(title, [Message::from(message)]))
+        .collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::Pipeline(PipelineConfig {
+            steps: self.recipe().steps,
+            input: self.input_content.text(),
+        })
+    }
+}
+
+impl PipelinePage {
+    fn step_view<'a>(&'a self, index: usize, step: &'a PipelineStep) -> Element<'a, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let mut options = widget::row::with_capacity(6)
+            .push(widget::text::title4(format!("{}.", index + 1)))
+            .push(widget::dropdown(
+                Step::ALL
+                    .map(|step| LANGUAGE_LOADER.get_attr("pipeline", step.id()))
+                    .to_vec(),
+                Step::ALL
+                    .iter()
+                    .position(|other| other.id() == step.step.id()),
+                move |selection| Message::from(PipelineMessage::OperationChanged(index, selection)),
+            ))
+            .align_y(Alignment::Center)
+            .spacing(space_s);
+        match step.step {
+            Step::Base64Encode { url_safe } | Step::Base64Decode { url_safe } => {
+                options = options.push(
+                    widget::checkbox(url_safe)
+                        .label(fl!("base64-string-encoder-decoder", "url-safe"))
+                        .on_toggle(move |url_safe| {
+                            Message::from(PipelineMessage::UrlSafeToggled(index, url_safe))
+                        }),
+                );
+            }
+            Step::ConvertFormat { from, to, indent } => {
+                options = options
+                    .push(widget::dropdown(
+                        DataFormat::ALL.map(DataFormat::name).to_vec(),
+                        DataFormat::ALL.iter().position(|format| *format == from),
+                        move |selection| {
+                            Message::from(PipelineMessage::FromFormatChanged(index, selection))
+                        },
+                    ))
+                    .push(widget::text::body(fl!("pipeline", "to")))
+                    .push(widget::dropdown(
+                        DataFormat::ALL.map(DataFormat::name).to_vec(),
+                        DataFormat::ALL.iter().position(|format| *format == to),
+                        move |selection| {
+                            Message::from(PipelineMessage::ToFormatChanged(index, selection))
+                        },
+                    ))
+                    .push_maybe(if to.supports_indent() {
+                        Some(widget::dropdown(
+                            INDENTS
+                                .map(|indent| {
+                                    format!(
+                                        "{} {}",
+                                        indent,
+                                        fl!("data-converter-formatter", "spaces")
+                                    )
+                                })
+                                .to_vec(),
+                            INDENTS.iter().position(|other| *other == indent),
+                            move |selection| {
+                                Message::from(PipelineMessage::IndentChanged(index, selection))
+                            },
+                        ))
+                    } else {
+                        None
+                    });
+            }
+            _ => {}
+        }
+
+        let step_header: Element<'_, Message> = row![
+            options.width(Length::Fill),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("go-up-symbolic")).on_press_maybe(
+                    (index > 0).then_some(Message::from(PipelineMessage::MoveStepUp(index)))
+                ),
+                widget::text(fl!("pipeline", "move-up")),
+                widget::tooltip::Position::Bottom,
+            ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("go-down-symbolic")).on_press_maybe(
+                    (index + 1 < self.steps.len())
+                        .then_some(Message::from(PipelineMessage::MoveStepDown(index)))
+                ),
+                widget::text(fl!("pipeline", "move-down")),
+                widget::tooltip::Position::Bottom,
+            ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                    .on_press(Message::from(PipelineMessage::RemoveStep(index))),
+                widget::text(fl!("pipeline", "remove")),
+                widget::tooltip::Position::Bottom,
+            ),
+            step.output.rendering_dropdown(move |selection| {
+                Message::from(PipelineMessage::RenderingChanged(index, selection))
+            }),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(PipelineMessage::SaveFile(index))),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+                    .on_press(Message::from(PipelineMessage::CopyOutput(index))),
+                widget::text(fl!("copy")),
+                widget::tooltip::Position::Bottom,
+            ),
+        ]
+        .align_y(Alignment::Center)
+        .into();
+
+        let output_editor: Element<'_, Message> = TextEditor::new(&step.output.content)
+            .padding(Padding::new(12.0))
+            .height(Length::Fixed(120.0))
+            .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+                text_editor_class,
+            )))
+            .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+            .on_action(move |action| {
+                Message::from(PipelineMessage::OutputEditorAction(index, action))
+            })
+            .into();
+
+        let mut step_column = column![step_header, output_editor].spacing(space_s);
+        if let Some(status_banner) = step.status.view() {
+            step_column = step_column.push(status_banner);
+        }
+        step_column.into()
+    }
+
+    fn recipe(&self) -> Recipe {
+        Recipe {
+            steps: self.steps.iter().map(|step| step.step).collect(),
+        }
+    }
+
+    fn update_step(
+        &mut self,
+        index: usize,
+        update: impl FnOnce(&mut Step),
+    ) -> Task<cosmic::Action<Message>> {
+        let Some(step) = self.steps.get_mut(index) else {
+            return Task::none();
+        };
+        update(&mut step.step);
+        self.run_live()
+    }
+
+    /// Feeds the input through every step in the background, replacing a run that is still going.
+    fn run_pipeline(&mut self) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
+        let recipe = self.recipe();
+        let input = self.input_content.text();
+        self.conversion.start(
+            move || {
+                recipe
+                    .run(input.into())
+                    .into_iter()
+                    .map(|result| result.map_err(|err| Status::from(&err)))
+                    .collect()
+            },
+            |id, results| {
+                // A run that failed as a whole is reported at its first step.
+                let results = results.unwrap_or_else(|status| vec![Err(status)]);
+                Message::from(PipelineMessage::Converted(id, results))
+            },
+        )
+    }
+
+    /// Runs the pipeline after a change, unless the input is too large to run on every change.
+    fn run_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.input_content.text()) {
+            return self.run_pipeline();
+        }
+        self.conversion.cancel();
+        self.recording.clear();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
+    }
+
+    /// Shows the output of every step, clearing the outputs after a failing one.
+    fn show_results(&mut self, results: Vec<Result<ByteBuffer, Status>>) {
+        let mut results = results.into_iter();
+        for step in &mut self.steps {
+            let (output, status) = match results.next() {
+                Some(Ok(output)) => (output, Status::Idle),
+                Some(Err(status)) => (ByteBuffer::default(), status),
+                None => (ByteBuffer::default(), Status::Idle),
+            };
+            step.output.set_buffer(output);
+            step.status = status;
+        }
+    }
+}
+
+fn recipe_filter() -> FileFilter {
+    FileFilter::new(fl!("pipeline", "recipes"))
+        .glob("*.toml")
+        .glob("*.json")
+}
+
+/// Recipes are written as TOML unless the file name asks for JSON.
+fn recipe_format(path: &Path) -> DataFormat {
+    match DataFormat::from_path(path) {
+        Some(DataFormat::Json) => DataFormat::Json,
+        _ => DataFormat::Toml,
+    }
+}
+
+async fn open_recipe(url: &url::Url) -> Result<Recipe, String> {
+    let path = url.to_file_path().map_err(|()| fl!("not-a-local-file"))?;
+    let input = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|err| err.to_string())?;
+    Recipe::parse(&input, recipe_format(&path)).map_err(|err| err.to_string())
+}
+
+async fn save_recipe(recipe: &Recipe, url: &url::Url) -> Result<(), String> {
+    let path = url.to_file_path().map_err(|()| fl!("not-a-local-file"))?;
+    let output = recipe
+        .serialize(recipe_format(&path))
+        .map_err(|err| err.to_string())?;
+    tokio::fs::write(&path, output)
+        .await
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! The tools of the app, each declared by its page module and listed here once.
+
+use {
+    crate::{
+        Message,
+        app::Page,
+        config::Config,
+        utility_pages::{
+            UtilityPage, base64_string_encoder_decoder_page, data_converter_formatter_page,
+            gzip_compressor_decompressor_page, jwt_debugger_page, key_creator_converter_page,
+            lorem_ipsum_generator_page, pipeline_page, text_diff_page, unix_time_converter_page,
+            url_encoder_decoder_page,
+        },
+    },
+    cosmic::widget::icon,
+    dev_toolbox_core::{detect::ContentKind, service::Tool},
+    std::{
+        any::Any,
+        fmt::{self, Debug},
+    },
+};
+
+/// Every tool, in the default order of the nav bar.
+pub(crate) const TOOLS: &[&ToolInfo] = &[
+    &unix_time_converter_page::TOOL,
+    &data_converter_formatter_page::TOOL,
+    &base64_string_encoder_decoder_page::TOOL,
+    &gzip_compressor_decompressor_page::TOOL,
+    &url_encoder_decoder_page::TOOL,
+    &jwt_debugger_page::TOOL,
+    &lorem_ipsum_generator_page::TOOL,
+    &pipeline_page::TOOL,
+    &text_diff_page::TOOL,
+    &key_creator_converter_page::TOOL,
+];
+
+/// How the app shows a tool and hands it its messages.
+pub(crate) struct ToolInfo {
+    pub(crate) page: Page,
+    /// A stable name of the tool, which is also the id of its title in the translations.
+    pub(crate) id: &'static str,
+    pub(crate) icon: ToolIcon,
+    /// A new instance of the page, with its options at their defaults.
+    pub(crate) new_page: fn() -> Box<dyn UtilityPage>,
+    /// The tool of the command line and D-Bus interface that the page serves.
+    pub(crate) service: Option<Tool>,
+    /// Whether the page takes pasted content of a detected kind as input.
+    pub(crate) takes: fn(ContentKind) -> bool,
+}
+
+impl ToolInfo {
+    /// A message of the tool's page, routed to the page by [`Message::Tool`].
+    pub(crate) fn message(&self, message: impl Any + Clone + Debug + Send) -> Message {
+        Message::Tool(self.page, ToolMessage(Box::new(message)))
+    }
+}
+
+/// A message of a tool's page, of the type its page module declares.
+pub struct ToolMessage(Box<dyn PageMessage>);
+
+impl ToolMessage {
+    /// The message, `None` when it is not of the type `M`.
+    fn downcast<M: Any>(self) -> Option<M> {
+        self.0.into_any().downcast().ok().map(|message| *message)
+    }
+}
+
+impl Clone for ToolMessage {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl Debug for ToolMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+trait PageMessage: Debug + Send {
+    fn clone_box(&self) -> Box<dyn PageMessage>;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<M: Any + Clone + Debug + Send> PageMessage for M {
+    fn clone_box(&self) -> Box<dyn PageMessage> {
+        Box::new(self.clone())
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Message {
+    /// The message of a tool's page when it is of the type `M`, which a page declares.
+    pub(crate) fn tool_message<M: Any>(self) -> Option<M> {
+        match self {
+            Message::Tool(_, message) => message.downcast(),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) enum ToolIcon {
+    /// An icon of the icon theme.
+    Named(&'static str),
+    /// A symbolic SVG icon bundled with the app.
+    Svg(&'static [u8]),
+}
+
+impl ToolIcon {
+    pub(crate) fn icon(&self) -> icon::Icon {
+        match self {
+            ToolIcon::Named(name) => icon::from_name(*name).icon(),
+            ToolIcon::Svg(bytes) => icon::from_svg_bytes(*bytes).symbolic(true).icon(),
+        }
+    }
+}
+
+/// Every tool, in the order chosen by the user followed by the tools that order lacks.
+pub(crate) fn ordered(config: &Config) -> Vec<&'static ToolInfo> {
+    let mut tools: Vec<&ToolInfo> = Vec::with_capacity(TOOLS.len());
+    let ids = config.tool_order.iter().map(String::as_str);
+    for id in ids.chain(TOOLS.iter().map(|tool| tool.id)) {
+        if let Some(tool) = TOOLS.iter().copied().find(|tool| tool.id == id)
+            && !tools.iter().any(|other| other.id == id)
+        {
+            tools.push(tool);
+        }
+    }
+    tools
+}
+
+/// The tools shown in the nav bar in their order, every tool when all of them were disabled.
+pub(crate) fn enabled(config: &Config) -> Vec<&'static ToolInfo> {
+    let tools = ordered(config);
+    let enabled: Vec<_> = tools
+        .iter()
+        .copied()
+        .filter(|tool| !config.disabled_tools.iter().any(|id| id == tool.id))
+        .collect();
+    if enabled.is_empty() { tools } else { enabled }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, dev_toolbox_core::data_format::DataFormat};
+
+    /// The page after the given one, so that adding a page fails to compile until it is listed.
+    fn next_page(page: Page) -> Option<Page> {
+        match page {
+            Page::UnixTimeConverter => Some(Page::DataConverterFormatter),
+            Page::DataConverterFormatter => Some(Page::Base64StringEncoderDecoder),
+            Page::Base64StringEncoderDecoder => Some(Page::GZipCompressorDecompressor),
+            Page::GZipCompressorDecompressor => Some(Page::UrlEncoderDecoder),
+            Page::UrlEncoderDecoder => Some(Page::JwtDebugger),
+            Page::JwtDebugger => Some(Page::LoremIpsumGenerator),
+            Page::LoremIpsumGenerator => Some(Page::Pipeline),
+            Page::Pipeline => Some(Page::TextDiff),
+            Page::TextDiff => Some(Page::KeyCreatorConverter),
+            Page::KeyCreatorConverter => None,
+        }
+    }
+
+    #[test]
+    fn registers_every_page_once() {
+        let pages: Vec<Page> =
+            std::iter::successors(Some(Page::UnixTimeConverter), |&page| next_page(page)).collect();
+        assert_eq!(TOOLS.len(), pages.len());
+        for page in pages {
+            let registered = TOOLS.iter().filter(|tool| tool.page == page).count();
+            assert_eq!(registered, 1, "{page:?}");
+        }
+        for tool in TOOLS {
+            let same_id = TOOLS.iter().filter(|other| other.id == tool.id).count();
+            assert_eq!(same_id, 1, "{}", tool.id);
+        }
+    }
+
+    #[test]
+    fn serves_every_tool_once() {
+        for tool in Tool::ALL {
+            let serving = TOOLS
+                .iter()
+                .filter(|info| info.service == Some(tool))
+                .count();
+            assert_eq!(serving, 1, "{}", tool.name());
+        }
+    }
+
+    #[test]
+    fn takes_every_kind_of_content_once() {
+        let kinds = [
+            ContentKind::Jwt,
+            ContentKind::Gzip,
+            ContentKind::PercentEncoded,
+            ContentKind::UnixSeconds,
+            ContentKind::UnixMilliseconds,
+            ContentKind::Base64,
+        ]
+        .into_iter()
+        .chain(DataFormat::ALL.map(ContentKind::Document));
+        for kind in kinds {
+            let taking = TOOLS.iter().filter(|tool| (tool.takes)(kind)).count();
+            assert_eq!(taking, 1, "{kind:?}");
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Localized status banner shown at the bottom of the utility pages.
+
+use {
+    crate::{Message, class::status_banner_style, fl, i18n::LANGUAGE_LOADER},
+    cosmic::{
+        self, Element,
+        iced::{Alignment, Length},
+        widget,
+    },
+    dev_toolbox_core::{
+        base64, data_format::ConversionError, gzip::DecompressError, jwt::JwtError, key::KeyError,
+        pipeline::StepError, unix_time::UnixTimeError, url,
+    },
+    std::fmt::Display,
+};
+
+#[derive(Clone, Debug, Default)]
+pub(crate) enum Status {
+    /// Nothing to report, the banner is hidden.
+    #[default]
+    Idle,
+    /// A hint that is neither a success nor an error.
+    Info(String),
+    Success(String),
+    Error {
+        title: String,
+        /// The underlying parser or decoder message, which is not localized.
+        details: Option<String>,
+    },
+}
+
+impl Status {
+    pub(crate) fn error(title: String, details: impl Display) -> Self {
+        Status::Error {
+            title,
+            details: Some(details.to_string()),
+        }
+    }
+
+    /// Reports the outcome of writing an output to a file.
+    pub(crate) fn saved(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Status::Success(fl!("saved-to-file")),
+            Err(err) => Status::error(fl!("save-failed"), err),
+        }
+    }
+
+    pub(crate) fn view(&self) -> Option<Element<'_, Message>> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let (icon_name, title, details, is_error) = match self {
+            Status::Idle => return None,
+            Status::Info(title) => ("dialog-information-symbolic", title, None, false),
+            Status::Success(title) => ("emblem-ok-symbolic", title, None, false),
+            Status::Error { title, details } => {
+                ("dialog-error-symbolic", title, details.as_ref(), true)
+            }
+        };
+
+        let mut text = widget::column::with_capacity(2)
+            .push(widget::text::heading(title))
+            .width(Length::Fill);
+        if let Some(details) = details {
+            text = text.push(widget::text::monotext(details));
+        }
+
+        Some(
+            widget::container(
+                widget::row::with_capacity(2)
+                    .push(widget::icon::from_name(icon_name).size(16).icon())
+                    .push(text)
+                    .spacing(space_s)
+                    .align_y(Alignment::Center),
+            )
+            .padding(space_s)
+            .width(Length::Fill)
+            .class(cosmic::theme::Container::Custom(Box::new(move |theme| {
+                status_banner_style(theme, is_error)
+            })))
+            .into(),
+        )
+    }
+}
+
+impl From<&ConversionError> for Status {
+    fn from(err: &ConversionError) -> Self {
+        let format = err.format().name();
+        let title = match (err, err.position()) {
+            (ConversionError::Serialize { .. }, _) => fl!("conversion-failed", format = format),
+            (_, Some(position)) => fl!(
+                "invalid-document-at",
+                format = format,
+                line = position.line,
+                column = position.column
+            ),
+            (_, None) => fl!("invalid-document", format = format),
+        };
+        let details = match err {
+            ConversionError::Json { source, .. } => source.to_string(),
+            ConversionError::Yaml { source, .. } => source.to_string(),
+            ConversionError::Xml { source, .. } => source.to_string(),
+            ConversionError::Toml { source, .. } => source.message().to_string(),
+            ConversionError::Serialize { message, .. } => message.clone(),
+        };
+        Status::error(title, details)
+    }
+}
+
+impl From<&base64::DecodeError> for Status {
+    fn from(err: &base64::DecodeError) -> Self {
+        Status::error(fl!("invalid-base64"), err)
+    }
+}
+
+impl From<&DecompressError> for Status {
+    fn from(err: &DecompressError) -> Self {
+        match err {
+            DecompressError::Base64(err) => err.into(),
+            DecompressError::Gzip(err) => Status::error(fl!("invalid-gzip"), err),
+        }
+    }
+}
+
+impl From<&url::DecodeError> for Status {
+    fn from(err: &url::DecodeError) -> Self {
+        Status::error(fl!("invalid-utf-8"), err)
+    }
+}
+
+impl From<&UnixTimeError> for Status {
+    fn from(err: &UnixTimeError) -> Self {
+        let title = match err {
+            UnixTimeError::OutOfRange(_) => fl!("timestamp-out-of-range"),
+            UnixTimeError::InvalidOffset(_) => fl!("invalid-timezone"),
+        };
+        Status::error(title, err)
+    }
+}
+
+impl From<JwtError> for Status {
+    fn from(err: JwtError) -> Self {
+        Status::Error {
+            title: LANGUAGE_LOADER.get_attr("jwt-debugger", err.message_id()),
+            details: None,
+        }
+    }
+}
+
+impl From<KeyError> for Status {
+    fn from(err: KeyError) -> Self {
+        Status::Error {
+            title: LANGUAGE_LOADER.get_attr("key-creator-converter", err.message_id()),
+            details: None,
+        }
+    }
+}
+
+impl From<&StepError> for Status {
+    fn from(err: &StepError) -> Self {
+        match err {
+            StepError::Base64(err) => err.into(),
+            StepError::Gzip(err) => err.into(),
+            StepError::NotText(err) => Status::error(fl!("not-text"), err),
+            StepError::Conversion(err) => err.into(),
+            StepError::Jwt(err) => (*err).into(),
+        }
+    }
+}
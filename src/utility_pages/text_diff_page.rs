@@ -0,0 +1,427 @@
+use {
+    crate::{
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, PageConfig, TextDiffConfig},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            conversion::{self, Conversion, Debounce},
+            diff_view, editor_key_binding,
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
+    },
+    cosmic::{
+        self, Application, Element, Task, iced,
+        iced::{
+            Alignment, Length, Padding, Subscription, clipboard,
+            widget::{column, row},
+        },
+        widget::{
+            self, Id,
+            text_editor::{self, TextEditor},
+        },
+    },
+    dev_toolbox_core::{
+        data_format::{self, DataFormat},
+        diff::{self, DiffLine, DiffOptions, ValueChange},
+    },
+};
+
+const ORIGINAL_EDITOR_ID: &str = "original-editor";
+const CHANGED_EDITOR_ID: &str = "changed-editor";
+const MODES: [&str; 2] = ["lines", "json"];
+
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::TextDiff,
+    id: "text-diff",
+    icon: ToolIcon::Named("view-dual-symbolic"),
+    new_page: || Box::new(TextDiffPage::default()),
+    service: None,
+    takes: |_| false,
+};
+
+#[derive(Debug, Clone)]
+pub enum TextDiffMessage {
+    OriginalEditorAction(text_editor::Action),
+    ChangedEditorAction(text_editor::Action),
+    ModeChanged(usize),
+    IgnoreWhitespaceToggled(bool),
+    InputSettled(u64),
+    Compare,
+    Compared(u64, Result<Comparison, Status>),
+    CancelComparison,
+    Swap,
+    Clear,
+    CopyDiff,
+    PasteText(Id),
+    ReplaceText(Id, String),
+    NoOp,
+}
+
+impl From<TextDiffMessage> for Message {
+    fn from(message: TextDiffMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
+/// The differences between the two texts, by line or by value depending on the mode.
+#[derive(Debug, Clone)]
+pub enum Comparison {
+    Lines(Vec<DiffLine>),
+    Values(Vec<ValueChange>),
+}
+
+#[derive(Default)]
+pub(crate) struct TextDiffPage {
+    original_content: text_editor::Content,
+    changed_content: text_editor::Content,
+    selected_mode: usize,
+    ignore_whitespace: bool,
+    debounce: Debounce,
+    conversion: Conversion,
+    comparison: Option<Comparison>,
+    status: Status,
+}
+
+impl UtilityPage for TextDiffPage {
+    fn get_utility_page(&self) -> Element<'_, Message> {
+        let space_s = cosmic::theme::spacing().space_s;
+
+        let header = widget::row::with_capacity(2)
+            .push(widget::text::title2(fl!("text-diff")))
+            .align_y(Alignment::End)
+            .spacing(space_s);
+
+        let options = widget::row::with_capacity(5)
+            .push(widget::dropdown(
+                MODES
+                    .map(|mode| LANGUAGE_LOADER.get_attr("text-diff", mode))
+                    .to_vec(),
+                Some(self.selected_mode),
+                |selection| Message::from(TextDiffMessage::ModeChanged(selection)),
+            ))
+            .push_maybe((MODES[self.selected_mode] == "lines").then(|| {
+                widget::checkbox(self.ignore_whitespace)
+                    .label(fl!("text-diff", "ignore-whitespace"))
+                    .on_toggle(|ignore_whitespace| {
+                        Message::from(TextDiffMessage::IgnoreWhitespaceToggled(ignore_whitespace))
+                    })
+            }))
+            .push(widget::horizontal_space())
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("object-flip-horizontal-symbolic"))
+                    .on_press(Message::from(TextDiffMessage::Swap)),
+                widget::text(fl!("text-diff", "swap")),
+                widget::tooltip::Position::Bottom,
+            ))
+            .push(
+                widget::button::suggested(fl!("text-diff", "compare"))
+                    .on_press(Message::from(TextDiffMessage::Compare)),
+            )
+            .spacing(space_s)
+            .align_y(Alignment::Center);
+
+        let editors = row![
+            editor_view(
+                fl!("text-diff", "original"),
+                &self.original_content,
+                ORIGINAL_EDITOR_ID,
+                TextDiffMessage::OriginalEditorAction,
+            ),
+            editor_view(
+                fl!("text-diff", "changed"),
+                &self.changed_content,
+                CHANGED_EDITOR_ID,
+                TextDiffMessage::ChangedEditorAction,
+            ),
+        ]
+        .spacing(space_s)
+        .height(Length::FillPortion(2));
+
+        let summary = match &self.comparison {
+            Some(Comparison::Lines(lines)) => diff_view::lines_summary(lines),
+            Some(Comparison::Values(changes)) => diff_view::changes_summary(changes),
+            None => String::new(),
+        };
+        let result_header: Element<'_, Message> = row![
+            widget::text::title4(fl!("text-diff", "differences")),
+            widget::text::body(summary).width(Length::Fill),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("edit-copy-symbolic"))
+                    .on_press(Message::from(TextDiffMessage::CopyDiff)),
+                widget::text(fl!("copy")),
+                widget::tooltip::Position::Bottom,
+            ),
+        ]
+        .spacing(space_s)
+        .align_y(Alignment::Center)
+        .into();
+
+        let result = widget::container(match &self.comparison {
+            Some(Comparison::Lines(lines)) => diff_view::lines_view(lines),
+            Some(Comparison::Values(changes)) => diff_view::changes_view(changes),
+            None => widget::text::body(fl!("text-diff", "empty")).into(),
+        })
+        .height(Length::FillPortion(3));
+
+        let mut page = column![header, options, editors, result_header, result]
+            .spacing(space_s)
+            .height(Length::Fill);
+        if let Some(progress) = self
+            .conversion
+            .view(Message::from(TextDiffMessage::CancelComparison))
+        {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
+    }
+
+    fn handle_message(
+        &mut self,
+        message: Message,
+    ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
+        match message.tool_message::<TextDiffMessage>() {
+            Some(text_diff_message) => match text_diff_message {
+                TextDiffMessage::OriginalEditorAction(action) => {
+                    let is_edit = action.is_edit();
+                    self.original_content.perform(action);
+                    if is_edit {
+                        return self.edited();
+                    }
+                }
+                TextDiffMessage::ChangedEditorAction(action) => {
+                    let is_edit = action.is_edit();
+                    self.changed_content.perform(action);
+                    if is_edit {
+                        return self.edited();
+                    }
+                }
+                TextDiffMessage::ModeChanged(selection) => {
+                    self.selected_mode = selection;
+                    return self.compare_live();
+                }
+                TextDiffMessage::IgnoreWhitespaceToggled(ignore_whitespace) => {
+                    self.ignore_whitespace = ignore_whitespace;
+                    return self.compare_live();
+                }
+                TextDiffMessage::InputSettled(edit) => {
+                    if self.debounce.is_settled(edit) {
+                        return self.compare_live();
+                    }
+                }
+                TextDiffMessage::Compare => {
+                    return self.compare();
+                }
+                TextDiffMessage::Compared(id, result) => {
+                    if self.conversion.finish(id) {
+                        match result {
+                            Ok(comparison) => {
+                                self.comparison = Some(comparison);
+                                self.status = Status::Idle;
+                            }
+                            Err(status) => {
+                                self.comparison = None;
+                                self.status = status;
+                            }
+                        }
+                    }
+                }
+                TextDiffMessage::CancelComparison => {
+                    self.conversion.cancel();
+                }
+                TextDiffMessage::Swap => {
+                    std::mem::swap(&mut self.original_content, &mut self.changed_content);
+                    return self.compare();
+                }
+                TextDiffMessage::Clear => {
+                    self.conversion.cancel();
+                    self.original_content = text_editor::Content::default();
+                    self.changed_content = text_editor::Content::default();
+                    self.comparison = None;
+                    self.status = Status::Idle;
+                }
+                TextDiffMessage::CopyDiff => {
+                    return clipboard::write(match &self.comparison {
+                        Some(Comparison::Lines(lines)) => diff::unified(lines),
+                        Some(Comparison::Values(changes)) => diff::changes_text(changes),
+                        None => String::new(),
+                    });
+                }
+                TextDiffMessage::PasteText(id) => {
+                    return clipboard::read().map(move |optional_data| match optional_data {
+                        Some(data) => cosmic::Action::App(Message::from(
+                            TextDiffMessage::ReplaceText(id.clone(), data),
+                        )),
+                        None => cosmic::Action::App(Message::from(TextDiffMessage::NoOp)),
+                    });
+                }
+                TextDiffMessage::ReplaceText(id, text) => {
+                    if id == Id::new(ORIGINAL_EDITOR_ID) {
+                        self.original_content = text_editor::Content::with_text(&text);
+                    } else if id == Id::new(CHANGED_EDITOR_ID) {
+                        self.changed_content = text_editor::Content::with_text(&text);
+                    }
+                    // Both texts are needed before there is anything to compare.
+                    if !self.original_content.text().is_empty()
+                        && !self.changed_content.text().is_empty()
+                    {
+                        return self.compare_live();
+                    }
+                }
+                TextDiffMessage::NoOp => {}
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
+        }
+        Task::none()
+    }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.text_diff;
+        self.selected_mode = config.mode.min(MODES.len() - 1);
+        self.ignore_whitespace = config.ignore_whitespace;
+        if !config.original.is_empty() || !config.changed.is_empty() {
+            self.original_content = text_editor::Content::with_text(&config.original);
+            self.changed_content = text_editor::Content::with_text(&config.changed);
+            return self.compare_live();
+        }
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::TextDiff(TextDiffConfig {
+            mode: self.selected_mode,
+            ignore_whitespace: self.ignore_whitespace,
+            original: self.original_content.text(),
+            changed: self.changed_content.text(),
+        })
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        Some(Message::from(match action {
+            EditAction::Convert => TextDiffMessage::Compare,
+            EditAction::Swap => TextDiffMessage::Swap,
+            EditAction::CopyOutput => TextDiffMessage::CopyDiff,
+            EditAction::PasteInput => TextDiffMessage::PasteText(Id::new(ORIGINAL_EDITOR_ID)),
+            EditAction::Clear => TextDiffMessage::Clear,
+        }))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        MODES
+            .iter()
+            .enumerate()
+            .map(|(index, mode)| {
+                Command::new(
+                    fl!(
+                        "command-palette",
+                        "compare",
+                        mode = LANGUAGE_LOADER.get_attr("text-diff", mode)
+                    ),
+                    [
+                        TextDiffMessage::ModeChanged(index),
+                        TextDiffMessage::Compare,
+                    ]
+                    .map(Message::from),
+                )
+            })
+            .collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+}
+
+impl TextDiffPage {
+    /// Compares the texts again once they stop changing, if they were compared before.
+    fn edited(&mut self) -> Task<cosmic::Action<Message>> {
+        if self.comparison.is_none() {
+            return Task::none();
+        }
+        self.debounce
+            .edit(|edit| Message::from(TextDiffMessage::InputSettled(edit)))
+    }
+
+    /// Compares the texts in the background, replacing a comparison that is still running.
+    fn compare(&mut self) -> Task<cosmic::Action<Message>> {
+        let original = self.original_content.text();
+        let changed = self.changed_content.text();
+        let options = DiffOptions {
+            ignore_whitespace: self.ignore_whitespace,
+        };
+        let by_value = MODES[self.selected_mode] == "json";
+        self.conversion.start(
+            move || {
+                if !by_value {
+                    return Ok(Comparison::Lines(diff::diff_lines(
+                        &original, &changed, options,
+                    )));
+                }
+                let original = data_format::parse(&original, DataFormat::Json)
+                    .map_err(|err| Status::error(fl!("text-diff", "invalid-original"), err))?;
+                let changed = data_format::parse(&changed, DataFormat::Json)
+                    .map_err(|err| Status::error(fl!("text-diff", "invalid-changed"), err))?;
+                Ok(Comparison::Values(diff::diff_values(&original, &changed)))
+            },
+            |id, result| Message::from(TextDiffMessage::Compared(id, result.flatten())),
+        )
+    }
+
+    /// Compares the texts after a change, unless they are too large to compare on every change.
+    fn compare_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.original_content.text())
+            && conversion::is_live(&self.changed_content.text())
+        {
+            return self.compare();
+        }
+        self.conversion.cancel();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
+    }
+}
+
+/// A text to compare, titled with its side.
+fn editor_view<'a>(
+    title: String,
+    content: &'a text_editor::Content,
+    id: &'static str,
+    on_action: fn(text_editor::Action) -> TextDiffMessage,
+) -> Element<'a, Message> {
+    let space_s = cosmic::theme::spacing().space_s;
+
+    let editor_header = row![
+        widget::text::title4(title)
+            .width(Length::Fill)
+            .align_x(Alignment::Start),
+        widget::tooltip(
+            widget::button::icon(widget::icon::from_name("edit-paste-symbolic"))
+                .on_press(Message::from(TextDiffMessage::PasteText(Id::new(id)))),
+            widget::text(fl!("paste")),
+            widget::tooltip::Position::Bottom,
+        ),
+    ]
+    .align_y(Alignment::Center);
+
+    let editor = TextEditor::new(content)
+        .padding(Padding::new(12.0))
+        .height(Length::Fill)
+        .class(cosmic::theme::iced::TextEditor::Custom(Box::new(
+            text_editor_class,
+        )))
+        .wrapping(iced::core::text::Wrapping::WordOrGlyph)
+        .on_action(move |action| Message::from(on_action(action)))
+        .key_binding(editor_key_binding);
+
+    column![editor_header, editor]
+        .spacing(space_s)
+        .width(Length::Fill)
+        .into()
+}
@@ -1,9 +1,19 @@
 use {
     crate::{
-        Message, app::AppModel, class::text_input_style, fl, i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        Message,
+        app::{AppModel, Page},
+        class::text_input_style,
+        command_palette::Command,
+        config::{Config, PageConfig, UnixTimeConverterConfig},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
-    chrono::{DateTime, Datelike, FixedOffset, Local},
+    chrono::FixedOffset,
     cosmic::{
         self, Application, Element, Task,
         iced::{
@@ -12,6 +22,14 @@ use {
         },
         widget::{self, Id, TextInput, text_input},
     },
+    dev_toolbox_core::{
+        detect::ContentKind,
+        service::Tool,
+        unix_time::{
+            TimestampUnit, UTC_OFFSET_SECONDS, find_current_timezone_position, format_offset,
+            format_timestamp,
+        },
+    },
 };
 
 const UNIX_TEXT_ID: &str = "unix-text";
@@ -21,47 +39,19 @@ const DMY_TEXT_ID: &str = "dmy-text";
 const HR_TEXT_ID: &str = "hr-text";
 const TIMESTAMP_TYPES: [&str; 2] = ["epoch-seconds", "epoch-milliseconds"];
 
-/// Offset seconds east of UTC
-const UTC_OFFSET_SECONDS: &[i32; 38] = &[
-    -12 * 3600,
-    -11 * 3600,
-    -10 * 3600,
-    -9 * 3600 - 30 * 60,
-    -9 * 3600,
-    -8 * 3600,
-    -7 * 3600,
-    -6 * 3600,
-    -5 * 3600,
-    -4 * 3600,
-    -3 * 3600 - 30 * 60,
-    -3 * 3600,
-    -2 * 3600,
-    -1 * 3600,
-    0,
-    1 * 3600,
-    2 * 3600,
-    3 * 3600,
-    3 * 3600 + 30 * 60,
-    4 * 3600,
-    4 * 3600 + 30 * 60,
-    5 * 3600,
-    5 * 3600 + 30 * 60,
-    5 * 3600 + 45 * 60,
-    6 * 3600,
-    6 * 3600 + 30 * 60,
-    7 * 3600,
-    8 * 3600,
-    8 * 3600 + 45 * 60,
-    9 * 3600,
-    9 * 3600 + 30 * 60,
-    10 * 3600,
-    10 * 3600 + 30 * 60,
-    11 * 3600,
-    12 * 3600,
-    12 * 3600 + 45 * 60,
-    13 * 3600,
-    14 * 3600,
-];
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::UnixTimeConverter,
+    id: "unix-time-converter",
+    icon: ToolIcon::Named("accessories-clock-symbolic"),
+    new_page: || Box::new(UnixTimeConverterPage::default()),
+    service: Some(Tool::Time),
+    takes: |kind| {
+        matches!(
+            kind,
+            ContentKind::UnixSeconds | ContentKind::UnixMilliseconds
+        )
+    },
+};
 
 #[derive(Debug, Clone)]
 pub enum UnixTimeConverterMessage {
@@ -75,6 +65,12 @@ pub enum UnixTimeConverterMessage {
     ReadOnlyInput(Id),
 }
 
+impl From<UnixTimeConverterMessage> for Message {
+    fn from(message: UnixTimeConverterMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 pub(crate) struct UnixTimeConverterPage {
     text: String,
     iso_8601_time: String,
@@ -84,6 +80,7 @@ pub(crate) struct UnixTimeConverterPage {
     timestamp_type: usize,
     selected_timezone: usize,
     timezones: Vec<String>,
+    status: Status,
 }
 
 impl Default for UnixTimeConverterPage {
@@ -97,26 +94,17 @@ impl Default for UnixTimeConverterPage {
             timestamp_type: 0,
             selected_timezone: find_current_timezone_position(),
             timezones: UTC_OFFSET_SECONDS
-                .map(|offset| format_offset(FixedOffset::east_opt(offset.clone()).unwrap()))
+                .map(|offset| format_offset(FixedOffset::east_opt(offset).unwrap()))
                 .to_vec(),
+            status: Status::default(),
         }
     }
 }
 
-fn find_current_timezone_position() -> usize {
-    let current_offset = chrono::offset::Local::now().offset().local_minus_utc();
-    match UTC_OFFSET_SECONDS.binary_search(&current_offset) {
-        Ok(position) => position,
-        Err(position) => position,
-    }
-}
-
 fn clipboard_button(id: &str) -> Element<'_, Message> {
     widget::tooltip(
         widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-            Message::UnixTimeConverterMessage(UnixTimeConverterMessage::CopyText(Id::new(
-                id.to_string(),
-            ))),
+            Message::from(UnixTimeConverterMessage::CopyText(Id::new(id.to_string()))),
         ),
         widget::text(fl!("copy")),
         widget::tooltip::Position::Bottom,
@@ -124,18 +112,6 @@ fn clipboard_button(id: &str) -> Element<'_, Message> {
     .into()
 }
 
-pub fn format_offset(offset: FixedOffset) -> String {
-    let secs = offset.local_minus_utc();
-
-    let sign = if secs >= 0 { '+' } else { '-' };
-    let secs = secs.abs();
-
-    let hours = secs / 3600;
-    let minutes = (secs % 3600) / 60;
-
-    format!("UTC{}{:02}:{:02}", sign, hours, minutes)
-}
-
 impl UtilityPage for UnixTimeConverterPage {
     fn get_utility_page(&self) -> Element<'_, Message> {
         let space_s = cosmic::theme::spacing().space_s;
@@ -159,9 +135,7 @@ impl UtilityPage for UnixTimeConverterPage {
                     .to_vec(),
                 Some(self.timestamp_type),
                 |selection| {
-                    Message::UnixTimeConverterMessage(
-                        UnixTimeConverterMessage::TimestampTypeChanged(selection),
-                    )
+                    Message::from(UnixTimeConverterMessage::TimestampTypeChanged(selection))
                 },
             ),
         )
@@ -172,19 +146,14 @@ impl UtilityPage for UnixTimeConverterPage {
             .style(text_input_style())
             .trailing_icon(
                 row![
-                    widget::button::text(fl!("unix-time-converter", "now")).on_press(
-                        Message::UnixTimeConverterMessage(UnixTimeConverterMessage::SetCurrentTime)
-                    ),
+                    widget::button::text(fl!("unix-time-converter", "now"))
+                        .on_press(Message::from(UnixTimeConverterMessage::SetCurrentTime)),
                     clipboard_button(UNIX_TEXT_ID),
                 ]
                 .into(),
             )
-            .on_input(|text| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::InputTextChanged(text))
-            })
-            .on_submit(|_| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::UnixTimestamp())
-            });
+            .on_input(|text| Message::from(UnixTimeConverterMessage::InputTextChanged(text)))
+            .on_submit(|_| Message::from(UnixTimeConverterMessage::UnixTimestamp()));
 
         let output_header: Element<'_, Message> = widget::text::title4(fl!("output"))
             .width(Length::Fill)
@@ -194,9 +163,7 @@ impl UtilityPage for UnixTimeConverterPage {
         let timezone_option: Element<'_, Message> = widget::settings::item(
             fl!("unix-time-converter", "timezone"),
             widget::dropdown(&self.timezones, Some(self.selected_timezone), |selection| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::TimezoneChanged(
-                    selection,
-                ))
+                Message::from(UnixTimeConverterMessage::TimezoneChanged(selection))
             }),
         )
         .into();
@@ -208,14 +175,14 @@ impl UtilityPage for UnixTimeConverterPage {
             .editing(false)
             .trailing_icon(clipboard_button(ISO_8601_TEXT_ID))
             .on_input(|_| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
+                Message::from(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
                     ISO_8601_TEXT_ID,
                 )))
             })
             .select_on_focus(true)
-            .on_focus(Message::UnixTimeConverterMessage(
-                UnixTimeConverterMessage::SelectAllTextField(Id::new(ISO_8601_TEXT_ID)),
-            ));
+            .on_focus(Message::from(UnixTimeConverterMessage::SelectAllTextField(
+                Id::new(ISO_8601_TEXT_ID),
+            )));
 
         let email_text = widget::text_input::text_input("", &self.email_time)
             .id(Id::new(EMAIL_TEXT_ID))
@@ -224,14 +191,14 @@ impl UtilityPage for UnixTimeConverterPage {
             .editing(false)
             .trailing_icon(clipboard_button(EMAIL_TEXT_ID))
             .on_input(|_| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
+                Message::from(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
                     EMAIL_TEXT_ID,
                 )))
             })
             .select_on_focus(true)
-            .on_focus(Message::UnixTimeConverterMessage(
-                UnixTimeConverterMessage::SelectAllTextField(Id::new(EMAIL_TEXT_ID)),
-            ));
+            .on_focus(Message::from(UnixTimeConverterMessage::SelectAllTextField(
+                Id::new(EMAIL_TEXT_ID),
+            )));
 
         let dmy_text = widget::text_input::text_input("", &self.dmy_time)
             .id(Id::new(DMY_TEXT_ID))
@@ -240,14 +207,14 @@ impl UtilityPage for UnixTimeConverterPage {
             .editing(false)
             .trailing_icon(clipboard_button(DMY_TEXT_ID))
             .on_input(|_| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
+                Message::from(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
                     DMY_TEXT_ID,
                 )))
             })
             .select_on_focus(true)
-            .on_focus(Message::UnixTimeConverterMessage(
-                UnixTimeConverterMessage::SelectAllTextField(Id::new(DMY_TEXT_ID)),
-            ));
+            .on_focus(Message::from(UnixTimeConverterMessage::SelectAllTextField(
+                Id::new(DMY_TEXT_ID),
+            )));
 
         let hr_text = widget::text_input::text_input("", &self.hr_time)
             .id(Id::new(HR_TEXT_ID))
@@ -256,16 +223,14 @@ impl UtilityPage for UnixTimeConverterPage {
             .editing(false)
             .trailing_icon(clipboard_button(HR_TEXT_ID))
             .on_input(|_| {
-                Message::UnixTimeConverterMessage(UnixTimeConverterMessage::ReadOnlyInput(Id::new(
-                    HR_TEXT_ID,
-                )))
+                Message::from(UnixTimeConverterMessage::ReadOnlyInput(Id::new(HR_TEXT_ID)))
             })
             .select_on_focus(true)
-            .on_focus(Message::UnixTimeConverterMessage(
-                UnixTimeConverterMessage::SelectAllTextField(Id::new(HR_TEXT_ID)),
-            ));
+            .on_focus(Message::from(UnixTimeConverterMessage::SelectAllTextField(
+                Id::new(HR_TEXT_ID),
+            )));
 
-        column![
+        let mut page = column![
             header,
             input_header,
             timestamp_option,
@@ -278,113 +243,162 @@ impl UtilityPage for UnixTimeConverterPage {
             hr_text
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::UnixTimeConverterMessage(unix_time_converter_message) => {
-                match unix_time_converter_message {
-                    UnixTimeConverterMessage::InputTextChanged(text) => {
-                        self.text = text;
-                    }
-                    UnixTimeConverterMessage::TimestampTypeChanged(selection) => {
-                        if let Ok(time) = self.text.parse::<i64>() {
-                            let new_time = match self.timestamp_type {
-                                0 => {
-                                    if selection == 0 {
-                                        time
-                                    } else {
-                                        time * 1000
-                                    }
-                                }
-                                1.. => {
-                                    if selection == 0 {
-                                        time / 1000
-                                    } else {
-                                        time
-                                    }
-                                }
-                            };
-                            self.text = new_time.to_string();
-                        }
-                        self.timestamp_type = selection;
-                        self.convert_unix_timestamp();
-                    }
-                    UnixTimeConverterMessage::TimezoneChanged(selection) => {
-                        self.selected_timezone = selection;
-                        self.convert_unix_timestamp();
-                    }
-                    UnixTimeConverterMessage::SetCurrentTime => {
-                        self.text = match self.timestamp_type {
-                            0 => Local::now().timestamp(),
-                            1.. => Local::now().timestamp_millis(),
-                        }
-                        .to_string();
-                        self.convert_unix_timestamp();
-                    }
-                    UnixTimeConverterMessage::UnixTimestamp() => {
-                        self.convert_unix_timestamp();
-                    }
-                    UnixTimeConverterMessage::SelectAllTextField(id) => {
-                        return text_input::select_all(id);
-                    }
-                    UnixTimeConverterMessage::CopyText(id) => {
-                        if id == Id::new(UNIX_TEXT_ID) {
-                            return clipboard::write(self.text.clone());
-                        } else if id == Id::new(EMAIL_TEXT_ID) {
-                            return clipboard::write(self.email_time.clone());
-                        } else if id == Id::new(ISO_8601_TEXT_ID) {
-                            return clipboard::write(self.iso_8601_time.clone());
-                        }
+        match message.tool_message::<UnixTimeConverterMessage>() {
+            Some(unix_time_converter_message) => match unix_time_converter_message {
+                UnixTimeConverterMessage::InputTextChanged(text) => {
+                    self.text = text;
+                }
+                UnixTimeConverterMessage::TimestampTypeChanged(selection) => {
+                    if let Ok(time) = self.text.parse::<i64>() {
+                        self.text = TimestampUnit::ALL[self.timestamp_type]
+                            .convert(time, TimestampUnit::ALL[selection])
+                            .to_string();
                     }
-                    UnixTimeConverterMessage::ReadOnlyInput(id) => {
-                        return text_input::move_cursor_to_end(id);
+                    self.timestamp_type = selection;
+                    self.convert_unix_timestamp();
+                }
+                UnixTimeConverterMessage::TimezoneChanged(selection) => {
+                    self.selected_timezone = selection;
+                    self.convert_unix_timestamp();
+                }
+                UnixTimeConverterMessage::SetCurrentTime => {
+                    self.text = TimestampUnit::ALL[self.timestamp_type].now().to_string();
+                    self.convert_unix_timestamp();
+                }
+                UnixTimeConverterMessage::UnixTimestamp() => {
+                    self.convert_unix_timestamp();
+                }
+                UnixTimeConverterMessage::SelectAllTextField(id) => {
+                    return text_input::select_all(id);
+                }
+                UnixTimeConverterMessage::CopyText(id) => {
+                    if id == Id::new(UNIX_TEXT_ID) {
+                        return clipboard::write(self.text.clone());
+                    } else if id == Id::new(EMAIL_TEXT_ID) {
+                        return clipboard::write(self.email_time.clone());
+                    } else if id == Id::new(ISO_8601_TEXT_ID) {
+                        return clipboard::write(self.iso_8601_time.clone());
                     }
                 }
-            }
-            _ => {
-                println!("Wrong message type");
-            }
+                UnixTimeConverterMessage::ReadOnlyInput(id) => {
+                    return text_input::move_cursor_to_end(id);
+                }
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
-}
 
-impl UnixTimeConverterPage {
-    fn convert_unix_timestamp(&mut self) {
-        let _ = self.text.parse::<i64>().inspect(|time| {
-            match self.timestamp_type {
-                0 => DateTime::from_timestamp_secs(*time),
-                1.. => DateTime::from_timestamp_millis(*time),
-            }
-            .inspect(|date_time| {
-                let date_time_with_offset = date_time.with_timezone(
-                    &FixedOffset::east_opt(UTC_OFFSET_SECONDS[self.selected_timezone]).unwrap(),
-                );
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.unix_time_converter;
+        self.timestamp_type = config.timestamp_type.min(TIMESTAMP_TYPES.len() - 1);
+        if let Some(position) = config.utc_offset_seconds.and_then(|utc_offset_seconds| {
+            UTC_OFFSET_SECONDS
+                .iter()
+                .position(|offset| *offset == utc_offset_seconds)
+        }) {
+            self.selected_timezone = position;
+        }
+        if !config.timestamp.is_empty() {
+            self.text = config.timestamp.clone();
+            self.convert_unix_timestamp();
+        }
+        Task::none()
+    }
 
-                self.iso_8601_time = date_time_with_offset.to_rfc3339();
+    fn page_config(&self) -> PageConfig {
+        PageConfig::UnixTimeConverter(UnixTimeConverterConfig {
+            timestamp_type: self.timestamp_type,
+            // Keep following the local timezone unless another one was picked.
+            utc_offset_seconds: (self.selected_timezone != find_current_timezone_position())
+                .then(|| UTC_OFFSET_SECONDS[self.selected_timezone]),
+            timestamp: self.text.clone(),
+        })
+    }
 
-                if date_time_with_offset.year() >= 0 && date_time_with_offset.year() < 10000 {
-                    self.email_time = date_time_with_offset.to_rfc2822();
-                } else {
-                    self.email_time = String::new();
-                }
+    fn paste_detected(
+        &mut self,
+        content: String,
+        kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        self.timestamp_type = match kind {
+            ContentKind::UnixMilliseconds => 1,
+            _ => 0,
+        };
+        self.text = content.trim().to_string();
+        self.convert_unix_timestamp();
+        Task::none()
+    }
+
+    fn prefill(&mut self, _operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        self.text = input.trim().to_string();
+        self.convert_unix_timestamp();
+        Task::none()
+    }
 
-                self.dmy_time.clear();
-                let _ = date_time_with_offset
-                    .format("%d/%m/%Y")
-                    .write_to(&mut self.dmy_time);
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        (action == EditAction::Convert)
+            .then_some(Message::from(UnixTimeConverterMessage::UnixTimestamp()))
+    }
 
-                self.hr_time.clear();
-                let _ = date_time_with_offset
-                    .format("%b %e, %l:%M %p")
-                    .write_to(&mut self.hr_time);
-            });
-        });
+    fn commands(&self) -> Vec<Command> {
+        let mut commands: Vec<Command> = TIMESTAMP_TYPES
+            .into_iter()
+            .enumerate()
+            .map(|(timestamp_type, id)| {
+                Command::new(
+                    LANGUAGE_LOADER.get_attr("unix-time-converter", id),
+                    [Message::from(
+                        UnixTimeConverterMessage::TimestampTypeChanged(timestamp_type),
+                    )],
+                )
+            })
+            .collect();
+        commands.push(Command::new(
+            fl!("unix-time-converter", "now"),
+            [Message::from(UnixTimeConverterMessage::SetCurrentTime)],
+        ));
+        commands
+    }
+}
+
+impl UnixTimeConverterPage {
+    fn convert_unix_timestamp(&mut self) {
+        let time = match self.text.trim().parse::<i64>() {
+            Ok(time) => time,
+            Err(_) if self.text.trim().is_empty() => {
+                self.status = Status::Idle;
+                return;
+            }
+            Err(err) => {
+                self.status = Status::error(fl!("invalid-timestamp"), err);
+                return;
+            }
+        };
+        match format_timestamp(
+            time,
+            TimestampUnit::ALL[self.timestamp_type],
+            UTC_OFFSET_SECONDS[self.selected_timezone],
+        ) {
+            Ok(formatted_time) => {
+                self.iso_8601_time = formatted_time.iso_8601;
+                self.email_time = formatted_time.email;
+                self.dmy_time = formatted_time.dmy;
+                self.hr_time = formatted_time.human_readable;
+                self.status = Status::Idle;
+            }
+            Err(err) => self.status = Status::from(&err),
+        }
     }
 }
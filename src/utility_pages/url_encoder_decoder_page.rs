@@ -1,44 +1,95 @@
 use {
     crate::{
-        Message, app::AppModel, class::text_editor_class, fl, i18n::LANGUAGE_LOADER,
-        utility_pages::UtilityPage,
+        Message,
+        app::{AppModel, Page},
+        class::text_editor_class,
+        command_palette::Command,
+        config::{Config, PageConfig, UrlEncoderDecoderConfig},
+        context_drawer_pages::history_page::{self, Recording},
+        fl,
+        i18n::LANGUAGE_LOADER,
+        utility_pages::{
+            EditAction, UtilityPage,
+            conversion::{self, Conversion, Debounce},
+            editor_key_binding,
+            files::{self, OpenedFile},
+            registry::{ToolIcon, ToolInfo},
+            status::Status,
+        },
     },
     cosmic::{
         self, Application, Element, Task,
         iced::{
-            self, Alignment, Length, Padding, clipboard,
-            keyboard::{Key, key},
+            self, Alignment, Length, Padding, Subscription, clipboard,
             widget::{column, row},
         },
         widget::{
             self, Id,
-            text_editor::{self, Binding, TextEditor},
+            text_editor::{self, TextEditor},
         },
     },
-    std::sync::Arc,
-    urlencoding,
+    dev_toolbox_core::{
+        detect::ContentKind,
+        service::Tool,
+        url::{decode, encode, is_round_trip},
+    },
+    std::{path::PathBuf, sync::Arc},
 };
 
 const INPUT_EDITOR_ID: &str = "input-editor";
 const OUTPUT_EDITOR_ID: &str = "output-editor";
 const OPERATIONS: [&str; 2] = ["encode", "decode"];
 
+pub(crate) const TOOL: ToolInfo = ToolInfo {
+    page: Page::UrlEncoderDecoder,
+    id: "url-encoder-decoder",
+    icon: ToolIcon::Named("link-symbolic"),
+    new_page: || Box::new(UrlEncoderDecoderPage::default()),
+    service: Some(Tool::Url),
+    takes: |kind| kind == ContentKind::PercentEncoded,
+};
+
 #[derive(Debug, Clone)]
 pub enum UrlEncoderDecoderMessage {
     InputEditorAction(text_editor::Action),
     OutputEditorAction(text_editor::Action),
     OperationChanged(usize),
+    LiveConvertToggled(bool),
+    InputSettled(u64),
     ConvertInput,
+    /// The output and whether it converts back to the input.
+    Converted(u64, Result<(String, bool), Status>),
+    CancelConversion,
+    Swap,
+    Clear,
     CopyText(Id),
     PasteText(Id),
     ReplaceText(Id, String),
+    OpenFile,
+    FileDropped(PathBuf),
+    FileOpened(Result<OpenedFile, String>),
+    SaveFile,
+    FileSaved(Result<(), String>),
     NoOp,
 }
 
+impl From<UrlEncoderDecoderMessage> for Message {
+    fn from(message: UrlEncoderDecoderMessage) -> Self {
+        TOOL.message(message)
+    }
+}
+
 pub(crate) struct UrlEncoderDecoderPage {
     input_content: text_editor::Content,
     output_content: text_editor::Content,
     selected_operation: usize,
+    live_convert: bool,
+    debounce: Debounce,
+    conversion: Conversion,
+    recording: Recording,
+    /// Whether the latest output converts back to the input.
+    round_trip: Option<bool>,
+    status: Status,
 }
 
 impl Default for UrlEncoderDecoderPage {
@@ -47,6 +98,12 @@ impl Default for UrlEncoderDecoderPage {
             input_content: text_editor::Content::default(),
             output_content: text_editor::Content::default(),
             selected_operation: 1,
+            live_convert: false,
+            debounce: Debounce::default(),
+            conversion: Conversion::default(),
+            recording: Recording::default(),
+            round_trip: None,
+            status: Status::default(),
         }
     }
 }
@@ -64,25 +121,33 @@ impl UtilityPage for UrlEncoderDecoderPage {
             widget::text::title4(fl!("input"))
                 .width(Length::Fill)
                 .align_x(Alignment::Start),
-            widget::button::text(fl!("convert")).on_press(Message::UrlEncoderDecoderMessage(
-                UrlEncoderDecoderMessage::ConvertInput
-            )),
+            widget::checkbox(self.live_convert)
+                .label(fl!("live-convert"))
+                .on_toggle(|live_convert| {
+                    Message::from(UrlEncoderDecoderMessage::LiveConvertToggled(live_convert))
+                }),
+            widget::button::text(fl!("convert"))
+                .on_press(Message::from(UrlEncoderDecoderMessage::ConvertInput)),
             widget::dropdown(
                 OPERATIONS
                     .map(|operation| LANGUAGE_LOADER.get(operation))
                     .to_vec(),
                 Some(self.selected_operation),
                 |selection| {
-                    Message::UrlEncoderDecoderMessage(UrlEncoderDecoderMessage::OperationChanged(
-                        selection,
-                    ))
+                    Message::from(UrlEncoderDecoderMessage::OperationChanged(selection))
                 },
             ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+                    .on_press(Message::from(UrlEncoderDecoderMessage::OpenFile)),
+                widget::text(fl!("open-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-paste-symbolic")).on_press(
-                    Message::UrlEncoderDecoderMessage(UrlEncoderDecoderMessage::PasteText(
-                        Id::new(INPUT_EDITOR_ID)
-                    ),)
+                    Message::from(UrlEncoderDecoderMessage::PasteText(Id::new(
+                        INPUT_EDITOR_ID
+                    )),)
                 ),
                 widget::text(fl!("paste")),
                 widget::tooltip::Position::Bottom,
@@ -97,28 +162,37 @@ impl UtilityPage for UrlEncoderDecoderPage {
                 text_editor_class,
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::UrlEncoderDecoderMessage(UrlEncoderDecoderMessage::InputEditorAction(
-                    action,
-                ))
-            })
-            .key_binding(|key_press| {
-                if key_press.key == Key::Named(key::Named::Tab)
-                    && matches!(key_press.status, text_editor::Status::Focused { .. })
-                {
-                    return Some(Binding::Insert('\t'));
-                }
-                return Binding::from_key_press(key_press);
-            })
+            .on_action(|action| Message::from(UrlEncoderDecoderMessage::InputEditorAction(action)))
+            .key_binding(editor_key_binding)
             .into();
+        let input_editor = files::drop_target(
+            input_editor,
+            |path| Message::from(UrlEncoderDecoderMessage::FileDropped(path)),
+            Message::from(UrlEncoderDecoderMessage::NoOp),
+        );
 
         let output_header: Element<'_, Message> = row![
-            widget::text::title4(fl!("output"))
-                .width(Length::Fill)
-                .align_x(Alignment::Start),
+            widget::row::with_capacity(2)
+                .push(widget::text::title4(fl!("output")))
+                .push_maybe(conversion::round_trip_view(self.round_trip))
+                .spacing(space_s)
+                .align_y(Alignment::Center)
+                .width(Length::Fill),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("object-flip-vertical-symbolic"))
+                    .on_press(Message::from(UrlEncoderDecoderMessage::Swap)),
+                widget::text(fl!("swap")),
+                widget::tooltip::Position::Bottom,
+            ),
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name("document-save-as-symbolic"))
+                    .on_press(Message::from(UrlEncoderDecoderMessage::SaveFile)),
+                widget::text(fl!("save-as-file")),
+                widget::tooltip::Position::Bottom,
+            ),
             widget::tooltip(
                 widget::button::icon(widget::icon::from_name("edit-copy-symbolic")).on_press(
-                    Message::UrlEncoderDecoderMessage(UrlEncoderDecoderMessage::CopyText(Id::new(
+                    Message::from(UrlEncoderDecoderMessage::CopyText(Id::new(
                         OUTPUT_EDITOR_ID
                     )),),
                 ),
@@ -136,14 +210,10 @@ impl UtilityPage for UrlEncoderDecoderPage {
                 text_editor_class,
             )))
             .wrapping(iced::core::text::Wrapping::WordOrGlyph)
-            .on_action(|action| {
-                Message::UrlEncoderDecoderMessage(UrlEncoderDecoderMessage::OutputEditorAction(
-                    action,
-                ))
-            })
+            .on_action(|action| Message::from(UrlEncoderDecoderMessage::OutputEditorAction(action)))
             .into();
 
-        column![
+        let mut page = column![
             header,
             input_header,
             input_editor,
@@ -151,106 +221,299 @@ impl UtilityPage for UrlEncoderDecoderPage {
             output_editor
         ]
         .spacing(space_s)
-        .height(Length::Fill)
-        .into()
+        .height(Length::Fill);
+        if let Some(progress) = self
+            .conversion
+            .view(Message::from(UrlEncoderDecoderMessage::CancelConversion))
+        {
+            page = page.push(progress);
+        }
+        if let Some(status_banner) = self.status.view() {
+            page = page.push(status_banner);
+        }
+        page.into()
     }
 
     fn handle_message(
         &mut self,
         message: Message,
     ) -> Task<cosmic::Action<<AppModel as Application>::Message>> {
-        match message {
-            Message::UrlEncoderDecoderMessage(data_converter_formatter_message) => {
-                match data_converter_formatter_message {
-                    UrlEncoderDecoderMessage::InputEditorAction(action) => {
-                        self.input_content.perform(action);
+        match message.tool_message::<UrlEncoderDecoderMessage>() {
+            Some(data_converter_formatter_message) => match data_converter_formatter_message {
+                UrlEncoderDecoderMessage::InputEditorAction(action) => {
+                    let is_edit = action.is_edit();
+                    self.input_content.perform(action);
+                    if is_edit {
+                        self.recording.clear();
                     }
-                    UrlEncoderDecoderMessage::OutputEditorAction(action) => {
-                        if !action.is_edit() {
-                            self.output_content.perform(action);
-                        }
+                    if is_edit && self.live_convert {
+                        return self.debounce.edit(|edit| {
+                            Message::from(UrlEncoderDecoderMessage::InputSettled(edit))
+                        });
                     }
-                    UrlEncoderDecoderMessage::OperationChanged(selection) => {
-                        if selection != self.selected_operation {
-                            self.selected_operation = selection;
-                            self.convert_input();
-                        }
+                }
+                UrlEncoderDecoderMessage::LiveConvertToggled(live_convert) => {
+                    self.live_convert = live_convert;
+                    if live_convert {
+                        return self.convert_live();
                     }
-                    UrlEncoderDecoderMessage::ConvertInput => {
-                        self.convert_input();
+                }
+                UrlEncoderDecoderMessage::InputSettled(edit) => {
+                    if self.live_convert && self.debounce.is_settled(edit) {
+                        return self.convert_live();
                     }
-                    UrlEncoderDecoderMessage::CopyText(id) => {
-                        let mut to_copy: String = String::new();
-                        if id == Id::new(INPUT_EDITOR_ID) {
-                            to_copy = self.input_content.text();
-                        } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            to_copy = self.output_content.text();
+                }
+                UrlEncoderDecoderMessage::OutputEditorAction(action) => {
+                    if !action.is_edit() {
+                        self.output_content.perform(action);
+                    }
+                }
+                UrlEncoderDecoderMessage::OperationChanged(selection) => {
+                    if selection != self.selected_operation {
+                        self.selected_operation = selection;
+                        return self.convert_live();
+                    }
+                }
+                UrlEncoderDecoderMessage::ConvertInput => {
+                    self.recording.request();
+                    return self.convert_input();
+                }
+                UrlEncoderDecoderMessage::Converted(id, result) => {
+                    if self.conversion.finish(id) {
+                        match result {
+                            Ok((output, round_trip)) => {
+                                self.round_trip = Some(round_trip);
+                                self.output_content.perform(text_editor::Action::SelectAll);
+                                self.output_content
+                                    .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
+                                self.output_content.perform(text_editor::Action::Edit(
+                                    text_editor::Edit::Paste(Arc::new(output)),
+                                ));
+                                self.status = Status::Idle;
+                                if self.recording.finish(true) {
+                                    return history_page::record(
+                                        Page::UrlEncoderDecoder,
+                                        self.history_entry(),
+                                    );
+                                }
+                            }
+                            Err(status) => {
+                                self.round_trip = None;
+                                self.recording.finish(false);
+                                self.status = status;
+                            }
                         }
-                        return clipboard::write(to_copy);
                     }
-                    UrlEncoderDecoderMessage::PasteText(id) => {
-                        return clipboard::read().map(move |optional_data| match optional_data {
-                            Some(data) => cosmic::Action::App(Message::UrlEncoderDecoderMessage(
-                                UrlEncoderDecoderMessage::ReplaceText(id.clone(), data),
-                            )),
-                            None => cosmic::Action::App(Message::UrlEncoderDecoderMessage(
-                                UrlEncoderDecoderMessage::NoOp,
-                            )),
-                        });
+                }
+                UrlEncoderDecoderMessage::Swap => {
+                    self.input_content =
+                        text_editor::Content::with_text(&self.output_content.text());
+                    self.selected_operation = 1 - self.selected_operation;
+                    self.recording.request();
+                    return self.convert_input();
+                }
+                UrlEncoderDecoderMessage::Clear => {
+                    self.conversion.cancel();
+                    self.recording.clear();
+                    self.input_content = text_editor::Content::default();
+                    self.round_trip = None;
+                    self.output_content = text_editor::Content::default();
+                    self.status = Status::Idle;
+                }
+                UrlEncoderDecoderMessage::CancelConversion => {
+                    self.conversion.cancel();
+                }
+                UrlEncoderDecoderMessage::CopyText(id) => {
+                    let mut to_copy: String = String::new();
+                    if id == Id::new(INPUT_EDITOR_ID) {
+                        to_copy = self.input_content.text();
+                    } else if id == Id::new(OUTPUT_EDITOR_ID) {
+                        to_copy = self.output_content.text();
                     }
-                    UrlEncoderDecoderMessage::ReplaceText(id, text) => {
-                        if id == Id::new(INPUT_EDITOR_ID) {
-                            self.input_content.perform(text_editor::Action::SelectAll);
-                            self.input_content.perform(text_editor::Action::Edit(
-                                text_editor::Edit::Paste(Arc::new(text)),
-                            ));
-                        } else if id == Id::new(OUTPUT_EDITOR_ID) {
-                            self.output_content.perform(text_editor::Action::SelectAll);
-                            self.output_content.perform(text_editor::Action::Edit(
-                                text_editor::Edit::Paste(Arc::new(text)),
-                            ));
+                    // Copying the output keeps its conversion in the history.
+                    let recorded = if id == Id::new(OUTPUT_EDITOR_ID) {
+                        history_page::record(Page::UrlEncoderDecoder, self.history_entry())
+                    } else {
+                        Task::none()
+                    };
+                    return Task::batch([clipboard::write(to_copy), recorded]);
+                }
+                UrlEncoderDecoderMessage::PasteText(id) => {
+                    return clipboard::read().map(move |optional_data| match optional_data {
+                        Some(data) => cosmic::Action::App(Message::from(
+                            UrlEncoderDecoderMessage::ReplaceText(id.clone(), data),
+                        )),
+                        None => cosmic::Action::App(Message::from(UrlEncoderDecoderMessage::NoOp)),
+                    });
+                }
+                UrlEncoderDecoderMessage::ReplaceText(id, text) => {
+                    if id == Id::new(INPUT_EDITOR_ID) {
+                        self.recording.clear();
+                        self.input_content.perform(text_editor::Action::SelectAll);
+                        self.input_content.perform(text_editor::Action::Edit(
+                            text_editor::Edit::Paste(Arc::new(text)),
+                        ));
+                        if self.live_convert {
+                            return self.convert_live();
                         }
+                    } else if id == Id::new(OUTPUT_EDITOR_ID) {
+                        self.output_content.perform(text_editor::Action::SelectAll);
+                        self.output_content.perform(text_editor::Action::Edit(
+                            text_editor::Edit::Paste(Arc::new(text)),
+                        ));
                     }
-                    UrlEncoderDecoderMessage::NoOp => {}
                 }
-            }
-            _ => {
-                println!("Wrong message type");
-            }
+                UrlEncoderDecoderMessage::OpenFile => {
+                    return files::open(|result| {
+                        Message::from(UrlEncoderDecoderMessage::FileOpened(result))
+                    });
+                }
+                UrlEncoderDecoderMessage::FileDropped(path) => {
+                    return files::read(path, |result| {
+                        Message::from(UrlEncoderDecoderMessage::FileOpened(result))
+                    });
+                }
+                UrlEncoderDecoderMessage::FileOpened(result) => {
+                    match result.and_then(|file| file.text()) {
+                        Ok(text) => {
+                            self.input_content = text_editor::Content::with_text(&text);
+                            return self.convert_live();
+                        }
+                        Err(err) => self.status = Status::error(fl!("open-failed"), err),
+                    }
+                }
+                UrlEncoderDecoderMessage::SaveFile => {
+                    return files::save(
+                        if self.selected_operation == 0 {
+                            "encoded.txt".to_string()
+                        } else {
+                            "decoded.txt".to_string()
+                        },
+                        self.output_content.text().into_bytes(),
+                        |result| Message::from(UrlEncoderDecoderMessage::FileSaved(result)),
+                    );
+                }
+                UrlEncoderDecoderMessage::FileSaved(result) => {
+                    self.status = Status::saved(result);
+                }
+                UrlEncoderDecoderMessage::NoOp => {}
+            },
+            None => unreachable!("a page only receives the messages its tool handles"),
         }
         Task::none()
     }
+
+    fn load_config(&mut self, config: &Config) -> Task<cosmic::Action<Message>> {
+        let config = &config.url_encoder_decoder;
+        self.live_convert = config.live_convert;
+        self.selected_operation = config.operation.min(OPERATIONS.len() - 1);
+        if !config.input.is_empty() {
+            self.input_content = text_editor::Content::with_text(&config.input);
+            return self.convert_live();
+        }
+        Task::none()
+    }
+
+    fn page_config(&self) -> PageConfig {
+        PageConfig::UrlEncoderDecoder(UrlEncoderDecoderConfig {
+            operation: self.selected_operation,
+            live_convert: self.live_convert,
+            input: self.input_content.text(),
+        })
+    }
+
+    fn paste_detected(
+        &mut self,
+        content: String,
+        _kind: ContentKind,
+    ) -> Task<cosmic::Action<Message>> {
+        self.selected_operation = 1;
+        self.input_content = text_editor::Content::with_text(&content);
+        self.convert_live()
+    }
+
+    fn edit_message(&self, action: EditAction) -> Option<Message> {
+        Some(Message::from(match action {
+            EditAction::Convert => UrlEncoderDecoderMessage::ConvertInput,
+            EditAction::Swap => UrlEncoderDecoderMessage::Swap,
+            EditAction::CopyOutput => UrlEncoderDecoderMessage::CopyText(Id::new(OUTPUT_EDITOR_ID)),
+            EditAction::PasteInput => UrlEncoderDecoderMessage::PasteText(Id::new(INPUT_EDITOR_ID)),
+            EditAction::Clear => UrlEncoderDecoderMessage::Clear,
+        }))
+    }
+
+    fn commands(&self) -> Vec<Command> {
+        OPERATIONS
+            .into_iter()
+            .enumerate()
+            .map(|(operation, id)| {
+                Command::new(
+                    LANGUAGE_LOADER.get(id),
+                    [
+                        UrlEncoderDecoderMessage::OperationChanged(operation),
+                        UrlEncoderDecoderMessage::ConvertInput,
+                    ]
+                    .map(Message::from),
+                )
+            })
+            .collect()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        self.conversion.subscription()
+    }
+
+    fn history_entry(&self) -> Option<(String, String)> {
+        self.recording
+            .is_converted()
+            .then(|| (self.input_content.text(), self.output_content.text()))
+    }
+
+    fn restore_input(&mut self, input: String) -> Task<cosmic::Action<Message>> {
+        self.input_content = text_editor::Content::with_text(&input);
+        self.convert_input()
+    }
+
+    fn prefill(&mut self, operation: &str, input: String) -> Task<cosmic::Action<Message>> {
+        if let Some(position) = OPERATIONS.iter().position(|other| *other == operation) {
+            self.selected_operation = position;
+        }
+        self.restore_input(input)
+    }
 }
 
 impl UrlEncoderDecoderPage {
-    fn convert_input(&mut self) {
+    /// Converts the input in the background, replacing a conversion that is still running.
+    fn convert_input(&mut self) -> Task<cosmic::Action<Message>> {
+        self.recording.clear();
         let input = self.input_content.text();
-        match self.selected_operation {
-            0 => {
-                self.output_content.perform(text_editor::Action::SelectAll);
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                self.output_content
-                    .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
-                        Arc::new(urlencoding::encode(input.as_str()).into_owned()),
-                    )));
-            }
-            _ => {
-                let decoded_url = urlencoding::decode(input.as_str());
-                match decoded_url {
-                    Ok(decoded_url) => {
-                        self.output_content.perform(text_editor::Action::SelectAll);
-                        self.output_content
-                            .perform(text_editor::Action::Edit(text_editor::Edit::Delete));
-                        self.output_content.perform(text_editor::Action::Edit(
-                            text_editor::Edit::Paste(Arc::new(decoded_url.into_owned())),
-                        ));
-                    }
-                    Err(err) => {
-                        println!("Error decoding URL: {:?}", err);
-                    }
+        let operation = self.selected_operation;
+        self.conversion.start(
+            move || match operation {
+                0 => {
+                    let output = encode(&input);
+                    let round_trip = is_round_trip(&output, input.as_bytes());
+                    Ok((output, round_trip))
                 }
-            }
+                _ => decode(&input)
+                    .map(|output| {
+                        let round_trip = is_round_trip(&input, output.as_bytes());
+                        (output, round_trip)
+                    })
+                    .map_err(|err| Status::from(&err)),
+            },
+            |id, result| Message::from(UrlEncoderDecoderMessage::Converted(id, result.flatten())),
+        )
+    }
+
+    /// Converts the input after a change, unless it is too large to convert on every change.
+    fn convert_live(&mut self) -> Task<cosmic::Action<Message>> {
+        if conversion::is_live(&self.input_content.text()) {
+            return self.convert_input();
         }
+        self.conversion.cancel();
+        self.recording.clear();
+        self.status = Status::Info(fl!("large-input"));
+        Task::none()
     }
 }